@@ -31,6 +31,7 @@
             voltui = pkgs.callPackage ./packages/voltui/default.nix { };
             kaltui = pkgs.callPackage ./packages/kaltui/default.nix { };
             caltui = pkgs.callPackage ./packages/caltui/default.nix { };
+            anytui = pkgs.callPackage ./packages/anytui/default.nix { };
             tuigreat = pkgs.callPackage ./packages/tuigreat/default.nix { };
           };
         in
@@ -0,0 +1,104 @@
+use blutui::BtTui;
+use caltui::CalTui;
+use kaltui::CalcTui;
+use mustui::MusicTui;
+use nettui::NetTui;
+use tuigreat::{AppResult, Hub, Screen, StatusMessage};
+use voltui::{PwTui, audio};
+
+/// Every tool, in the order of their tabs.
+const APPS: &[&str] = &["voltui", "nettui", "blutui", "mustui", "caltui", "kaltui"];
+
+/// Where mustui looks without `--music`.
+const MUSIC_DIR: &str = "~/Music";
+
+fn print_help() {
+    eprintln!(
+        "anytui - The anytui tools in one terminal
+
+USAGE:
+    anytui [APP]... [--music <DIR>] [--inline[=<LINES>]] [--debug]
+    anytui ctl [--app <APP>] <ACTION [COUNT]|:COMMAND|state> [--json]
+
+ARGS:
+    [APP]...       Tools to open, in tab order (default: {})
+
+OPTIONS:
+    --music <DIR>  Music directory for mustui (default: {MUSIC_DIR})
+    --inline       Draw below the prompt instead of the whole screen
+    --debug        Log commands and keys to ~/.local/state/anytui/anytui.log
+    -h, --help     Print this help message
+
+Alt-1 to Alt-9 switch between the tools. `ctl` controls the running hub,
+the selected tool or the one named with --app.",
+        APPS.join(" ")
+    );
+}
+
+/// Start `name` and add it to `hub`.
+fn open(hub: &mut Hub, name: &str, music: &str) -> AppResult<()> {
+    match name {
+        "voltui" => {
+            let backend = audio::detect_audio_backend()
+                .ok_or("no audio backend found (install pactl or amixer)")?;
+            hub.add(name, PwTui::new(backend)?);
+        }
+        "nettui" => hub.add(name, NetTui::new()?),
+        "blutui" => hub.add(name, BtTui::new()?),
+        "mustui" => hub.add(name, MusicTui::new(music)?),
+        "caltui" => hub.add(name, CalTui::new()),
+        "kaltui" => hub.add(name, CalcTui::new()),
+        _ => return Err(format!("unknown app `{name}`, see `anytui --help`").into()),
+    }
+    Ok(())
+}
+
+fn main() -> AppResult<()> {
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).is_some_and(|arg| arg == "ctl") {
+        tuigreat::ipc::ctl("anytui", &args[2..]);
+    }
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        print_help();
+        return Ok(());
+    }
+    let screen = Screen::from_args(&args)?;
+
+    let mut music = MUSIC_DIR.to_string();
+    let mut names = Vec::new();
+    let mut rest = args.iter().skip(1);
+    while let Some(arg) = rest.next() {
+        if arg == "--music" {
+            music = rest.next().ok_or("--music needs a directory")?.clone();
+        } else if let Some(dir) = arg.strip_prefix("--music=") {
+            music = dir.to_string();
+        } else if !arg.starts_with("--") {
+            names.push(arg.as_str());
+        }
+    }
+    if names.is_empty() {
+        names = APPS.to_vec();
+    }
+
+    // Tools missing their service are left out, and said so
+    let mut hub = Hub::new();
+    let mut failed = Vec::new();
+    for name in names {
+        if let Err(e) = open(&mut hub, name, &music) {
+            if !APPS.contains(&name) {
+                return Err(e);
+            }
+            failed.push(format!("{name} ({e})"));
+        }
+    }
+    if hub.is_empty() {
+        return Err(format!("no tool could start: {}", failed.join(", ")).into());
+    }
+    if !failed.is_empty() {
+        hub.push_message(StatusMessage::warning(format!(
+            "Not started: {}",
+            failed.join(", ")
+        )));
+    }
+    tuigreat::app::run_on(hub, screen)
+}
@@ -1,8 +1,12 @@
-use std::io::Write;
-use std::process::{Child, Command, Stdio};
+use std::io::{Read, Write};
+use std::process::{Child, ChildStdout, Command, Stdio};
 use std::sync::Mutex;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
 
-use tuigreat::AppResult;
+use std::time::Duration;
+
+use tuigreat::{AppResult, Error, runner};
 
 #[derive(Clone)]
 pub struct Device {
@@ -13,7 +17,9 @@ pub struct Device {
 }
 
 pub fn get_controller_info() -> AppResult<(bool, String)> {
-    let output = Command::new("bluetoothctl").args(["show"]).output()?;
+    let output = runner::Command::new("bluetoothctl")
+        .args(["show"])
+        .output()?;
 
     let out = String::from_utf8_lossy(&output.stdout);
 
@@ -31,17 +37,17 @@ pub fn get_controller_info() -> AppResult<(bool, String)> {
     Ok((powered, name))
 }
 
-pub fn set_power(on: bool) -> AppResult<()> {
+pub fn set_power(on: bool) -> Result<(), Error> {
     let state = if on { "on" } else { "off" };
-    Command::new("bluetoothctl")
+    runner::Command::new("bluetoothctl")
         .args(["power", state])
-        .output()?;
+        .run()?;
     Ok(())
 }
 
 pub fn get_paired_devices() -> AppResult<Vec<Device>> {
     // Use "devices Paired"
-    let output = Command::new("bluetoothctl")
+    let output = runner::Command::new("bluetoothctl")
         .args(["devices", "Paired"])
         .output()?;
 
@@ -72,7 +78,7 @@ pub fn get_paired_devices() -> AppResult<Vec<Device>> {
 
 pub fn get_available_devices() -> AppResult<Vec<Device>> {
     // First get paired device addresses to filter them out
-    let paired_output = Command::new("bluetoothctl")
+    let paired_output = runner::Command::new("bluetoothctl")
         .args(["devices", "Paired"])
         .output()?;
     let paired_out = String::from_utf8_lossy(&paired_output.stdout);
@@ -89,7 +95,9 @@ pub fn get_available_devices() -> AppResult<Vec<Device>> {
         .collect();
 
     // Get devices from bluetoothctl (this includes discovered devices)
-    let output = Command::new("bluetoothctl").args(["devices"]).output()?;
+    let output = runner::Command::new("bluetoothctl")
+        .args(["devices"])
+        .output()?;
     let out = String::from_utf8_lossy(&output.stdout);
 
     let mut seen_addrs = std::collections::HashSet::new();
@@ -130,7 +138,9 @@ pub fn get_available_devices() -> AppResult<Vec<Device>> {
 
 fn get_devices_from_dbus(paired_addrs: &std::collections::HashSet<String>) -> Vec<Device> {
     // Use busctl to list all device objects under org.bluez
-    let output = Command::new("busctl").args(["tree", "org.bluez"]).output();
+    let output = runner::Command::new("busctl")
+        .args(["tree", "org.bluez"])
+        .output();
 
     let Ok(out) = output else {
         return Vec::new();
@@ -183,7 +193,7 @@ fn get_devices_from_dbus(paired_addrs: &std::collections::HashSet<String>) -> Ve
 }
 
 fn get_device_name_dbus(device_path: &str) -> Option<String> {
-    let output = Command::new("busctl")
+    let output = runner::Command::new("busctl")
         .args([
             "get-property",
             "org.bluez",
@@ -203,7 +213,7 @@ fn get_device_name_dbus(device_path: &str) -> Option<String> {
 }
 
 fn get_device_info(address: &str) -> AppResult<(bool, bool, String)> {
-    let output = Command::new("bluetoothctl")
+    let output = runner::Command::new("bluetoothctl")
         .args(["info", address])
         .output()?;
 
@@ -252,7 +262,9 @@ pub fn scan(start: bool) {
         }
     } else {
         // Stop scanning and kill background process
-        let _ = Command::new("bluetoothctl").args(["scan", "off"]).output();
+        let _ = runner::Command::new("bluetoothctl")
+            .args(["scan", "off"])
+            .output();
 
         if let Ok(mut guard) = SCAN_PROCESS.lock()
             && let Some(mut child) = guard.take()
@@ -284,25 +296,29 @@ pub fn restart_discovery() {
     }
 }
 
-pub fn connect_device(address: &str) -> AppResult<()> {
-    Command::new("bluetoothctl")
+/// How long to wait for a device to answer a connection attempt.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(20);
+
+pub fn connect_device(address: &str) -> Result<(), Error> {
+    runner::Command::new("bluetoothctl")
         .args(["connect", address])
-        .output()?;
+        .timeout(CONNECT_TIMEOUT)
+        .run()?;
     Ok(())
 }
 
-pub fn disconnect_device(address: &str) -> AppResult<()> {
-    Command::new("bluetoothctl")
+pub fn disconnect_device(address: &str) -> Result<(), Error> {
+    runner::Command::new("bluetoothctl")
         .args(["disconnect", address])
-        .output()?;
+        .run()?;
     Ok(())
 }
 
 static PAIR_PROCESS: Mutex<Option<Child>> = Mutex::new(None);
 
-/// Start pairing process - returns immediately
-/// Check `get_pending_passkey()` to see if confirmation is needed
-pub fn start_pairing(address: &str) -> AppResult<()> {
+/// Start pairing process - returns immediately with its output
+/// Pass the output to `read_output()` and `parse_passkey()` to see if confirmation is needed
+pub fn start_pairing(address: &str) -> AppResult<Option<ChildStdout>> {
     // Kill any existing pairing process
     if let Ok(mut guard) = PAIR_PROCESS.lock()
         && let Some(mut child) = guard.take()
@@ -312,11 +328,12 @@ pub fn start_pairing(address: &str) -> AppResult<()> {
     }
 
     // Start interactive bluetoothctl for pairing with agent
-    let child = Command::new("bluetoothctl")
+    let mut child = Command::new("bluetoothctl")
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
         .spawn()?;
+    let stdout = child.stdout.take();
 
     if let Ok(mut guard) = PAIR_PROCESS.lock() {
         *guard = Some(child);
@@ -332,50 +349,43 @@ pub fn start_pairing(address: &str) -> AppResult<()> {
         let _ = writeln!(stdin, "pair {address}");
     }
 
-    Ok(())
+    Ok(stdout)
 }
 
-/// Check if there's a pending passkey to confirm
-/// Returns the passkey if found
-pub fn get_pending_passkey() -> Option<String> {
-    use std::io::Read;
-
-    if let Ok(mut guard) = PAIR_PROCESS.lock()
-        && let Some(ref mut child) = *guard
-        && let Some(ref mut stdout) = child.stdout
-    {
+/// Read pairing output on a thread as it arrives. Prompts such as
+/// `Confirm passkey 123456 (yes/no):` end without a newline, so the output
+/// is sent in chunks rather than lines.
+pub fn read_output(mut stdout: ChildStdout) -> Receiver<String> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
         let mut buffer = [0u8; 4096];
-        // Set non-blocking read
-        #[cfg(unix)]
+        while let Ok(n) = stdout.read(&mut buffer)
+            && n > 0
         {
-            use std::os::unix::io::AsRawFd;
-            let fd = stdout.as_raw_fd();
-            unsafe {
-                let flags = libc::fcntl(fd, libc::F_GETFL);
-                if flags != -1 {
-                    libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK);
-                }
+            let output = String::from_utf8_lossy(&buffer[..n]).into_owned();
+            if tx.send(output).is_err() {
+                return;
             }
         }
+    });
+    rx
+}
 
-        if let Ok(n) = stdout.read(&mut buffer)
-            && n > 0
+/// Find a passkey confirmation request in pairing output
+/// Returns the passkey if found
+pub fn parse_passkey(output: &str) -> Option<String> {
+    // Look for passkey confirmation request
+    for line in output.lines() {
+        if line.contains("Confirm passkey")
+            || line.contains("confirm passkey")
+            || line.contains("Passkey:")
         {
-            let output = String::from_utf8_lossy(&buffer[..n]);
-            // Look for passkey confirmation request
-            for line in output.lines() {
-                if line.contains("Confirm passkey")
-                    || line.contains("confirm passkey")
-                    || line.contains("Passkey:")
-                {
-                    // Extract 6-digit passkey
-                    if let Some(pin) = line
-                        .split_whitespace()
-                        .find(|s| s.chars().all(|c| c.is_ascii_digit()) && s.len() == 6)
-                    {
-                        return Some(pin.to_string());
-                    }
-                }
+            // Extract 6-digit passkey
+            if let Some(pin) = line
+                .split_whitespace()
+                .find(|s| s.chars().all(|c| c.is_ascii_digit()) && s.len() == 6)
+            {
+                return Some(pin.to_string());
             }
         }
     }
@@ -406,9 +416,9 @@ pub fn confirm_passkey(accept: bool) {
     }
 }
 
-pub fn remove_device(address: &str) -> AppResult<()> {
-    Command::new("bluetoothctl")
+pub fn remove_device(address: &str) -> Result<(), Error> {
+    runner::Command::new("bluetoothctl")
         .args(["remove", address])
-        .output()?;
+        .run()?;
     Ok(())
 }
@@ -0,0 +1,79 @@
+//! `blutui connect <device>`, `blutui list` and the other commands that
+//! run without the UI.
+
+use serde_json::{Value, json};
+use tuigreat::cli::{Call, Cli};
+use tuigreat::widgets::CommandSpec;
+use tuigreat::{AppResult, Error};
+
+use crate::bluetooth::{
+    Device, connect_device, disconnect_device, get_controller_info, get_paired_devices,
+    remove_device, set_power,
+};
+
+const COMMANDS: &[CommandSpec] = &[
+    CommandSpec::new("status", "", "Show the controller"),
+    CommandSpec::new("power", "<on|off>", "Turn Bluetooth on or off"),
+    CommandSpec::new("list", "", "List paired devices"),
+    CommandSpec::new("connect", "<address|name>", "Connect a paired device"),
+    CommandSpec::new("disconnect", "<address|name>", "Disconnect a device"),
+    CommandSpec::new("remove", "<address|name>", "Forget a paired device"),
+];
+
+pub const CLI: Cli = Cli::new("blutui", COMMANDS);
+
+/// Run a command.
+///
+/// # Errors
+/// Returns an error if the arguments are wrong or `bluetoothctl` fails.
+pub fn run(call: &Call) -> AppResult<Value> {
+    let value = match call.name {
+        "status" => {
+            let (powered, name) = get_controller_info()?;
+            json!({ "name": name, "powered": powered })
+        }
+        "power" => {
+            match call.arg(0)? {
+                "on" => set_power(true)?,
+                "off" => set_power(false)?,
+                _ => return Err(call.usage_error().into()),
+            }
+            Value::Null
+        }
+        "list" => get_paired_devices()?.iter().map(device_json).collect(),
+        "connect" => {
+            let device = find_device(&call.rest(0)?)?;
+            connect_device(&device.address)?;
+            Value::String(format!("Connected to {}", device.name))
+        }
+        "disconnect" => {
+            let device = find_device(&call.rest(0)?)?;
+            disconnect_device(&device.address)?;
+            Value::String(format!("Disconnected from {}", device.name))
+        }
+        "remove" => {
+            let device = find_device(&call.rest(0)?)?;
+            remove_device(&device.address)?;
+            Value::String(format!("Removed {}", device.name))
+        }
+        _ => return Err(call.usage_error().into()),
+    };
+    Ok(value)
+}
+
+/// The paired device with this address or name.
+fn find_device(device: &str) -> AppResult<Device> {
+    get_paired_devices()?
+        .into_iter()
+        .find(|d| d.address.eq_ignore_ascii_case(device) || d.name == device)
+        .ok_or_else(|| Error::backend(format!("No paired device {device}")).into())
+}
+
+fn device_json(device: &Device) -> Value {
+    json!({
+        "address": device.address,
+        "name": device.name,
+        "connected": device.connected,
+        "icon": device.icon,
+    })
+}
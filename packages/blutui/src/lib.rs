@@ -0,0 +1,7 @@
+//! Bluetooth manager: `bluetoothctl` wrappers, commands and the UI.
+
+pub mod bluetooth;
+pub mod cli;
+pub mod ui;
+
+pub use ui::BtTui;
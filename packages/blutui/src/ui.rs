@@ -0,0 +1,1130 @@
+//! The interactive UI, also hosted by the `anytui` hub.
+
+use std::sync::mpsc::Receiver;
+use std::time::{Duration, Instant};
+
+use crossterm::event::MouseEvent;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+use serde_json::{Value, json};
+use tuigreat::{
+    Action, App, AppResult, Confirmations, Error, Events, Interval, Keymap, MessageLog, Notifier,
+    StatusMessage, Tasks, Theme, repeat_action,
+    widgets::{
+        CommandEvent, CommandLine, CommandSpec, ConfirmEvent, ConfirmPopup, DetailPopup, HelpPopup,
+        Invocation, MatchMode, MessagePopup, SearchDirection, SearchPopup, SearchState,
+        SelectableList, Tabs, centered_rect,
+    },
+    yank,
+};
+
+use crate::bluetooth::{
+    Device, confirm_passkey, connect_device, disconnect_device, get_available_devices,
+    get_controller_info, get_paired_devices, parse_passkey, read_output, remove_device,
+    restart_discovery, scan, set_power, start_pairing,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+enum UiMode {
+    #[default]
+    Normal,
+    Help,
+    Search,
+    Command,
+    Jump {
+        forward: bool,
+    },
+    PinConfirm,
+}
+
+/// How often to refresh the paired list.
+const PAIRED_REFRESH: Duration = Duration::from_secs(2);
+/// How often to refresh found devices while scanning.
+const SCAN_REFRESH: Duration = Duration::from_secs(1);
+/// How often to restart discovery while scanning.
+const DISCOVERY_RESTART: Duration = Duration::from_secs(15);
+
+/// Commands for the `:` command line.
+const COMMANDS: &[CommandSpec] = &[
+    CommandSpec::new("connect", "<device>", "Connect a paired device"),
+    CommandSpec::new("disconnect", "<device>", "Disconnect a paired device"),
+    CommandSpec::new("pair", "<device>", "Pair with an available device"),
+    CommandSpec::new("remove", "<device>", "Remove a paired device"),
+    CommandSpec::new("power", "", "Toggle Bluetooth power"),
+    CommandSpec::new("scan", "", "Start or stop scanning"),
+];
+
+/// Results of background tasks.
+enum TaskResult {
+    Connected {
+        name: String,
+        result: Result<(), Error>,
+    },
+    Disconnected {
+        name: String,
+        result: Result<(), Error>,
+    },
+    /// A pairing PIN was confirmed for `device` or rejected.
+    PinAnswered { device: Option<String> },
+}
+
+/// Paired and nearby Bluetooth devices.
+pub struct BtTui {
+    theme: Theme,
+    keymap: Keymap,
+    tabs: Tabs,
+    paired: SelectableList<Device>,
+    available: SelectableList<Device>,
+    mode: UiMode,
+    command: CommandLine,
+    messages: MessageLog,
+    // Full output of the last error, opened with `!`
+    details: Option<DetailPopup>,
+    history: Option<MessagePopup>,
+    confirmations: Confirmations,
+    confirm: Option<ConfirmPopup>,
+    notifier: Notifier,
+    controller_powered: bool,
+    scanning: bool,
+    scan_timer: Interval,
+    discovery_timer: Interval,
+    paired_timer: Interval,
+    events: Events,
+    tasks: Tasks<TaskResult>,
+    // Pairing state
+    pairing_in_progress: bool,
+    pairing_output: Option<Receiver<String>>,
+    pairing_device: String,
+    // PIN value for confirmation
+    pin_value: String,
+    // With `--inline`, Enter prints the device address and quits
+    picker: bool,
+    picked: Option<String>,
+}
+
+impl BtTui {
+    /// Load the controller state and the known devices.
+    ///
+    /// # Errors
+    /// Returns an error if the controller cannot be queried.
+    pub fn new() -> AppResult<Self> {
+        let (powered, _) = get_controller_info()?;
+        let paired = get_paired_devices().unwrap_or_default();
+        let available = get_available_devices().unwrap_or_default();
+
+        // Auto-start scanning if bluetooth is powered on
+        let scanning = if powered {
+            scan(true);
+            true
+        } else {
+            false
+        };
+
+        let tabs = Tabs::new(vec!["Available".to_string(), "Paired".to_string()])
+            .with_app_title("Bluetooth Manager v0.1");
+
+        let keymap = Keymap::load("blutui");
+        let mut messages = MessageLog::new();
+        if scanning {
+            messages.hint(StatusMessage::info("Scanning..."));
+        } else {
+            messages.push(StatusMessage::info(if powered {
+                "Bluetooth ON"
+            } else {
+                "Bluetooth OFF"
+            }));
+        }
+        let confirmations = Confirmations::load("blutui", &["remove"]);
+        for w in keymap.warnings().iter().chain(confirmations.warnings()) {
+            messages.push(StatusMessage::warning(format!("Warning: {w}")));
+        }
+
+        let mut app = Self {
+            theme: Theme::default(),
+            keymap,
+            tabs,
+            paired: SelectableList::new(paired, |d| {
+                let connected = if d.connected { "*" } else { " " };
+                let icon = device_icon(&d.icon);
+                format!("{} {} {}", connected, icon, d.name)
+            })
+            .with_match_mode(MatchMode::Fuzzy)
+            .with_key(|d| d.address.clone()),
+            available: SelectableList::new(available, |d| {
+                let icon = device_icon(&d.icon);
+                format!("  {} {}", icon, d.name)
+            })
+            .with_match_mode(MatchMode::Fuzzy)
+            .with_key(|d| d.address.clone()),
+            mode: UiMode::default(),
+            command: CommandLine::new(COMMANDS.to_vec()),
+            messages,
+            details: None,
+            history: None,
+            confirmations,
+            confirm: None,
+            notifier: Notifier::new("blutui"),
+            controller_powered: powered,
+            scanning,
+            scan_timer: Interval::new(SCAN_REFRESH),
+            discovery_timer: Interval::new(DISCOVERY_RESTART),
+            paired_timer: Interval::new(PAIRED_REFRESH),
+            events: Events::default(),
+            tasks: Tasks::new(),
+            pairing_in_progress: false,
+            pairing_output: None,
+            pairing_device: String::new(),
+            pin_value: String::new(),
+            picker: false,
+            picked: None,
+        };
+        app.update_completions();
+        Ok(app)
+    }
+
+    /// With `picker`, Enter quits and [`App::output`] gives
+    /// the address of the selected device.
+    #[must_use]
+    pub fn with_picker(mut self, picker: bool) -> Self {
+        self.picker = picker;
+        self
+    }
+
+    fn current_tab(&self) -> usize {
+        self.tabs.selected()
+    }
+
+    fn refresh(&mut self) -> AppResult<()> {
+        let (powered, _) = get_controller_info()?;
+        self.controller_powered = powered;
+
+        let paired = get_paired_devices().unwrap_or_default();
+        let available = get_available_devices().unwrap_or_default();
+
+        self.paired.set_items(paired);
+        self.available.set_items(available);
+        self.update_completions();
+
+        self.messages.push(StatusMessage::info(if powered {
+            "Bluetooth ON"
+        } else {
+            "Bluetooth OFF"
+        }));
+        Ok(())
+    }
+
+    /// Tell the desktop about paired devices that connected or disconnected
+    /// since the last refresh, e.g. headphones running out of battery.
+    fn notify_connection_changes(&self, paired: &[Device]) {
+        for device in paired {
+            let was_connected = self
+                .paired
+                .items()
+                .iter()
+                .find(|d| d.address == device.address)
+                .map(|d| d.connected);
+            match (was_connected, device.connected) {
+                (Some(false), true) => {
+                    self.notifier
+                        .notify(format!("{} connected", device.name), "Bluetooth");
+                }
+                (Some(true), false) => {
+                    self.notifier
+                        .notify(format!("{} disconnected", device.name), "Bluetooth");
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Offer device names when completing commands.
+    fn update_completions(&mut self) {
+        let paired: Vec<String> = self.paired.items().iter().map(|d| d.name.clone()).collect();
+        for command in ["connect", "disconnect", "remove"] {
+            self.command.set_arguments(command, paired.clone());
+        }
+        let available = self.available.items().iter().map(|d| d.name.clone());
+        self.command.set_arguments("pair", available);
+    }
+
+    fn toggle_power(&mut self) -> AppResult<()> {
+        let turning_on = !self.controller_powered;
+        set_power(turning_on)?;
+        self.refresh()?;
+        // Start scanning when turning on
+        if turning_on && self.controller_powered {
+            self.start_scan();
+        }
+        Ok(())
+    }
+
+    fn start_scan(&mut self) {
+        let now = Instant::now();
+        self.scan_timer.reset(now);
+        self.discovery_timer.reset(now);
+        self.scanning = true;
+        self.messages.hint(StatusMessage::info("Scanning..."));
+        scan(true);
+    }
+
+    fn stop_scan(&mut self) -> AppResult<()> {
+        self.scanning = false;
+        scan(false);
+        self.refresh()
+    }
+
+    fn connect_selected(&mut self) -> AppResult<()> {
+        if !self.controller_powered {
+            self.messages.push(StatusMessage::info("Bluetooth is off"));
+            return Ok(());
+        }
+        match self.current_tab() {
+            0 => {
+                // Available tab - pair
+                if let Some(device) = self.available.selected() {
+                    self.pairing_device = device.name.clone();
+                    self.pairing_in_progress = true;
+                    self.messages.push(StatusMessage::info(format!(
+                        "Pairing with {}...",
+                        device.name
+                    )));
+                    let output = start_pairing(&device.address)?;
+                    self.pairing_output = output.map(|out| self.events.forward(read_output(out)));
+                }
+            }
+            1 => {
+                // Paired tab - connect/disconnect each marked device
+                let devices: Vec<Device> = self
+                    .paired
+                    .marked_or_selected()
+                    .into_iter()
+                    .cloned()
+                    .collect();
+                self.paired.clear_marks();
+                for device in devices {
+                    let addr = device.address;
+                    let name = device.name;
+                    if device.connected {
+                        self.tasks
+                            .spawn(format!(" Disconnecting from {name}..."), move |_| {
+                                let result = disconnect_device(&addr);
+                                TaskResult::Disconnected { name, result }
+                            });
+                    } else {
+                        self.tasks
+                            .spawn(format!(" Connecting to {name}..."), move |_| {
+                                let result = connect_device(&addr);
+                                TaskResult::Connected { name, result }
+                            });
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn finish_task(&mut self, result: TaskResult) -> AppResult<()> {
+        match result {
+            TaskResult::Connected { result: Err(e), .. }
+            | TaskResult::Disconnected { result: Err(e), .. } => self.messages.push(e),
+            TaskResult::Connected { name, .. } => {
+                self.refresh()?;
+                let connected = self
+                    .paired
+                    .items()
+                    .iter()
+                    .any(|d| d.name == name && d.connected);
+                self.messages.push(if connected {
+                    StatusMessage::success(format!("Connected to {name}"))
+                } else {
+                    StatusMessage::error(format!("Could not connect to {name}"))
+                });
+            }
+            TaskResult::Disconnected { name, .. } => {
+                self.refresh()?;
+                self.messages
+                    .push(StatusMessage::success(format!("Disconnected from {name}")));
+            }
+            TaskResult::PinAnswered { device: Some(name) } => {
+                self.refresh()?;
+                self.messages
+                    .push(StatusMessage::success(format!("Paired with {name}")));
+            }
+            TaskResult::PinAnswered { device: None } => {}
+        }
+        Ok(())
+    }
+
+    fn confirm_pin(&mut self) {
+        let device = std::mem::take(&mut self.pairing_device);
+        self.tasks.spawn(" Confirming PIN...", move |_| {
+            confirm_passkey(true);
+            TaskResult::PinAnswered {
+                device: Some(device),
+            }
+        });
+        self.end_pairing();
+    }
+
+    fn reject_pin(&mut self) {
+        self.tasks.spawn(" Cancelling pairing...", |_| {
+            confirm_passkey(false);
+            TaskResult::PinAnswered { device: None }
+        });
+        self.messages.push(StatusMessage::info("Pairing cancelled"));
+        self.end_pairing();
+    }
+
+    fn end_pairing(&mut self) {
+        self.mode = UiMode::Normal;
+        self.pin_value.clear();
+        self.pairing_in_progress = false;
+        self.pairing_output = None;
+        self.pairing_device.clear();
+    }
+
+    /// Check pairing output for a passkey to confirm.
+    fn pending_passkey(&self) -> Option<String> {
+        let output = self.pairing_output.as_ref()?;
+        let mut passkey = None;
+        while let Ok(chunk) = output.try_recv() {
+            passkey = passkey.or_else(|| parse_passkey(&chunk));
+        }
+        passkey
+    }
+
+    /// Remove the marked or selected paired devices, asking first.
+    fn remove_selected(&mut self) -> AppResult<()> {
+        if !self.controller_powered {
+            self.messages.push(StatusMessage::info("Bluetooth is off"));
+            return Ok(());
+        }
+        if self.current_tab() != 1 {
+            return Ok(());
+        }
+        let question = match self.paired.marked_or_selected().as_slice() {
+            [] => return Ok(()),
+            [device] => format!("Remove {}? It has to be paired again.", device.name),
+            devices => format!(
+                "Remove {} devices? They have to be paired again.",
+                devices.len()
+            ),
+        };
+        self.confirm = self.confirmations.ask("remove", question);
+        if self.confirm.is_none() {
+            self.remove_devices()?;
+        }
+        Ok(())
+    }
+
+    fn remove_devices(&mut self) -> AppResult<()> {
+        let devices: Vec<Device> = self
+            .paired
+            .marked_or_selected()
+            .into_iter()
+            .cloned()
+            .collect();
+        for device in &devices {
+            remove_device(&device.address)?;
+        }
+        self.messages
+            .push(StatusMessage::success(match devices.as_slice() {
+                [] => return Ok(()),
+                [device] => format!("Removed {}", device.name),
+                devices => format!("Removed {} devices", devices.len()),
+            }));
+        self.paired.clear_marks();
+        self.refresh()
+    }
+
+    /// Switch to `tab` and select the device called `name` there.
+    fn select_device(&mut self, tab: usize, name: &str) -> Option<&Device> {
+        let list = if tab == 0 {
+            &mut self.available
+        } else {
+            &mut self.paired
+        };
+        let index = list
+            .items()
+            .iter()
+            .position(|d| d.name == name)
+            .or_else(|| {
+                list.items()
+                    .iter()
+                    .position(|d| d.name.eq_ignore_ascii_case(name))
+            })?;
+        list.select(index);
+        self.tabs.select(tab);
+        self.focused_list().selected()
+    }
+
+    fn run_command(&mut self, invocation: &Invocation) -> AppResult<()> {
+        let device_tab = match invocation.name {
+            "power" => return self.toggle_power(),
+            "scan" if self.scanning => return self.stop_scan(),
+            "scan" => {
+                self.start_scan();
+                return Ok(());
+            }
+            "pair" => 0,
+            _ => 1,
+        };
+        let name = match invocation.arg() {
+            Ok(name) => name,
+            Err(e) => {
+                self.messages.push(StatusMessage::error(e));
+                return Ok(());
+            }
+        };
+        let Some(device) = self.select_device(device_tab, name) else {
+            self.messages
+                .push(StatusMessage::error(format!("No device named {name}")));
+            return Ok(());
+        };
+        match (invocation.name, device.connected) {
+            ("connect", true) => {
+                let message = format!("Already connected to {}", device.name);
+                self.messages.push(StatusMessage::info(message));
+            }
+            ("disconnect", false) => {
+                let message = format!("{} is not connected", device.name);
+                self.messages.push(StatusMessage::info(message));
+            }
+            ("remove", _) => self.remove_selected()?,
+            _ => self.connect_selected()?,
+        }
+        Ok(())
+    }
+
+    fn handle_command_action(&mut self, action: Action) -> AppResult<()> {
+        match self.command.handle_action(action) {
+            CommandEvent::Editing => {}
+            CommandEvent::Cancelled => self.mode = UiMode::Normal,
+            CommandEvent::Run(invocation) => {
+                self.mode = UiMode::Normal;
+                self.run_command(&invocation)?;
+            }
+            CommandEvent::Error(e) => {
+                self.mode = UiMode::Normal;
+                self.messages.push(StatusMessage::error(e));
+            }
+        }
+        Ok(())
+    }
+
+    fn focused_list(&mut self) -> &mut SelectableList<Device> {
+        if self.current_tab() == 0 {
+            &mut self.available
+        } else {
+            &mut self.paired
+        }
+    }
+
+    fn start_search(&mut self, direction: SearchDirection) {
+        self.focused_list().start_search(direction);
+        self.mode = UiMode::Search;
+    }
+
+    fn search_edit(&mut self, action: Action) {
+        self.focused_list().edit_search(action);
+    }
+
+    fn focused_search(&self) -> SearchState<'_> {
+        if self.current_tab() == 0 {
+            self.available.search_state()
+        } else {
+            self.paired.search_state()
+        }
+    }
+
+    fn focused_match_info(&self) -> Option<(usize, usize)> {
+        if self.current_tab() == 0 {
+            self.available.match_info()
+        } else {
+            self.paired.match_info()
+        }
+    }
+
+    fn clear_search(&mut self) {
+        self.focused_list().clear_search();
+        self.mode = UiMode::Normal;
+    }
+
+    fn next_match(&mut self) {
+        self.focused_list().next_match();
+    }
+
+    fn prev_match(&mut self) {
+        self.focused_list().prev_match();
+    }
+
+    fn half_page_down(&mut self) {
+        self.focused_list().half_page_down();
+    }
+
+    fn half_page_up(&mut self) {
+        self.focused_list().half_page_up();
+    }
+
+    fn full_page_down(&mut self) {
+        self.focused_list().page_down();
+    }
+
+    fn full_page_up(&mut self) {
+        self.focused_list().page_up();
+    }
+
+    fn search_popup_title(&self) -> &'static str {
+        if self.current_tab() == 0 {
+            " Search Available "
+        } else {
+            " Search Paired "
+        }
+    }
+
+    fn render_pin_popup(&self, frame: &mut Frame) {
+        let area = centered_rect(40, 7, frame.area());
+        frame.render_widget(Clear, area);
+
+        let block = Block::default()
+            .title(format!(" Pair with {} ", self.pairing_device))
+            .title_style(self.theme.title())
+            .borders(Borders::ALL)
+            .border_type(Theme::BORDER_TYPE)
+            .border_style(self.theme.border_focused());
+
+        let content = vec![
+            Line::from(""),
+            Line::from(vec![
+                Span::raw("  PIN: "),
+                Span::styled(&self.pin_value, self.theme.highlight()),
+            ]),
+            Line::from(""),
+            Line::from(Span::styled(
+                "  [Enter] Accept  [Esc] Reject",
+                self.theme.muted(),
+            )),
+        ];
+
+        let popup = Paragraph::new(content).block(block);
+        frame.render_widget(popup, area);
+    }
+
+    fn yank_selected(&mut self) {
+        let text = if self.current_tab() == 0 {
+            self.available.selected().map(|d| d.name.clone())
+        } else {
+            self.paired.selected().map(|d| d.name.clone())
+        };
+
+        if let Some(text) = text {
+            match yank(&text) {
+                Ok(backend) => self.messages.push(StatusMessage::success(format!(
+                    "Yanked with {backend}: {text}"
+                ))),
+                Err(e) => self.messages.push(e),
+            }
+        } else {
+            self.messages.push(StatusMessage::info("Nothing to yank"));
+        }
+    }
+}
+
+fn device_icon(icon: &str) -> &str {
+    match icon {
+        "audio-headphones" | "audio-headset" => "[H]",
+        "audio-speakers" => "[S]",
+        "input-keyboard" => "[K]",
+        "input-mouse" => "[M]",
+        "input-gaming" => "[G]",
+        "phone" => "[P]",
+        "computer" => "[C]",
+        _ => "[?]",
+    }
+}
+
+impl App for BtTui {
+    fn title(&self) -> &'static str {
+        "bt-tui"
+    }
+
+    fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    fn input_mode(&self) -> bool {
+        matches!(
+            self.mode,
+            UiMode::Search | UiMode::Command | UiMode::Jump { .. }
+        )
+    }
+
+    fn handle_action_count(&mut self, action: Action, count: usize) -> AppResult<bool> {
+        match action {
+            // `5G` / `5gg` go to line 5
+            Action::Top | Action::Bottom
+                if self.mode == UiMode::Normal
+                    && self.details.is_none()
+                    && self.history.is_none()
+                    && self.confirm.is_none() =>
+            {
+                match self.current_tab() {
+                    0 => self.available.goto_line(count),
+                    1 => self.paired.goto_line(count),
+                    _ => {}
+                }
+                Ok(true)
+            }
+            _ => repeat_action(self, action, count),
+        }
+    }
+
+    fn mouse_capture(&self) -> bool {
+        true
+    }
+
+    fn handle_mouse(&mut self, event: MouseEvent) -> AppResult<bool> {
+        if self.mode == UiMode::Normal
+            && self.details.is_none()
+            && self.history.is_none()
+            && self.confirm.is_none()
+            && !self.tabs.handle_mouse(&event)
+        {
+            self.focused_list().handle_mouse(&event);
+        }
+        Ok(true)
+    }
+
+    fn start(&mut self, events: &Events) {
+        self.events = events.clone();
+        self.tasks.connect(events);
+    }
+
+    fn report_error(&mut self, error: Box<dyn std::error::Error>) -> AppResult<()> {
+        self.messages.report(error)
+    }
+
+    fn output(&self) -> Option<String> {
+        self.picked.clone()
+    }
+
+    fn execute(&mut self, line: &str) -> AppResult<()> {
+        match self.command.parse(line) {
+            CommandEvent::Run(invocation) => self.run_command(&invocation),
+            CommandEvent::Error(e) => Err(e.into()),
+            CommandEvent::Editing | CommandEvent::Cancelled => Ok(()),
+        }
+    }
+
+    fn save_session(&self) -> Option<Value> {
+        Some(json!({
+            "tab": self.current_tab(),
+            "paired": self.paired.save_session(),
+        }))
+    }
+
+    fn restore_session(&mut self, saved: &Value) {
+        if let Some(tab) = saved["tab"].as_u64().and_then(|t| usize::try_from(t).ok()) {
+            self.tabs.select(tab);
+        }
+        self.paired.restore_session(&saved["paired"]);
+    }
+
+    fn summary(&self) -> Option<String> {
+        if !self.controller_powered {
+            return Some("off".to_string());
+        }
+        let connected: Vec<&str> = self
+            .paired
+            .items()
+            .iter()
+            .filter(|d| d.connected)
+            .map(|d| d.name.as_str())
+            .collect();
+        Some(if connected.is_empty() {
+            "on".to_string()
+        } else {
+            connected.join(", ")
+        })
+    }
+
+    fn tick(&mut self) -> AppResult<()> {
+        for result in self.tasks.finished() {
+            self.finish_task(result)?;
+        }
+
+        // Check for a passkey in the pairing output
+        if self.pairing_in_progress
+            && self.mode != UiMode::PinConfirm
+            && let Some(passkey) = self.pending_passkey()
+        {
+            self.pin_value = passkey;
+            self.mode = UiMode::PinConfirm;
+            self.messages.push(StatusMessage::info(format!(
+                "Confirm PIN for {}",
+                self.pairing_device
+            )));
+        }
+
+        // Skip blocking refreshes during popups/input to keep UI responsive
+        if self.mode != UiMode::Normal {
+            return Ok(());
+        }
+        let now = Instant::now();
+
+        // Periodic refresh of paired list
+        if self.controller_powered && self.paired_timer.due(now) {
+            let paired = get_paired_devices().unwrap_or_default();
+            self.notify_connection_changes(&paired);
+            self.paired.set_items(paired);
+        }
+
+        // Scanning logic
+        if self.scanning {
+            if self.scan_timer.due(now) {
+                let available = get_available_devices().unwrap_or_default();
+                let count = available.len();
+                self.available.set_items(available);
+                if !self.pairing_in_progress {
+                    self.messages
+                        .hint(StatusMessage::info(format!("Scanning... ({count} found)")));
+                }
+            }
+
+            // Restart discovery periodically to keep it active
+            // (BlueZ stops discovery after timeout)
+            if self.discovery_timer.due(now) {
+                restart_discovery();
+            }
+        }
+        Ok(())
+    }
+
+    fn next_tick(&self) -> Option<Instant> {
+        if self.mode != UiMode::Normal {
+            return [self.tasks.next_tick(), self.messages.next_expiry()]
+                .into_iter()
+                .flatten()
+                .min();
+        }
+        let paired = self
+            .controller_powered
+            .then(|| self.paired_timer.deadline());
+        let scan = self.scanning.then(|| {
+            self.scan_timer
+                .deadline()
+                .min(self.discovery_timer.deadline())
+        });
+        [
+            paired,
+            scan,
+            self.tasks.next_tick(),
+            self.messages.next_expiry(),
+        ]
+        .into_iter()
+        .flatten()
+        .min()
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn handle_action(&mut self, action: Action) -> AppResult<bool> {
+        // Jump mode - waiting for character
+        if let UiMode::Jump { forward } = self.mode {
+            match action {
+                Action::Char(c) => {
+                    let found = if self.current_tab() == 0 {
+                        self.available.jump_to_char(c, forward)
+                    } else {
+                        self.paired.jump_to_char(c, forward)
+                    };
+                    self.messages.hint(StatusMessage::info(if found {
+                        format!("Jumped to '{c}'")
+                    } else {
+                        format!("No match for '{c}'")
+                    }));
+                }
+                _ => {
+                    self.messages.hint(StatusMessage::info("Jump cancelled"));
+                }
+            }
+            self.mode = UiMode::Normal;
+            return Ok(true);
+        }
+
+        // Search mode
+        if self.mode == UiMode::Search {
+            match action {
+                Action::Back => {
+                    if self.focused_search().query.is_empty() {
+                        self.clear_search();
+                    } else {
+                        self.search_edit(action);
+                    }
+                }
+                Action::Quit => self.clear_search(),
+                Action::Select => {
+                    self.mode = UiMode::Normal;
+                    if let Some((cur, total)) = self.focused_match_info() {
+                        self.messages
+                            .hint(StatusMessage::info(format!("Match {cur}/{total}")));
+                    }
+                }
+                action => self.search_edit(action),
+            }
+            return Ok(true);
+        }
+
+        // PIN confirmation mode
+        if self.mode == UiMode::PinConfirm {
+            match action {
+                Action::Select => self.confirm_pin(),
+                Action::Quit | Action::Back => self.reject_pin(),
+                _ => {}
+            }
+            return Ok(true);
+        }
+
+        if self.mode == UiMode::Command {
+            self.handle_command_action(action)?;
+            return Ok(true);
+        }
+
+        if self.mode == UiMode::Help {
+            if matches!(action, Action::Help | Action::Back | Action::Quit) {
+                self.mode = UiMode::Normal;
+            }
+            return Ok(true);
+        }
+
+        if let Some(details) = &mut self.details {
+            if !details.handle_action(action) {
+                self.details = None;
+            }
+            return Ok(true);
+        }
+
+        if let Some(confirm) = &mut self.confirm {
+            match confirm.handle_action(action) {
+                ConfirmEvent::Pending => {}
+                ConfirmEvent::Cancelled => self.confirm = None,
+                ConfirmEvent::Confirmed => {
+                    self.confirmations.confirmed(confirm);
+                    self.confirm = None;
+                    self.remove_devices()?;
+                }
+            }
+            return Ok(true);
+        }
+
+        if let Some(history) = &mut self.history {
+            if !history.handle_action(action) {
+                self.history = None;
+            }
+            return Ok(true);
+        }
+
+        match action {
+            // Esc first shows the whole list again
+            Action::Back if self.focused_search().is_filtered() => self.clear_search(),
+            Action::Back => self.focused_list().unmark(),
+            Action::Quit => {
+                if self.scanning {
+                    self.stop_scan()?;
+                }
+                return Ok(false);
+            }
+            Action::Help => self.mode = UiMode::Help,
+            Action::Details => {
+                self.details = self
+                    .messages
+                    .last_detail()
+                    .and_then(DetailPopup::for_status)
+            }
+            Action::Messages => self.history = Some(MessagePopup::new(&self.messages)),
+            Action::Refresh => {
+                if self.scanning {
+                    self.stop_scan()?;
+                } else {
+                    self.start_scan();
+                }
+            }
+            Action::Down => match self.current_tab() {
+                0 => self.available.next(),
+                1 => self.paired.next(),
+                _ => {}
+            },
+            Action::Up => match self.current_tab() {
+                0 => self.available.previous(),
+                1 => self.paired.previous(),
+                _ => {}
+            },
+            Action::Left => self.tabs.previous(),
+            Action::Right => self.tabs.next(),
+            Action::Top => match self.current_tab() {
+                0 => self.available.first(),
+                1 => self.paired.first(),
+                _ => {}
+            },
+            Action::Bottom => match self.current_tab() {
+                0 => self.available.last(),
+                1 => self.paired.last(),
+                _ => {}
+            },
+            Action::Select if self.picker => {
+                let devices = match self.current_tab() {
+                    0 => &self.available,
+                    _ => &self.paired,
+                };
+                self.picked = devices.selected().map(|d| d.address.clone());
+                return Ok(false);
+            }
+            Action::Select => self.connect_selected()?,
+            Action::Mute => self.toggle_power()?,
+            Action::Delete if self.current_tab() == 1 => self.remove_selected()?,
+            // Page navigation
+            Action::PageUp => self.half_page_up(),
+            Action::PageDown => self.half_page_down(),
+            Action::FullPageUp => self.full_page_up(),
+            Action::FullPageDown => self.full_page_down(),
+            // Search
+            Action::Search => self.start_search(SearchDirection::Forward),
+            Action::Command => {
+                self.command.open();
+                self.mode = UiMode::Command;
+            }
+            Action::SearchNext => self.next_match(),
+            Action::SearchPrev => self.prev_match(),
+            // Yank
+            Action::Yank => self.yank_selected(),
+            // Marks, for connecting or removing several paired devices
+            Action::Mark if self.current_tab() == 1 => self.paired.toggle_mark(),
+            Action::Visual if self.current_tab() == 1 => self.paired.toggle_visual(),
+            // Jump to char (vim-style f/F)
+            Action::JumpTo => {
+                self.mode = UiMode::Jump { forward: true };
+                self.messages.hint(StatusMessage::info("Jump to: "));
+            }
+            Action::JumpBack => {
+                self.mode = UiMode::Jump { forward: false };
+                self.messages.hint(StatusMessage::info("Jump back to: "));
+            }
+            _ => {}
+        }
+        Ok(true)
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let main_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1), // Tabs
+                Constraint::Min(5),    // Content
+                Constraint::Length(3), // Status box
+            ])
+            .split(frame.area());
+
+        // Tabs
+        self.tabs.render(frame, main_chunks[0], &self.theme);
+
+        // Content based on tab
+        match self.current_tab() {
+            0 => {
+                self.available
+                    .render(frame, main_chunks[1], "", &self.theme, true);
+            }
+            1 => {
+                self.paired
+                    .render(frame, main_chunks[1], "", &self.theme, true);
+            }
+            _ => {}
+        }
+
+        // Status box
+        let power_indicator = if self.controller_powered {
+            Span::styled(" [ON]", self.theme.success())
+        } else {
+            Span::styled(" [OFF]", self.theme.error())
+        };
+
+        let scan_indicator = if self.scanning {
+            Span::styled("[SCAN]", self.theme.highlight())
+        } else {
+            Span::raw("")
+        };
+
+        let status_block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(Theme::BORDER_TYPE)
+            .border_style(self.theme.border());
+        let command_area = status_block.inner(main_chunks[2]);
+        let mut status = self.tasks.indicator().map_or_else(
+            || self.messages.to_line(&self.theme),
+            |progress| StatusMessage::info(progress.trim_start()).to_line(&self.theme),
+        );
+        status.spans.splice(0..0, [power_indicator, scan_indicator]);
+        let status = Paragraph::new(status).block(status_block);
+        frame.render_widget(status, main_chunks[2]);
+        if self.mode == UiMode::Command {
+            self.command.render(frame, command_area, &self.theme);
+        }
+
+        if self.mode == UiMode::Help {
+            let bindings = self.keymap.help(&[
+                (&[Action::Down, Action::Up], "Navigate up/down"),
+                (&[Action::Left, Action::Right], "Switch panel"),
+                (&[Action::Top, Action::Bottom], "Top/Bottom"),
+                (&[Action::PageUp, Action::PageDown], "Half page"),
+                (&[Action::FullPageUp, Action::FullPageDown], "Full page"),
+                (&[Action::Search], "Search"),
+                (&[Action::SearchNext, Action::SearchPrev], "Next/Prev match"),
+                (&[Action::Yank], "Yank (copy)"),
+                (&[Action::JumpTo, Action::JumpBack], "Jump to char"),
+                (&[Action::Select], "Connect/Pair"),
+                (&[Action::Delete], "Remove paired"),
+                (&[Action::Mark, Action::Visual], "Mark paired item/range"),
+                (&[Action::Mute], "Toggle power"),
+                (&[Action::Refresh], "Start/Stop scan"),
+                (&[Action::Command], "Command line"),
+                (&[Action::Details], "Error details"),
+                (&[Action::Messages], "Message history"),
+                (&[Action::Log], "Debug log"),
+                (&[Action::Quit], "Quit"),
+            ]);
+            HelpPopup::render(frame, &bindings, &self.theme);
+        }
+
+        if let Some(details) = &self.details {
+            details.render(frame, &self.theme);
+        }
+
+        if let Some(history) = &self.history {
+            history.render(frame, &self.theme);
+        }
+
+        if let Some(confirm) = &self.confirm {
+            confirm.render(frame, &self.theme);
+        }
+
+        if self.mode == UiMode::Search {
+            SearchPopup::render(
+                frame,
+                self.search_popup_title(),
+                self.focused_search(),
+                &self.theme,
+            );
+        }
+
+        if self.mode == UiMode::PinConfirm {
+            self.render_pin_popup(frame);
+        }
+    }
+}
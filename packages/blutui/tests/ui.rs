@@ -0,0 +1,53 @@
+use std::sync::Arc;
+
+use blutui::BtTui;
+use tuigreat::App;
+use tuigreat::runner::{self, FakeRunner};
+use tuigreat::testing::TestHarness;
+
+/// The app on a recorded `bluetoothctl` session, with the controller off
+/// so that no scan is started.
+fn app() -> (TestHarness<BtTui>, Arc<FakeRunner>) {
+    let fake = Arc::new(
+        FakeRunner::load(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/bluetoothctl.txt"
+        ))
+        .unwrap(),
+    );
+    runner::set(fake.clone());
+    let app = BtTui::new().unwrap();
+    (TestHarness::new(app, 80, 12), fake)
+}
+
+#[test]
+fn test_devices_from_bluetoothctl() {
+    let (mut harness, _) = app();
+    let screen = harness.screen();
+    // Paired devices are only on their own tab
+    assert!(screen.contains("[?] JBL Flip 5"));
+    assert!(!screen.contains("WH-1000XM4"));
+    assert!(screen.contains("Bluetooth OFF"));
+    assert_eq!(harness.app().summary(), Some("off".to_string()));
+
+    harness.press("l");
+    let screen = harness.screen();
+    assert!(screen.contains(">  * [H] WH-1000XM4"));
+    assert!(screen.contains("     [M] MX Master 3"));
+}
+
+#[test]
+fn test_connect_needs_power() {
+    let (mut harness, fake) = app();
+    harness.press("l").press("j").press("Enter");
+    assert!(harness.screen().contains("Bluetooth is off"));
+    assert!(!fake.ran(&["bluetoothctl", "connect"]));
+}
+
+#[test]
+fn test_snapshots() {
+    let (mut harness, _) = app();
+    harness.assert_snapshot("available");
+    harness.press("l");
+    harness.assert_snapshot("paired");
+}
@@ -0,0 +1,37 @@
+//! `caltui day 2025-12-24`, `caltui week` and the other commands that run
+//! without the UI.
+
+use chrono::{Datelike, Local, NaiveDate};
+use serde_json::{Value, json};
+use tuigreat::AppResult;
+use tuigreat::cli::{Call, Cli};
+use tuigreat::widgets::CommandSpec;
+
+const COMMANDS: &[CommandSpec] = &[
+    CommandSpec::new("day", "[yyyy-mm-dd]", "Show today or a date"),
+    CommandSpec::new("week", "[yyyy-mm-dd]", "Print the ISO week number"),
+];
+
+pub const CLI: Cli = Cli::new("caltui", COMMANDS);
+
+/// Run a command.
+///
+/// # Errors
+/// Returns an error if the date is invalid.
+pub fn run(call: &Call) -> AppResult<Value> {
+    let date = match call.args.first() {
+        Some(arg) => NaiveDate::parse_from_str(arg, "%Y-%m-%d").map_err(|_| call.usage_error())?,
+        None => Local::now().date_naive(),
+    };
+    let value = match call.name {
+        "day" => json!({
+            "date": date.format("%Y-%m-%d").to_string(),
+            "weekday": date.format("%A").to_string(),
+            "week": date.iso_week().week(),
+            "day_of_year": date.ordinal(),
+        }),
+        "week" => Value::from(date.iso_week().week()),
+        _ => return Err(call.usage_error().into()),
+    };
+    Ok(value)
+}
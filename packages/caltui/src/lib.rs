@@ -0,0 +1,6 @@
+//! Calendar viewer: date commands and the UI.
+
+pub mod cli;
+pub mod ui;
+
+pub use ui::CalTui;
@@ -0,0 +1,40 @@
+//! `kaltui eval '2^10'`, which runs without the UI.
+
+use serde_json::Value;
+use tuigreat::AppResult;
+use tuigreat::cli::{Call, Cli};
+use tuigreat::widgets::CommandSpec;
+
+use crate::parse_and_eval;
+
+const COMMANDS: &[CommandSpec] = &[CommandSpec::new("eval", "<expression>", "Print the result")];
+
+pub const CLI: Cli = Cli::new("kaltui", COMMANDS);
+
+/// Run a command.
+///
+/// # Errors
+/// Returns an error if the expression is invalid.
+pub fn run(call: &Call) -> AppResult<Value> {
+    match call.name {
+        "eval" => {
+            let value = parse_and_eval(&call.rest(0)?)?;
+            if !value.is_finite() {
+                return Err("Invalid result".into());
+            }
+            Ok(number(value))
+        }
+        _ => Err(call.usage_error().into()),
+    }
+}
+
+/// `n` as a JSON number, without a fraction when it is whole, and without
+/// the thousands separators of [`format_number`](crate::format_number).
+fn number(n: f64) -> Value {
+    if n.fract().abs() < f64::EPSILON
+        && let Ok(int_val) = format!("{n:.0}").parse::<i64>()
+    {
+        return Value::from(int_val);
+    }
+    Value::from(n)
+}
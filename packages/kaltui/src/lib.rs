@@ -1,3 +1,8 @@
+pub mod cli;
+pub mod ui;
+
+pub use ui::CalcTui;
+
 /// Validate that parentheses are balanced.
 #[must_use]
 pub fn validate_parens(expr: &str) -> bool {
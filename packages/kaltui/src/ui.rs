@@ -0,0 +1,588 @@
+//! The interactive UI, also hosted by the `anytui` hub.
+
+use std::time::Instant;
+
+use crate::{format_number, parse_and_eval};
+use crossterm::event::MouseEvent;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+};
+use serde_json::{Value, json};
+use tuigreat::{
+    Action, App, AppResult, Confirmations, Edit, Keymap, MessageLog, StatusMessage, Theme, paste,
+    widgets::{ConfirmEvent, ConfirmPopup, HelpPopup, MessagePopup, Tabs, TextInput},
+    yank,
+};
+
+const MAX_HISTORY: usize = 1000;
+
+/// A calculator with a history of results.
+pub struct CalcTui {
+    theme: Theme,
+    keymap: Keymap,
+    tabs: Tabs,
+    input: TextInput,
+    result: String,
+    history: Vec<(String, String)>,
+    history_state: ListState,
+    show_help: bool,
+    messages: MessageLog,
+    message_history: Option<MessagePopup>,
+    confirmations: Confirmations,
+    confirm: Option<ConfirmPopup>,
+    focus: usize, // 0 = input, 1 = result (in calculator tab)
+    // With `--inline`, Enter prints the result and quits
+    picker: bool,
+    picked: Option<String>,
+}
+
+impl Default for CalcTui {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CalcTui {
+    /// An empty calculator with the user's key bindings.
+    pub fn new() -> Self {
+        let keymap = Keymap::load("kaltui");
+        let mut messages = MessageLog::new();
+        // Clearing is cheap to undo by retyping, so only ask when configured
+        let confirmations = Confirmations::load("kaltui", &[]);
+        for w in keymap.warnings().iter().chain(confirmations.warnings()) {
+            messages.push(StatusMessage::warning(format!("Warning: {w}")));
+        }
+
+        Self {
+            theme: Theme::default(),
+            keymap,
+            tabs: Tabs::new(vec!["Calculator".to_string(), "History".to_string()])
+                .with_app_title("Calculator v0.1"),
+            input: TextInput::new(),
+            result: String::new(),
+            history: Vec::new(),
+            history_state: ListState::default(),
+            show_help: false,
+            messages,
+            message_history: None,
+            confirmations,
+            confirm: None,
+            focus: 0,
+            picker: false,
+            picked: None,
+        }
+    }
+
+    /// With `picker`, Enter quits and [`App::output`] gives
+    /// the result.
+    #[must_use]
+    pub fn with_picker(mut self, picker: bool) -> Self {
+        self.picker = picker;
+        self
+    }
+
+    fn current_tab(&self) -> usize {
+        self.tabs.selected()
+    }
+
+    fn evaluate(&mut self) {
+        if self.input.is_empty() {
+            self.messages
+                .push(StatusMessage::info("Nothing to evaluate"));
+            return;
+        }
+
+        match parse_and_eval(self.input.text()) {
+            Ok(value) => {
+                if value.is_finite() {
+                    self.result = format_number(value);
+                    self.history
+                        .push((self.input.text().to_string(), self.result.clone()));
+                    if self.history.len() > MAX_HISTORY {
+                        self.history.drain(0..(self.history.len() - MAX_HISTORY));
+                    }
+                    self.input.clear();
+                    self.messages.push(StatusMessage::success("Calculated"));
+                } else {
+                    self.result = "Error".to_string();
+                    self.messages.push(StatusMessage::error("Invalid result"));
+                }
+            }
+            Err(e) => {
+                self.result.clear();
+                self.messages.push(StatusMessage::error(e.to_string()));
+            }
+        }
+    }
+
+    /// Whether keys go to the calculator input
+    fn editing(&self) -> bool {
+        self.current_tab() == 0 && self.focus == 0
+    }
+
+    fn clear(&mut self) {
+        self.input.clear();
+        self.result.clear();
+        self.messages.push(StatusMessage::info("Cleared"));
+    }
+
+    fn handle_char(&mut self, c: char) {
+        // Only accept input when on calculator tab and focused on input
+        if !self.editing() {
+            return;
+        }
+        match c {
+            '0'..='9' | '.' | '+' | '-' | '*' | '/' | '^' | '(' | ')' | '\'' => {
+                self.input.insert(c);
+                self.messages.clear();
+            }
+            // Space (only if not at start and previous char isn't space)
+            ' ' => {
+                let before = self.input.before_cursor();
+                if !before.is_empty() && !before.ends_with(' ') {
+                    self.input.insert(' ');
+                }
+            }
+            // Alternative operators
+            ':' => {
+                self.input.insert('/');
+                self.messages.clear();
+            }
+            'x' | 'X' => {
+                self.input.insert('*');
+                self.messages.clear();
+            }
+            // Calculate
+            '=' => self.evaluate(),
+            _ => {
+                // Show error for invalid characters
+                self.messages
+                    .push(StatusMessage::error(format!("Invalid input: '{c}'")));
+            }
+        }
+    }
+
+    fn do_paste(&mut self) {
+        if !self.editing() {
+            return;
+        }
+
+        let Some(text) = paste() else {
+            self.messages.push(StatusMessage::info("Nothing to paste"));
+            return;
+        };
+
+        // Filter to only valid calculator characters
+        let filtered: String = text
+            .chars()
+            .filter(|c| {
+                matches!(
+                    c,
+                    '0'..='9'
+                        | '.'
+                        | '+'
+                        | '-'
+                        | '*'
+                        | '/'
+                        | '^'
+                        | '('
+                        | ')'
+                        | ' '
+                        | 'x'
+                        | 'X'
+                        | ':'
+                )
+            })
+            .map(|c| match c {
+                'x' | 'X' => '*',
+                ':' => '/',
+                _ => c,
+            })
+            .collect();
+
+        self.input.insert_str(&filtered);
+        self.messages
+            .push(StatusMessage::success(format!("Pasted: {filtered}")));
+    }
+
+    fn render_calculator_tab(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let calc_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Input
+                Constraint::Min(3),    // Result
+            ])
+            .split(area);
+
+        // Input field
+        let input_border = if self.focus == 0 {
+            self.theme.border_focused()
+        } else {
+            self.theme.border()
+        };
+        let input = Paragraph::new(Line::from(
+            self.input.spans(self.theme.normal(), self.focus == 0),
+        ))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(Theme::BORDER_TYPE)
+                .border_style(input_border),
+        );
+        frame.render_widget(input, calc_chunks[0]);
+
+        // Result field
+        let result_border = if self.focus == 1 {
+            self.theme.border_focused()
+        } else {
+            self.theme.border()
+        };
+        let result_style = if self.result.is_empty() {
+            self.theme.muted()
+        } else {
+            self.theme.highlight()
+        };
+        let result = Paragraph::new(Span::styled(&self.result, result_style)).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(Theme::BORDER_TYPE)
+                .border_style(result_border),
+        );
+        frame.render_widget(result, calc_chunks[1]);
+    }
+
+    fn render_history_tab(&mut self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let history_items: Vec<ListItem> = self
+            .history
+            .iter()
+            .rev()
+            .map(|(expr, res)| {
+                ListItem::new(Line::from(vec![
+                    Span::styled(expr, self.theme.muted()),
+                    Span::raw(" = "),
+                    Span::styled(res, self.theme.normal()),
+                ]))
+            })
+            .collect();
+
+        let history = List::new(history_items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(Theme::BORDER_TYPE)
+                    .border_style(self.theme.border_focused()),
+            )
+            .highlight_style(self.theme.selected())
+            .highlight_symbol(" > ");
+
+        frame.render_stateful_widget(history, area, &mut self.history_state);
+    }
+
+    fn do_yank(&mut self) {
+        let text = match self.current_tab() {
+            0 => {
+                // Yank based on focus: input if focus=0, result if focus=1
+                if self.focus == 0 {
+                    self.input.text().to_string()
+                } else {
+                    self.result.clone()
+                }
+            }
+            1 => self
+                .history_state
+                .selected()
+                .and_then(|i| {
+                    if i < self.history.len() {
+                        self.history.get(self.history.len() - 1 - i)
+                    } else {
+                        None
+                    }
+                })
+                .map(|(_, r)| r.clone())
+                .unwrap_or_default(),
+            _ => String::new(),
+        };
+
+        if text.is_empty() {
+            self.messages.push(StatusMessage::info("Nothing to yank"));
+            return;
+        }
+
+        match yank(&text) {
+            Ok(backend) => self.messages.push(StatusMessage::success(format!(
+                "Yanked with {backend}: {text}"
+            ))),
+            Err(e) => self.messages.push(e),
+        }
+    }
+
+    fn history_next(&mut self) {
+        if self.history.is_empty() {
+            return;
+        }
+        let i = match self.history_state.selected() {
+            Some(i) => {
+                if i >= self.history.len() - 1 {
+                    0
+                } else {
+                    i + 1
+                }
+            }
+            None => 0,
+        };
+        self.history_state.select(Some(i));
+    }
+
+    fn history_prev(&mut self) {
+        if self.history.is_empty() {
+            return;
+        }
+        let i = match self.history_state.selected() {
+            Some(i) => {
+                if i == 0 {
+                    self.history.len() - 1
+                } else {
+                    i - 1
+                }
+            }
+            None => self.history.len() - 1,
+        };
+        self.history_state.select(Some(i));
+    }
+}
+
+impl App for CalcTui {
+    fn title(&self) -> &'static str {
+        "calc-tui"
+    }
+
+    fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    fn output(&self) -> Option<String> {
+        self.picked.clone()
+    }
+
+    fn save_session(&self) -> Option<Value> {
+        Some(json!({"history": self.history}))
+    }
+
+    fn restore_session(&mut self, saved: &Value) {
+        let Some(entries) = saved["history"].as_array() else {
+            return;
+        };
+        let history = entries.iter().filter_map(|entry| {
+            let expression = entry[0].as_str()?;
+            let result = entry[1].as_str()?;
+            Some((expression.to_string(), result.to_string()))
+        });
+        self.history = history.collect();
+        if self.history.len() > MAX_HISTORY {
+            self.history.drain(0..(self.history.len() - MAX_HISTORY));
+        }
+    }
+
+    fn summary(&self) -> Option<String> {
+        let (_, result) = self.history.last()?;
+        Some(format!("= {result}"))
+    }
+
+    // Digits are calculator input, not counts
+    fn accepts_count(&self) -> bool {
+        false
+    }
+
+    fn mouse_capture(&self) -> bool {
+        true
+    }
+
+    fn handle_mouse(&mut self, event: MouseEvent) -> AppResult<bool> {
+        if !self.show_help && self.message_history.is_none() && self.confirm.is_none() {
+            self.tabs.handle_mouse(&event);
+        }
+        Ok(true)
+    }
+
+    fn next_tick(&self) -> Option<Instant> {
+        self.messages.next_expiry()
+    }
+
+    fn handle_action(&mut self, action: Action) -> AppResult<bool> {
+        if self.show_help {
+            if matches!(action, Action::Help | Action::Back | Action::Quit) {
+                self.show_help = false;
+            }
+            return Ok(true);
+        }
+
+        if let Some(confirm) = &mut self.confirm {
+            match confirm.handle_action(action) {
+                ConfirmEvent::Pending => {}
+                ConfirmEvent::Cancelled => self.confirm = None,
+                ConfirmEvent::Confirmed => {
+                    self.confirmations.confirmed(confirm);
+                    self.confirm = None;
+                    self.clear();
+                }
+            }
+            return Ok(true);
+        }
+
+        if let Some(history) = &mut self.message_history {
+            if !history.handle_action(action) {
+                self.message_history = None;
+            }
+            return Ok(true);
+        }
+
+        match action {
+            Action::Quit => return Ok(false),
+            Action::Help => self.show_help = true,
+            Action::Messages => self.message_history = Some(MessagePopup::new(&self.messages)),
+            // Move the cursor within the input, switching tab at either end
+            Action::Left if self.editing() && self.input.cursor() > 0 => {
+                self.input.edit(Edit::Left);
+            }
+            Action::Right if self.editing() && !self.input.after_cursor().is_empty() => {
+                self.input.edit(Edit::Right);
+            }
+            Action::Left => self.tabs.previous(),
+            Action::Right => self.tabs.next(),
+            Action::Select if self.picker && self.current_tab() == 0 => {
+                if !self.input.is_empty() {
+                    self.evaluate();
+                }
+                // The input is cleared once it has a result
+                if self.input.is_empty() && !self.result.is_empty() {
+                    self.picked = Some(self.result.clone());
+                    return Ok(false);
+                }
+            }
+            Action::Select if self.current_tab() == 0 => self.evaluate(),
+            Action::Back if self.current_tab() == 0 && !self.input.is_empty() => {
+                self.input.backspace();
+                self.messages.clear();
+            }
+            Action::Up => match self.current_tab() {
+                0 if self.focus > 0 => self.focus -= 1,
+                1 => self.history_prev(),
+                _ => {}
+            },
+            Action::Down => match self.current_tab() {
+                0 if self.focus < 1 => self.focus += 1,
+                1 => self.history_next(),
+                _ => {}
+            },
+            Action::Top => {
+                if self.current_tab() == 0 && self.focus == 0 {
+                    // In calculator input, '0' should be a digit (tuigreat maps 0 to Top)
+                    self.input.insert('0');
+                    self.messages.clear();
+                } else if self.current_tab() == 1 && !self.history.is_empty() {
+                    self.history_state.select(Some(0));
+                }
+            }
+            Action::Bottom if self.editing() => self.input.edit(Edit::End),
+            Action::Bottom if self.current_tab() == 1 && !self.history.is_empty() => {
+                self.history_state.select(Some(self.history.len() - 1));
+            }
+            Action::Yank => self.do_yank(),
+            Action::Char(c) => self.handle_char(c),
+            // These are captured by keybindings, remap to operators
+            Action::VolumeUp if self.current_tab() == 0 && self.focus == 0 => {
+                self.input.insert('+');
+                self.messages.clear();
+            }
+            Action::VolumeDown if self.current_tab() == 0 && self.focus == 0 => {
+                self.input.insert('-');
+                self.messages.clear();
+            }
+            Action::Search if self.current_tab() == 0 && self.focus == 0 => {
+                self.input.insert('/');
+                self.messages.clear();
+            }
+            // `:` is division here, not the command line
+            Action::Command => self.handle_char(':'),
+            Action::Delete => {
+                self.confirm = self
+                    .confirmations
+                    .ask("clear", "Clear the expression and result?");
+                if self.confirm.is_none() {
+                    self.clear();
+                }
+            }
+            Action::Paste => self.do_paste(),
+            _ => {}
+        }
+        Ok(true)
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1), // Tabs
+                Constraint::Min(5),    // Content
+                Constraint::Length(3), // Status
+            ])
+            .split(frame.area());
+
+        // Tabs
+        self.tabs.render(frame, chunks[0], &self.theme);
+
+        // Content based on tab
+        match self.current_tab() {
+            0 => self.render_calculator_tab(frame, chunks[1]),
+            1 => self.render_history_tab(frame, chunks[1]),
+            _ => {}
+        }
+
+        // Status box
+        let status_block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(Theme::BORDER_TYPE)
+            .border_style(self.theme.border());
+        let status = Paragraph::new(self.messages.to_line(&self.theme)).block(status_block);
+        frame.render_widget(status, chunks[2]);
+
+        if self.show_help {
+            let mut bindings = self.keymap.help(&[
+                (&[Action::Left, Action::Right], "Move cursor, switch tab"),
+                (&[Action::Bottom], "End of input"),
+                (&[Action::Paste], "Paste"),
+                (&[Action::Yank], "Yank (copy)"),
+                (&[Action::Char('='), Action::Select], "Calculate"),
+                (&[Action::Delete], "Clear all"),
+                (&[Action::Messages], "Message history"),
+                (&[Action::Log], "Debug log"),
+                (&[Action::Quit], "Quit"),
+            ]);
+            // Calculator input keys are typed literally, not bound
+            bindings.splice(
+                1..1,
+                [
+                    ("0-9".to_string(), "Digits"),
+                    ("+-*/ x :".to_string(), "Operators"),
+                    ("^ ()".to_string(), "Power, parens"),
+                ],
+            );
+            HelpPopup::render(frame, &bindings, &self.theme);
+        }
+
+        if let Some(history) = &self.message_history {
+            history.render(frame, &self.theme);
+        }
+
+        if let Some(confirm) = &self.confirm {
+            confirm.render(frame, &self.theme);
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use kaltui::{format_number, format_with_thousands, parse_and_eval, validate_parens};
+use kaltui::{CalcTui, cli, format_number, format_with_thousands, parse_and_eval, validate_parens};
+use serde_json::json;
+use tuigreat::App;
+use tuigreat::cli::format_value;
 
 #[test]
 fn test_format_with_thousands() {
@@ -81,3 +84,33 @@ fn test_errors() {
     assert!(parse_and_eval("1/0").is_err());
     assert!(parse_and_eval("abc").is_err());
 }
+
+#[test]
+fn test_eval_command() {
+    let args: Vec<String> = ["kaltui", "eval", "2^10", "+", "0.5"]
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    let call = cli::CLI.parse(&args).unwrap().unwrap();
+    assert_eq!(call.args, ["2^10", "+", "0.5"]);
+    assert_eq!(cli::run(&call).unwrap(), serde_json::json!(1024.5));
+
+    let args: Vec<String> = ["kaltui", "eval", "3000*2"]
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    let call = cli::CLI.parse(&args).unwrap().unwrap();
+    assert_eq!(format_value(&cli::run(&call).unwrap(), false), "6000");
+}
+
+#[test]
+fn test_session_keeps_history() {
+    let mut calc = CalcTui::new();
+    assert_eq!(calc.save_session(), Some(json!({"history": []})));
+    calc.restore_session(&json!({"history": [["1+1", "2"], "bogus", ["2+3", "5"]]}));
+    assert_eq!(calc.summary().as_deref(), Some("= 5"));
+    assert_eq!(
+        calc.save_session(),
+        Some(json!({"history": [["1+1", "2"], ["2+3", "5"]]}))
+    );
+}
@@ -0,0 +1,69 @@
+//! `mustui songs ~/Music` and the other commands that list a library
+//! without the UI.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use serde_json::{Value, json};
+use tuigreat::AppResult;
+use tuigreat::cli::{Call, Cli};
+use tuigreat::widgets::CommandSpec;
+
+use crate::library::{expand_tilde, scan_albums_in_artist, scan_music_dir, scan_songs_recursive};
+
+const COMMANDS: &[CommandSpec] = &[
+    CommandSpec::new("artists", "<dir>", "List the artists of a library"),
+    CommandSpec::new("albums", "<dir>", "List the albums of a library"),
+    CommandSpec::new("songs", "<dir>", "List every song under a directory"),
+];
+
+pub const CLI: Cli = Cli::new("mustui", COMMANDS);
+
+/// Whether `args` (program name first) name a music directory to open
+/// rather than a command: a single argument that is an existing directory,
+/// so `mustui songs` opens `./songs` while `mustui songs ~/Music` still
+/// lists the songs of `~/Music`.
+#[must_use]
+pub fn opens_directory(args: &[String]) -> bool {
+    let words: Vec<&String> = args
+        .iter()
+        .skip(1)
+        .filter(|arg| !arg.starts_with("--"))
+        .collect();
+    matches!(words.as_slice(), [dir] if expand_tilde(dir).is_dir())
+}
+
+/// Run a command.
+///
+/// # Errors
+/// Returns an error if the directory is missing.
+pub fn run(call: &Call) -> AppResult<Value> {
+    let dir = expand_tilde(&call.rest(0)?);
+    if !dir.is_dir() {
+        return Err(format!("Not a directory: {}", dir.display()).into());
+    }
+    let (artists, albums, _) = scan_music_dir(&dir);
+    let value = match call.name {
+        "artists" => artists.iter().map(|a| entry(&a.name, &a.path)).collect(),
+        "albums" => {
+            let nested = artists.iter().flat_map(|a| scan_albums_in_artist(&a.path));
+            albums
+                .into_iter()
+                .chain(nested)
+                .map(|a| entry(&a.name, &a.path))
+                .collect()
+        }
+        "songs" => {
+            let mut songs = Vec::new();
+            scan_songs_recursive(&dir, &mut songs, &mut HashSet::new());
+            songs.sort_by(|a, b| a.path.cmp(&b.path));
+            songs.iter().map(|s| entry(&s.name, &s.path)).collect()
+        }
+        _ => return Err(call.usage_error().into()),
+    };
+    Ok(value)
+}
+
+fn entry(name: &str, path: &Path) -> Value {
+    json!({ "name": name, "path": path.display().to_string() })
+}
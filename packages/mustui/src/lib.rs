@@ -0,0 +1,9 @@
+//! Music player: library scanning, playback options, MPRIS and the UI.
+
+pub mod cli;
+pub mod library;
+pub mod mpris;
+pub mod playback;
+pub mod ui;
+
+pub use ui::MusicTui;
@@ -3,6 +3,7 @@ use std::sync::mpsc;
 use std::thread;
 use tokio::sync::watch;
 use tokio::task::LocalSet;
+use tuigreat::Events;
 
 #[derive(Debug, Clone)]
 pub enum MprisCommand {
@@ -41,6 +42,13 @@ pub struct MprisHandle {
 }
 
 impl MprisHandle {
+    /// Wake the app loop whenever a command arrives.
+    pub fn wake_on_command(&mut self, events: &Events) {
+        let (_, placeholder) = mpsc::channel();
+        let cmd_rx = std::mem::replace(&mut self.cmd_rx, placeholder);
+        self.cmd_rx = events.forward(cmd_rx);
+    }
+
     pub fn update_state(&self, state: MprisState) {
         let _ = self.state_tx.send(state);
     }
@@ -19,6 +19,23 @@ impl ShuffleLevel {
             Self::All => "*",
         }
     }
+
+    /// Name in the saved session.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Album => "album",
+            Self::Artist => "artist",
+            Self::All => "all",
+        }
+    }
+
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        [Self::Album, Self::Artist, Self::All]
+            .into_iter()
+            .find(|level| level.name() == name)
+    }
 }
 
 /// Playback options and state.
@@ -0,0 +1,28 @@
+use std::fs;
+
+use mustui::cli;
+
+fn args(words: &[&str]) -> Vec<String> {
+    words.iter().map(ToString::to_string).collect()
+}
+
+#[test]
+fn test_directory_named_like_a_command() {
+    let root = std::env::temp_dir().join(format!("mustui-cli-{}", std::process::id()));
+    fs::create_dir_all(root.join("songs")).unwrap();
+    let songs = root.join("songs").display().to_string();
+    let albums = root.join("albums").display().to_string();
+    let dotted = root.join(".").join("songs").display().to_string();
+
+    // A lone `songs` that exists is the library to open
+    assert!(cli::opens_directory(&args(&["mustui", &songs])));
+    assert!(cli::opens_directory(&args(&["mustui", &songs, "--debug"])));
+    assert!(cli::opens_directory(&args(&["mustui", &dotted])));
+    // With a directory after it, it is the command
+    assert!(!cli::opens_directory(&args(&["mustui", &songs, "."])));
+    // Without such a directory it is the command too
+    assert!(!cli::opens_directory(&args(&["mustui", &albums])));
+    assert!(!cli::opens_directory(&args(&["mustui"])));
+
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -1,10 +1,11 @@
-use std::process::Command;
-use tuigreat::AppResult;
+use std::time::Duration;
+use tuigreat::Error;
+use tuigreat::runner::Command;
 
 use crate::network::WifiNetwork;
 
-/// Ticks to wait after triggering scan before fetching results (~500ms).
-pub const SCAN_DELAY_TICKS: u32 = 5;
+/// Time to wait after triggering scan before fetching results.
+pub const SCAN_DELAY: Duration = Duration::from_millis(500);
 
 /// Trigger a Wi-Fi scan (non-blocking, returns immediately).
 pub fn trigger_scan(interface: &str) {
@@ -14,20 +15,18 @@ pub fn trigger_scan(interface: &str) {
 }
 
 /// Fetch scan results (non-blocking, reads cached results from iwd).
-pub fn get_networks(interface: &str) -> AppResult<Vec<WifiNetwork>> {
-    let output = Command::new("iwctl")
+pub fn get_networks(interface: &str) -> Result<Vec<WifiNetwork>, Error> {
+    let text = match Command::new("iwctl")
         .args(["station", interface, "get-networks"])
-        .output()?;
-
-    if output.status.success() {
-        let connected = get_connected_network(interface);
-        Ok(parse_iwctl_networks(
-            &String::from_utf8_lossy(&output.stdout),
-            connected.as_deref(),
-        ))
-    } else {
-        Ok(vec![])
-    }
+        .run()
+    {
+        Ok(text) => text,
+        // No results yet, e.g. while scanning
+        Err(Error::Backend { .. }) => return Ok(vec![]),
+        Err(e) => return Err(e),
+    };
+    let connected = get_connected_network(interface);
+    Ok(parse_iwctl_networks(&text, connected.as_deref()))
 }
 
 /// Check if iwd has stored credentials for a network.
@@ -39,35 +38,30 @@ pub fn has_stored_credentials(ssid: &str) -> bool {
         return false;
     };
 
-    let stdout = nettui::strip_ansi(&String::from_utf8_lossy(&output.stdout));
-    nettui::is_ssid_in_known_networks(&stdout, ssid)
+    let stdout = crate::strip_ansi(&String::from_utf8_lossy(&output.stdout));
+    crate::is_ssid_in_known_networks(&stdout, ssid)
 }
 
-pub fn connect_wifi(interface: &str, ssid: &str, password: Option<&str>) -> AppResult<String> {
-    let output = if let Some(psk) = password {
+pub fn connect_wifi(interface: &str, ssid: &str, password: Option<&str>) -> Result<String, Error> {
+    let mut command = Command::new("iwctl");
+    if let Some(psk) = password {
         if psk.is_empty() {
             return Ok(format!("'{ssid}' requires a password"));
         }
-        Command::new("iwctl")
-            .args(["--passphrase", psk, "station", interface, "connect", ssid])
-            .output()?
-    } else {
-        Command::new("iwctl")
-            .args(["station", interface, "connect", ssid])
-            .output()?
-    };
-
-    if output.status.success() {
-        Ok(format!("Connecting to {ssid}"))
-    } else {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        if stderr.contains("passphrase") || stderr.contains("Passphrase") {
+        command.args(["--passphrase", psk]).redact(psk);
+    }
+    match command.args(["station", interface, "connect", ssid]).run() {
+        Ok(_) => Ok(format!("Connecting to {ssid}")),
+        Err(Error::Backend { stderr, .. })
+            if stderr.contains("passphrase") || stderr.contains("Passphrase") =>
+        {
             Ok(format!("'{ssid}' requires a password"))
-        } else if stderr.is_empty() {
+        }
+        // iwctl does not always say why, the connection may still come up
+        Err(Error::Backend { stderr, .. }) if stderr.is_empty() => {
             Ok(format!("Connecting to {ssid}"))
-        } else {
-            Ok(format!("Failed: {}", stderr.trim()))
         }
+        Err(e) => Err(e),
     }
 }
 
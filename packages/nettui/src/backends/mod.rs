@@ -2,7 +2,9 @@ pub mod iwd;
 pub mod nmcli;
 pub mod wpa;
 
-use tuigreat::AppResult;
+use std::time::Duration;
+
+use tuigreat::Error;
 
 use crate::network::{WifiBackend, WifiNetwork};
 
@@ -16,7 +18,7 @@ pub fn trigger_scan(backend: WifiBackend, interface: &str) {
 }
 
 /// Fetch scan results (non-blocking, reads cached results).
-pub fn get_networks(backend: WifiBackend, interface: &str) -> AppResult<Vec<WifiNetwork>> {
+pub fn get_networks(backend: WifiBackend, interface: &str) -> Result<Vec<WifiNetwork>, Error> {
     match backend {
         WifiBackend::Iwd => iwd::get_networks(interface),
         WifiBackend::Wpa => wpa::get_networks(interface),
@@ -24,12 +26,12 @@ pub fn get_networks(backend: WifiBackend, interface: &str) -> AppResult<Vec<Wifi
     }
 }
 
-/// Ticks to wait between scan trigger and result fetch.
-pub fn scan_delay_ticks(backend: WifiBackend) -> u32 {
+/// Time to wait between scan trigger and result fetch.
+pub fn scan_delay(backend: WifiBackend) -> Duration {
     match backend {
-        WifiBackend::Iwd => iwd::SCAN_DELAY_TICKS,
-        WifiBackend::Wpa => wpa::SCAN_DELAY_TICKS,
-        WifiBackend::Nmcli => nmcli::SCAN_DELAY_TICKS,
+        WifiBackend::Iwd => iwd::SCAN_DELAY,
+        WifiBackend::Wpa => wpa::SCAN_DELAY,
+        WifiBackend::Nmcli => nmcli::SCAN_DELAY,
     }
 }
 
@@ -48,7 +50,7 @@ pub fn connect_wifi(
     interface: &str,
     ssid: &str,
     password: Option<&str>,
-) -> AppResult<String> {
+) -> Result<String, Error> {
     match backend {
         WifiBackend::Iwd => iwd::connect_wifi(interface, ssid, password),
         WifiBackend::Wpa => wpa::connect_wifi(interface, ssid, password),
@@ -1,10 +1,11 @@
-use std::process::Command;
-use tuigreat::AppResult;
+use std::time::Duration;
+use tuigreat::Error;
+use tuigreat::runner::Command;
 
 use crate::network::WifiNetwork;
 
-/// Ticks to wait after triggering scan before fetching results (~1000ms).
-pub const SCAN_DELAY_TICKS: u32 = 10;
+/// Time to wait after triggering scan before fetching results.
+pub const SCAN_DELAY: Duration = Duration::from_millis(1000);
 
 /// Trigger a Wi-Fi scan (non-blocking, returns immediately).
 pub fn trigger_scan(interface: &str) {
@@ -14,8 +15,8 @@ pub fn trigger_scan(interface: &str) {
 }
 
 /// Fetch scan results (non-blocking, reads cached results from `NetworkManager`).
-pub fn get_networks(interface: &str) -> AppResult<Vec<WifiNetwork>> {
-    let output = Command::new("nmcli")
+pub fn get_networks(interface: &str) -> Result<Vec<WifiNetwork>, Error> {
+    let result = Command::new("nmcli")
         .args([
             "-t",
             "-f",
@@ -26,12 +27,12 @@ pub fn get_networks(interface: &str) -> AppResult<Vec<WifiNetwork>> {
             "ifname",
             interface,
         ])
-        .output()?;
+        .run();
 
-    if output.status.success() {
-        Ok(parse_nmcli_output(&String::from_utf8_lossy(&output.stdout)))
-    } else {
-        Ok(vec![])
+    match result {
+        Ok(text) => Ok(parse_nmcli_output(&text)),
+        Err(Error::Backend { .. }) => Ok(vec![]),
+        Err(e) => Err(e),
     }
 }
 
@@ -48,28 +49,27 @@ pub fn has_stored_credentials(ssid: &str) -> bool {
         })
 }
 
-pub fn connect_wifi(interface: &str, ssid: &str, password: Option<&str>) -> AppResult<String> {
-    let mut args = vec!["dev", "wifi", "connect", ssid, "ifname", interface];
+pub fn connect_wifi(interface: &str, ssid: &str, password: Option<&str>) -> Result<String, Error> {
+    let mut command = Command::new("nmcli");
+    command.args(["dev", "wifi", "connect", ssid, "ifname", interface]);
     if let Some(psk) = password {
         if psk.is_empty() {
             return Ok(format!("'{ssid}' requires a password"));
         }
-        args.extend(["password", psk]);
+        command.args(["password", psk]).redact(psk);
     }
 
-    let output = Command::new("nmcli").args(&args).output()?;
-
-    if output.status.success() {
-        Ok(format!("Connecting to {ssid}"))
-    } else {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        if stderr.contains("Secrets were required") || stderr.contains("No suitable") {
+    match command.run() {
+        Ok(_) => Ok(format!("Connecting to {ssid}")),
+        Err(Error::Backend { stderr, .. })
+            if stderr.contains("Secrets were required") || stderr.contains("No suitable") =>
+        {
             Ok(format!("'{ssid}' requires a password"))
-        } else if stderr.is_empty() {
+        }
+        Err(Error::Backend { stderr, .. }) if stderr.is_empty() => {
             Ok(format!("Connecting to {ssid}"))
-        } else {
-            Ok(format!("Failed: {}", stderr.trim()))
         }
+        Err(e) => Err(e),
     }
 }
 
@@ -1,10 +1,11 @@
-use std::process::Command;
-use tuigreat::AppResult;
+use std::time::Duration;
+use tuigreat::Error;
+use tuigreat::runner::Command;
 
 use crate::network::WifiNetwork;
 
-/// Ticks to wait after triggering scan before fetching results (~2000ms).
-pub const SCAN_DELAY_TICKS: u32 = 20;
+/// Time to wait after triggering scan before fetching results.
+pub const SCAN_DELAY: Duration = Duration::from_millis(2000);
 
 /// Trigger a Wi-Fi scan (non-blocking, returns immediately).
 pub fn trigger_scan(interface: &str) {
@@ -14,20 +15,17 @@ pub fn trigger_scan(interface: &str) {
 }
 
 /// Fetch scan results (non-blocking, reads cached results from `wpa_supplicant`).
-pub fn get_networks(interface: &str) -> AppResult<Vec<WifiNetwork>> {
-    let output = Command::new("wpa_cli")
+pub fn get_networks(interface: &str) -> Result<Vec<WifiNetwork>, Error> {
+    let text = match Command::new("wpa_cli")
         .args(["-i", interface, "scan_results"])
-        .output()?;
-
-    if output.status.success() {
-        let connected_ssid = get_connected_network(interface);
-        Ok(parse_wpa_scan_results(
-            &String::from_utf8_lossy(&output.stdout),
-            connected_ssid.as_deref(),
-        ))
-    } else {
-        Ok(vec![])
-    }
+        .run()
+    {
+        Ok(text) => text,
+        Err(Error::Backend { .. }) => return Ok(vec![]),
+        Err(e) => return Err(e),
+    };
+    let connected_ssid = get_connected_network(interface);
+    Ok(parse_wpa_scan_results(&text, connected_ssid.as_deref()))
 }
 
 /// Check if `wpa_supplicant` has a configured network for this SSID.
@@ -35,13 +33,13 @@ pub fn has_stored_credentials(interface: &str, ssid: &str) -> bool {
     find_configured_network(interface, ssid).is_some()
 }
 
-pub fn connect_wifi(interface: &str, ssid: &str, password: Option<&str>) -> AppResult<String> {
+pub fn connect_wifi(interface: &str, ssid: &str, password: Option<&str>) -> Result<String, Error> {
     // Without a password, try stored credentials first, then open network.
     let Some(psk) = password else {
         if let Some(net_id) = find_configured_network(interface, ssid) {
-            let _ = Command::new("wpa_cli")
+            Command::new("wpa_cli")
                 .args(["-i", interface, "select_network", &net_id])
-                .output()?;
+                .run()?;
             return Ok(format!("Connecting to {ssid}"));
         }
         // No configured network — connect as open (key_mgmt=NONE)
@@ -55,10 +53,10 @@ pub fn connect_wifi(interface: &str, ssid: &str, password: Option<&str>) -> AppR
     // Add network with password
     let output = Command::new("wpa_cli")
         .args(["-i", interface, "add_network"])
-        .output()?;
-    let net_id = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        .run()?;
+    let net_id = output.trim().to_string();
 
-    let _ = Command::new("wpa_cli")
+    Command::new("wpa_cli")
         .args([
             "-i",
             interface,
@@ -67,9 +65,9 @@ pub fn connect_wifi(interface: &str, ssid: &str, password: Option<&str>) -> AppR
             "ssid",
             &format!("\"{ssid}\""),
         ])
-        .output()?;
+        .run()?;
 
-    let _ = Command::new("wpa_cli")
+    Command::new("wpa_cli")
         .args([
             "-i",
             interface,
@@ -78,23 +76,24 @@ pub fn connect_wifi(interface: &str, ssid: &str, password: Option<&str>) -> AppR
             "psk",
             &format!("\"{psk}\""),
         ])
-        .output()?;
+        .redact(psk)
+        .run()?;
 
-    let _ = Command::new("wpa_cli")
+    Command::new("wpa_cli")
         .args(["-i", interface, "select_network", &net_id])
-        .output()?;
+        .run()?;
 
     Ok(format!("Connecting to {ssid}"))
 }
 
 /// Connect to an open network (no password, `key_mgmt=NONE`).
-fn connect_open_network(interface: &str, ssid: &str) -> AppResult<String> {
+fn connect_open_network(interface: &str, ssid: &str) -> Result<String, Error> {
     let output = Command::new("wpa_cli")
         .args(["-i", interface, "add_network"])
-        .output()?;
-    let net_id = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        .run()?;
+    let net_id = output.trim().to_string();
 
-    let _ = Command::new("wpa_cli")
+    Command::new("wpa_cli")
         .args([
             "-i",
             interface,
@@ -103,15 +102,15 @@ fn connect_open_network(interface: &str, ssid: &str) -> AppResult<String> {
             "ssid",
             &format!("\"{ssid}\""),
         ])
-        .output()?;
+        .run()?;
 
-    let _ = Command::new("wpa_cli")
+    Command::new("wpa_cli")
         .args(["-i", interface, "set_network", &net_id, "key_mgmt", "NONE"])
-        .output()?;
+        .run()?;
 
-    let _ = Command::new("wpa_cli")
+    Command::new("wpa_cli")
         .args(["-i", interface, "select_network", &net_id])
-        .output()?;
+        .run()?;
 
     Ok(format!("Connecting to {ssid}"))
 }
@@ -150,7 +149,7 @@ fn get_connected_network(interface: &str) -> Option<String> {
 
 /// Parse `wpa_cli` `scan_results` output.
 /// Format:
-/// ```
+/// ```text
 /// bssid / frequency / signal level / flags / ssid
 /// aa:bb:cc:dd:ee:ff    2437    -45    [WPA2-PSK-CCMP][ESS]    MyNetwork
 /// ```
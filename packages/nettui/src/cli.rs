@@ -0,0 +1,117 @@
+//! `nettui wifi connect <ssid>`, `nettui iface list` and the other
+//! commands that run without the UI.
+
+use std::io::{self, IsTerminal, Read};
+
+use serde_json::{Value, json};
+use tuigreat::cli::{Call, Cli};
+use tuigreat::widgets::CommandSpec;
+use tuigreat::{AppResult, Error};
+
+use crate::backends;
+use crate::network::{
+    Interface, WifiBackend, WifiNetwork, detect_wifi_backend, get_interfaces, toggle_interface,
+};
+
+const COMMANDS: &[CommandSpec] = &[
+    CommandSpec::new("iface list", "", "List interfaces"),
+    CommandSpec::new("iface up", "<name>", "Bring an interface up"),
+    CommandSpec::new("iface down", "<name>", "Take an interface down"),
+    CommandSpec::new("wifi list", "", "List networks from the last scan"),
+    CommandSpec::new("wifi scan", "", "Scan and list networks"),
+    CommandSpec::new("wifi connect", "<ssid>", "Connect (password on stdin)"),
+];
+
+pub const CLI: Cli = Cli::new("nettui", COMMANDS);
+
+/// Run a command.
+///
+/// # Errors
+/// Returns an error if the arguments are wrong or the backend fails.
+pub fn run(call: &Call) -> AppResult<Value> {
+    let value = match call.name {
+        "iface list" => get_interfaces()?.iter().map(interface_json).collect(),
+        "iface up" | "iface down" => {
+            let up = call.name == "iface up";
+            Value::String(toggle_interface(call.arg(0)?, up, None)?)
+        }
+        "wifi list" => {
+            let (backend, iface) = wifi()?;
+            networks_json(&backends::get_networks(backend, &iface)?)
+        }
+        "wifi scan" => {
+            let (backend, iface) = wifi()?;
+            backends::trigger_scan(backend, &iface);
+            std::thread::sleep(backends::scan_delay(backend));
+            networks_json(&backends::get_networks(backend, &iface)?)
+        }
+        "wifi connect" => {
+            let ssid = call.rest(0)?;
+            let (backend, iface) = wifi()?;
+            let network = backends::get_networks(backend, &iface)?
+                .into_iter()
+                .find(|n| n.ssid == ssid);
+            let password = if network.is_some_and(|n| n.secured)
+                && !backends::has_stored_credentials(backend, &iface, &ssid)
+            {
+                Some(read_password(&ssid)?)
+            } else {
+                None
+            };
+            let message = backends::connect_wifi(backend, &iface, &ssid, password.as_deref())?;
+            Value::String(message)
+        }
+        _ => return Err(call.usage_error().into()),
+    };
+    Ok(value)
+}
+
+/// The Wi-Fi backend and the wireless interface it manages.
+fn wifi() -> Result<(WifiBackend, String), Error> {
+    let backend = detect_wifi_backend().ok_or_else(|| {
+        Error::backend("No Wi-Fi backend found (install iwd, wpa_supplicant or NetworkManager)")
+    })?;
+    let iface = get_interfaces()?
+        .into_iter()
+        .find(|i| i.itype == "wlan")
+        .ok_or_else(|| Error::backend("No wireless interface"))?;
+    Ok((backend, iface.name))
+}
+
+/// The password piped to stdin, so it does not show up in `ps`.
+fn read_password(ssid: &str) -> Result<String, Error> {
+    let mut stdin = io::stdin();
+    if stdin.is_terminal() {
+        return Err(Error::backend(format!(
+            "{ssid} needs a password, pipe it to stdin"
+        )));
+    }
+    let mut password = String::new();
+    stdin
+        .read_to_string(&mut password)
+        .map_err(|e| Error::backend(format!("Could not read the password: {e}")))?;
+    Ok(password.trim_end_matches(['\r', '\n']).to_string())
+}
+
+fn interface_json(iface: &Interface) -> Value {
+    json!({
+        "name": iface.name,
+        "type": iface.itype,
+        "state": iface.oper_state,
+        "address": iface.address,
+    })
+}
+
+fn networks_json(networks: &[WifiNetwork]) -> Value {
+    networks
+        .iter()
+        .map(|n| {
+            json!({
+                "ssid": n.ssid,
+                "signal": n.signal,
+                "secured": n.secured,
+                "connected": n.connected,
+            })
+        })
+        .collect()
+}
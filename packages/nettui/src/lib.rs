@@ -1,4 +1,11 @@
-//! Network TUI parsing utilities.
+//! Network TUI parsing utilities, backends and the UI.
+
+pub mod backends;
+pub mod cli;
+pub mod network;
+pub mod ui;
+
+pub use ui::NetTui;
 
 /// A Wi-Fi network discovered during scanning.
 #[derive(Clone, Debug, PartialEq)]
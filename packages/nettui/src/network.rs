@@ -1,8 +1,7 @@
-use std::io::Write;
-use std::process::{Command, Stdio};
 use std::sync::OnceLock;
 
-use tuigreat::AppResult;
+use tuigreat::Error;
+use tuigreat::runner::Command;
 
 static USE_SUDO: OnceLock<bool> = OnceLock::new();
 
@@ -64,58 +63,50 @@ pub fn has_sudo_cached() -> bool {
     }
     Command::new(find_sudo())
         .args(["--non-interactive", "true"])
-        .stdout(Stdio::null())
-        .stderr(Stdio::null())
-        .status()
-        .map(|s| s.success())
-        .unwrap_or(false)
+        .output()
+        .is_ok_and(|output| output.status.success())
 }
 
-/// Run a command with sudo, using provided password if needed
-pub fn run_with_sudo(program: &str, args: &[&str], password: Option<&str>) -> AppResult<String> {
+/// Run a command with sudo, using provided password if needed. Without
+/// cached credentials or a password this is
+/// [`Error::PermissionDenied`], so the caller can ask for one.
+pub fn run_with_sudo(
+    program: &str,
+    args: &[&str],
+    password: Option<&str>,
+) -> Result<String, Error> {
     if !needs_sudo() {
-        let output = Command::new(program).args(args).output()?;
-        return Ok(String::from_utf8_lossy(&output.stdout).to_string());
+        return Command::new(program).args(args).run();
     }
 
     // Try non-interactive first (cached credentials)
-    let try_cached = Command::new(find_sudo())
-        .args(["--non-interactive", program])
-        .args(args)
-        .output()?;
-
-    if try_cached.status.success() {
-        return Ok(String::from_utf8_lossy(&try_cached.stdout).to_string());
-    }
-
-    // Need password
+    let mut cached = Command::new(find_sudo());
+    cached.args(["--non-interactive", program]).args(args);
     let Some(pass) = password else {
-        return Err("Sudo password required".into());
+        return match cached.run() {
+            Err(Error::Backend {
+                command, stderr, ..
+            }) => Err(Error::PermissionDenied { command, stderr }),
+            result => result,
+        };
     };
+    if let Ok(stdout) = cached.run() {
+        return Ok(stdout);
+    }
 
-    let mut child = Command::new(find_sudo())
+    Command::new(find_sudo())
         .args(["-S", program])
         .args(args)
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()?;
-
-    if let Some(mut stdin) = child.stdin.take() {
-        writeln!(stdin, "{pass}")?;
-    }
-
-    let output = child.wait_with_output()?;
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        if stderr.contains("incorrect password") || stderr.contains("Sorry") {
-            Err("Incorrect password".into())
-        } else {
-            Err(stderr.trim().to_string().into())
-        }
-    }
+        .stdin(&format!("{pass}\n"))
+        .run()
+        .map_err(|e| match e {
+            Error::Backend {
+                command, stderr, ..
+            } if stderr.contains("incorrect password") || stderr.contains("Sorry") => {
+                Error::PermissionDenied { command, stderr }
+            }
+            e => e,
+        })
 }
 
 #[derive(Clone)]
@@ -134,13 +125,13 @@ pub struct WifiNetwork {
     pub connected: bool,
 }
 
-pub fn get_interfaces() -> AppResult<Vec<Interface>> {
-    let output = Command::new("networkctl")
+pub fn get_interfaces() -> Result<Vec<Interface>, Error> {
+    let text = Command::new("networkctl")
         .args(["--json=short", "list"])
-        .output()?;
+        .run()?;
 
     let json: serde_json::Value =
-        serde_json::from_slice(&output.stdout).unwrap_or(serde_json::json!({"Interfaces": []}));
+        serde_json::from_str(&text).map_err(|e| Error::parse("networkctl list", e))?;
 
     let interfaces = json["Interfaces"]
         .as_array()
@@ -195,18 +186,8 @@ pub fn toggle_interface(
     interface: &str,
     bring_up: bool,
     password: Option<&str>,
-) -> AppResult<String> {
+) -> Result<String, Error> {
     let action = if bring_up { "up" } else { "down" };
-
-    match run_with_sudo("networkctl", &[action, interface], password) {
-        Ok(_) => Ok(format!("{interface} {action}")),
-        Err(e) => {
-            let msg = e.to_string();
-            if msg == "Sudo password required" {
-                Err(e)
-            } else {
-                Ok(msg)
-            }
-        }
-    }
+    run_with_sudo("networkctl", &[action, interface], password)?;
+    Ok(format!("{interface} {action}"))
 }
@@ -0,0 +1,1270 @@
+//! The interactive UI, also hosted by the `anytui` hub.
+
+use std::time::{Duration, Instant};
+
+use crossterm::event::MouseEvent;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+use serde_json::{Value, json};
+use tuigreat::{
+    Action, App, AppResult, Confirmations, Error, Events, Interval, Keymap, MessageLog, Notifier,
+    StatusMessage, Tasks, Theme, repeat_action,
+    widgets::{
+        CommandEvent, CommandLine, CommandSpec, ConfirmEvent, ConfirmPopup, DetailPopup, HelpPopup,
+        Invocation, MatchMode, MessagePopup, SearchDirection, SearchPopup, SearchState,
+        SelectableList, Tabs, TextInput, centered_rect,
+    },
+    yank,
+};
+
+use crate::backends;
+use crate::network::{
+    self, Interface, WifiBackend, WifiNetwork, detect_wifi_backend, get_interfaces,
+    has_sudo_cached, needs_sudo, toggle_interface,
+};
+
+#[derive(Debug, Clone, PartialEq, Default)]
+enum UiMode {
+    #[default]
+    Normal,
+    Help,
+    Search,
+    Command,
+    Jump {
+        forward: bool,
+    },
+    PasswordInput,
+    SudoPassword {
+        iface: String,
+        bring_up: bool,
+    },
+    SudoForScan,
+    Details(DetailPopup),
+    Messages(MessagePopup),
+    Confirm(ConfirmPopup),
+}
+
+/// Wi-Fi scanning state
+#[derive(Debug, Clone, Copy, Default)]
+#[allow(clippy::struct_excessive_bools)]
+struct WifiState {
+    available: bool,
+    scanned: bool,
+    scanning: bool,
+    /// True between `trigger_scan` and `get_networks` (two-phase non-blocking scan).
+    scan_triggered: bool,
+}
+
+/// Interface refresh interval on the Interfaces tab.
+const IFACE_REFRESH: Duration = Duration::from_secs(3);
+/// Time between Wi-Fi scans on the Wi-Fi tab.
+const RESCAN_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Commands for the `:` command line.
+const COMMANDS: &[CommandSpec] = &[
+    CommandSpec::new("connect", "<ssid>", "Connect to a Wi-Fi network"),
+    CommandSpec::new("toggle", "<interface>", "Bring an interface up or down"),
+    CommandSpec::new("scan", "", "Scan for Wi-Fi networks"),
+    CommandSpec::new("refresh", "", "Reload interfaces"),
+];
+
+/// Results of background tasks.
+enum TaskResult {
+    Connected(Result<String, Error>),
+}
+
+/// Interfaces and Wi-Fi networks.
+pub struct NetTui {
+    theme: Theme,
+    keymap: Keymap,
+    tabs: Tabs,
+    interfaces: SelectableList<Interface>,
+    networks: SelectableList<WifiNetwork>,
+    mode: UiMode,
+    command: CommandLine,
+    messages: MessageLog,
+    confirmations: Confirmations,
+    notifier: Notifier,
+    backend: Option<WifiBackend>,
+    wifi: WifiState,
+    // When the last Wi-Fi scan was triggered
+    scan_started: Option<Instant>,
+    iface_timer: Interval,
+    // Refresh scheduled after an interface toggle or connect (instead of `Thread::sleep`)
+    refresh_at: Option<Instant>,
+    // WiFi password input
+    password_input: TextInput,
+    pending_ssid: String,
+    // Sudo password input
+    sudo_password: TextInput,
+    tasks: Tasks<TaskResult>,
+    // With `--inline`, Enter prints the interface or SSID and quits
+    picker: bool,
+    picked: Option<String>,
+}
+
+impl NetTui {
+    /// Load the interfaces and the networks of the detected Wi-Fi backend.
+    ///
+    /// # Errors
+    /// Returns an error if the interfaces cannot be listed.
+    pub fn new() -> AppResult<Self> {
+        let interfaces = get_interfaces()?;
+        let mut command = CommandLine::new(COMMANDS.to_vec());
+        command.set_arguments("toggle", interfaces.iter().map(|i| i.name.clone()));
+
+        let backend = detect_wifi_backend();
+        let app_title = match backend {
+            Some(b) => format!("Network Manager v0.1 ({b})"),
+            None => "Network Manager v0.1".to_string(),
+        };
+
+        let keymap = Keymap::load("nettui");
+        let mut messages = MessageLog::new();
+        let confirmations = Confirmations::load("nettui", &["down"]);
+        for w in keymap.warnings().iter().chain(confirmations.warnings()) {
+            messages.push(StatusMessage::warning(format!("Warning: {w}")));
+        }
+
+        Ok(Self {
+            theme: Theme::default(),
+            keymap,
+            tabs: Tabs::new(vec!["Interfaces".to_string(), "Wi-Fi".to_string()])
+                .with_app_title(&app_title),
+            interfaces: SelectableList::new(interfaces, |i| {
+                let addr = i.address.as_deref().unwrap_or("-");
+                format!(
+                    "{:<14} {:>10}  {:<12}  {}",
+                    i.name, i.itype, i.oper_state, addr
+                )
+            })
+            .with_key(|i| i.name.clone()),
+            networks: SelectableList::new(vec![], |n: &WifiNetwork| {
+                let connected = if n.connected { "*" } else { " " };
+                let security = if n.secured { "WPA" } else { "   " };
+                format!("{} {:3}% {} {}", connected, n.signal, security, n.ssid)
+            })
+            .with_match_mode(MatchMode::Fuzzy),
+            mode: UiMode::default(),
+            command,
+            messages,
+            confirmations,
+            notifier: Notifier::new("nettui"),
+            backend,
+            wifi: WifiState {
+                available: backend.is_some(),
+                scanned: false,
+                scanning: false,
+                scan_triggered: false,
+            },
+            scan_started: None,
+            iface_timer: Interval::new(IFACE_REFRESH),
+            refresh_at: None,
+            password_input: TextInput::masked(),
+            pending_ssid: String::new(),
+            sudo_password: TextInput::masked(),
+            tasks: Tasks::new(),
+            picker: false,
+            picked: None,
+        })
+    }
+
+    /// With `picker`, Enter quits and [`App::output`] gives
+    /// the interface name or SSID.
+    #[must_use]
+    pub fn with_picker(mut self, picker: bool) -> Self {
+        self.picker = picker;
+        self
+    }
+
+    fn current_tab(&self) -> usize {
+        self.tabs.selected()
+    }
+
+    fn has_wifi_enabled(&self) -> bool {
+        self.interfaces
+            .items()
+            .iter()
+            .any(|i| i.itype == "wlan" && i.oper_state != "off")
+    }
+
+    fn refresh(&mut self) -> AppResult<()> {
+        let interfaces = get_interfaces()?;
+        self.notify_address_changes(&interfaces);
+        self.interfaces.set_items(interfaces);
+        let names: Vec<String> = self
+            .interfaces
+            .items()
+            .iter()
+            .map(|i| i.name.clone())
+            .collect();
+        self.command.set_arguments("toggle", names);
+        Ok(())
+    }
+
+    /// Tell the desktop about interfaces that got or lost their address
+    /// since the last refresh.
+    fn notify_address_changes(&self, interfaces: &[Interface]) {
+        for iface in interfaces {
+            let Some(old) = self
+                .interfaces
+                .items()
+                .iter()
+                .find(|i| i.name == iface.name)
+            else {
+                continue;
+            };
+            match (&old.address, &iface.address) {
+                (Some(_), None) => self
+                    .notifier
+                    .notify(format!("{} is offline", iface.name), "Lost its address"),
+                (None, Some(address)) => self
+                    .notifier
+                    .notify(format!("{} is online", iface.name), address),
+                _ => {}
+            }
+        }
+    }
+
+    /// Tell the desktop when the Wi-Fi connection changed between scans.
+    fn notify_wifi_change(&self, networks: &[WifiNetwork]) {
+        if !self.wifi.scanned {
+            return;
+        }
+        let connected =
+            |list: &[WifiNetwork]| list.iter().find(|n| n.connected).map(|n| n.ssid.clone());
+        match (connected(self.networks.items()), connected(networks)) {
+            (old, Some(new)) if old.as_ref() != Some(&new) => {
+                self.notifier.notify(format!("Connected to {new}"), "Wi-Fi");
+            }
+            (Some(old), None) => {
+                self.notifier
+                    .notify(format!("Disconnected from {old}"), "Wi-Fi");
+            }
+            _ => {}
+        }
+    }
+
+    fn schedule_refresh(&mut self, delay: Duration) {
+        self.refresh_at = Some(Instant::now() + delay);
+    }
+
+    /// Whether the Wi-Fi tab is open and scanning in the background.
+    fn scan_active(&self) -> bool {
+        self.current_tab() == 1 && self.has_wifi_enabled() && self.mode == UiMode::Normal
+    }
+
+    /// When the next scan phase is due: fetching results after the
+    /// backend's delay, or triggering the next scan.
+    fn scan_deadline(&self) -> Instant {
+        match self.scan_started {
+            Some(start) if self.wifi.scan_triggered => {
+                start + self.backend.map_or(Duration::ZERO, backends::scan_delay)
+            }
+            Some(start) => start + RESCAN_INTERVAL,
+            None => Instant::now(),
+        }
+    }
+
+    /// Phase 1: Trigger a Wi-Fi scan (non-blocking).
+    fn trigger_wifi_scan(&mut self) {
+        self.scan_started = Some(Instant::now());
+        let Some(backend) = self.backend else { return };
+        if let Some(iface) = self.interfaces.items().iter().find(|i| i.itype == "wlan") {
+            backends::trigger_scan(backend, &iface.name);
+            self.wifi.scanning = true;
+            self.wifi.scan_triggered = true;
+            self.messages.hint(StatusMessage::info("Scanning..."));
+        }
+    }
+
+    /// Phase 2: Fetch scan results (non-blocking, reads cached results).
+    fn fetch_wifi_networks(&mut self) {
+        let Some(backend) = self.backend else { return };
+        if let Some(iface) = self.interfaces.items().iter().find(|i| i.itype == "wlan") {
+            let networks = backends::get_networks(backend, &iface.name).unwrap_or_default();
+            let count = networks.len();
+            let ssids: Vec<String> = networks.iter().map(|n| n.ssid.clone()).collect();
+            self.command.set_arguments("connect", ssids);
+            self.notify_wifi_change(&networks);
+            self.networks.set_items(networks);
+            self.wifi.scanned = true;
+            self.wifi.scanning = false;
+            self.wifi.scan_triggered = false;
+            self.messages
+                .push(StatusMessage::info(format!("{count} networks found")));
+        }
+    }
+
+    /// Toggle the selected interface, asking before taking it down.
+    fn request_toggle(&mut self) {
+        let Some(iface) = self.interfaces.selected() else {
+            return;
+        };
+        if iface.oper_state != "off"
+            && let Some(confirm) = self.confirmations.ask(
+                "down",
+                format!("Take {} down? Connections over it will drop.", iface.name),
+            )
+        {
+            self.mode = UiMode::Confirm(confirm);
+            return;
+        }
+        self.toggle_selected_interface(None);
+    }
+
+    fn toggle_selected_interface(&mut self, password: Option<&str>) {
+        if let Some(iface) = self.interfaces.selected() {
+            let bring_up = iface.oper_state == "off";
+            let name = iface.name.clone();
+
+            match toggle_interface(&name, bring_up, password) {
+                Ok(result) => {
+                    self.messages.push(StatusMessage::success(result));
+                    // Schedule a refresh after a short delay
+                    self.schedule_refresh(Duration::from_millis(300));
+                }
+                Err(Error::PermissionDenied { .. }) if password.is_none() => {
+                    // Show sudo password popup
+                    self.sudo_password.clear();
+                    self.mode = UiMode::SudoPassword {
+                        iface: name,
+                        bring_up,
+                    };
+                }
+                Err(e) => self.messages.push(e),
+            }
+        }
+    }
+
+    fn confirm_sudo_password(&mut self) {
+        if let UiMode::SudoPassword { iface, bring_up } = &self.mode {
+            let iface = iface.clone();
+            let bring_up = *bring_up;
+            let pass = self.sudo_password.text().to_string();
+
+            match toggle_interface(&iface, bring_up, Some(&pass)) {
+                Ok(result) => {
+                    self.messages.push(StatusMessage::success(result));
+                    // Schedule a refresh after a short delay
+                    self.schedule_refresh(Duration::from_millis(300));
+                }
+                Err(e) => self.messages.push(e),
+            }
+        }
+        self.mode = UiMode::Normal;
+        self.sudo_password.clear();
+    }
+
+    fn cancel_sudo(&mut self) {
+        self.mode = UiMode::Normal;
+        self.sudo_password.clear();
+        self.messages.push(StatusMessage::info("Cancelled"));
+    }
+
+    fn selected_wifi_interface(&self) -> Option<&Interface> {
+        self.interfaces.items().iter().find(|i| i.itype == "wlan")
+    }
+
+    fn connect_to_network(&mut self) {
+        let Some(network) = self.networks.selected() else {
+            return;
+        };
+        let ssid = network.ssid.clone();
+        let secured = network.secured;
+
+        let Some(iface) = self.selected_wifi_interface() else {
+            return;
+        };
+        let iface_name = iface.name.clone();
+        let Some(backend) = self.backend else { return };
+
+        if secured && !backends::has_stored_credentials(backend, &iface_name, &ssid) {
+            // Secured network without stored credentials — prompt for password
+            self.pending_ssid = ssid;
+            self.password_input.clear();
+            self.mode = UiMode::PasswordInput;
+            return;
+        }
+
+        // Open network or has stored credentials — connect directly
+        self.spawn_connect(backend, iface_name, ssid, None);
+    }
+
+    /// Connect in the background; the result arrives in `finish_task`.
+    fn spawn_connect(
+        &mut self,
+        backend: WifiBackend,
+        iface: String,
+        ssid: String,
+        password: Option<String>,
+    ) {
+        self.tasks
+            .spawn(format!(" Connecting to {ssid}..."), move |_| {
+                let result = backends::connect_wifi(backend, &iface, &ssid, password.as_deref());
+                TaskResult::Connected(result)
+            });
+    }
+
+    fn finish_task(&mut self, result: TaskResult) {
+        match result {
+            TaskResult::Connected(Ok(msg)) => {
+                self.messages.push(StatusMessage::info(msg));
+                self.schedule_refresh(Duration::from_millis(500));
+            }
+            TaskResult::Connected(Err(e)) => self.messages.push(e),
+        }
+    }
+
+    fn confirm_wifi_password(&mut self) {
+        if let (Some(iface), Some(backend)) = (self.selected_wifi_interface(), self.backend) {
+            let iface = iface.name.clone();
+            let ssid = self.pending_ssid.clone();
+            let pass = self.password_input.text().to_string();
+            self.spawn_connect(backend, iface, ssid, Some(pass));
+        }
+        self.mode = UiMode::Normal;
+        self.password_input.clear();
+        self.pending_ssid.clear();
+    }
+
+    fn cancel_password(&mut self) {
+        self.mode = UiMode::Normal;
+        self.password_input.clear();
+        self.pending_ssid.clear();
+        self.messages.push(StatusMessage::info("Cancelled"));
+    }
+
+    fn start_search(&mut self, direction: SearchDirection) {
+        match self.current_tab() {
+            0 => self.interfaces.start_search(direction),
+            1 => self.networks.start_search(direction),
+            _ => {}
+        }
+        self.mode = UiMode::Search;
+    }
+
+    fn search_edit(&mut self, action: Action) {
+        match self.current_tab() {
+            0 => self.interfaces.edit_search(action),
+            1 => self.networks.edit_search(action),
+            _ => false,
+        };
+    }
+
+    fn focused_search(&self) -> SearchState<'_> {
+        if self.current_tab() == 1 {
+            self.networks.search_state()
+        } else {
+            self.interfaces.search_state()
+        }
+    }
+
+    fn focused_match_info(&self) -> Option<(usize, usize)> {
+        match self.current_tab() {
+            0 => self.interfaces.match_info(),
+            1 => self.networks.match_info(),
+            _ => None,
+        }
+    }
+
+    fn clear_search(&mut self) {
+        match self.current_tab() {
+            0 => self.interfaces.clear_search(),
+            1 => self.networks.clear_search(),
+            _ => {}
+        }
+        self.mode = UiMode::Normal;
+    }
+
+    fn next_match(&mut self) {
+        match self.current_tab() {
+            0 => {
+                self.interfaces.next_match();
+            }
+            1 => {
+                self.networks.next_match();
+            }
+            _ => {}
+        }
+    }
+
+    fn prev_match(&mut self) {
+        match self.current_tab() {
+            0 => {
+                self.interfaces.prev_match();
+            }
+            1 => {
+                self.networks.prev_match();
+            }
+            _ => {}
+        }
+    }
+
+    fn half_page_down(&mut self) {
+        match self.current_tab() {
+            0 => self.interfaces.half_page_down(),
+            1 => self.networks.half_page_down(),
+            _ => {}
+        }
+    }
+
+    fn half_page_up(&mut self) {
+        match self.current_tab() {
+            0 => self.interfaces.half_page_up(),
+            1 => self.networks.half_page_up(),
+            _ => {}
+        }
+    }
+
+    fn full_page_down(&mut self) {
+        match self.current_tab() {
+            0 => self.interfaces.page_down(),
+            1 => self.networks.page_down(),
+            _ => {}
+        }
+    }
+
+    fn full_page_up(&mut self) {
+        match self.current_tab() {
+            0 => self.interfaces.page_up(),
+            1 => self.networks.page_up(),
+            _ => {}
+        }
+    }
+
+    fn render_status_bar(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let sudo_indicator = if !needs_sudo() {
+            Span::styled(" [ROOT]", self.theme.success())
+        } else if has_sudo_cached() {
+            Span::styled(" [SUDO]", self.theme.highlight())
+        } else {
+            Span::styled(" [USER]", self.theme.muted())
+        };
+
+        let scan_indicator = if self.wifi.scanning {
+            Span::styled(" [SCAN]", self.theme.highlight())
+        } else {
+            Span::raw("")
+        };
+
+        let status_block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(Theme::BORDER_TYPE)
+            .border_style(self.theme.border());
+        let command_area = status_block.inner(area);
+        let mut status = self.tasks.indicator().map_or_else(
+            || self.messages.to_line(&self.theme),
+            |progress| StatusMessage::info(progress.trim_start()).to_line(&self.theme),
+        );
+        status.spans.splice(0..0, [sudo_indicator, scan_indicator]);
+        let status = Paragraph::new(status).block(status_block);
+        frame.render_widget(status, area);
+        if self.mode == UiMode::Command {
+            self.command.render(frame, command_area, &self.theme);
+        }
+    }
+
+    fn search_popup_title(&self) -> &'static str {
+        if self.current_tab() == 0 {
+            " Search Interfaces "
+        } else {
+            " Search Networks "
+        }
+    }
+
+    fn render_password_popup(&self, frame: &mut Frame) {
+        let area = centered_rect(50, 7, frame.area());
+        frame.render_widget(Clear, area);
+
+        let block = Block::default()
+            .title(format!(" Password for {} ", self.pending_ssid))
+            .title_style(self.theme.title())
+            .borders(Borders::ALL)
+            .border_type(Theme::BORDER_TYPE)
+            .border_style(self.theme.border_focused());
+
+        let mut input = vec![Span::raw("  ")];
+        input.extend(self.password_input.spans(Style::default(), true));
+        let content = vec![
+            Line::from(""),
+            Line::from(input),
+            Line::from(""),
+            Line::from(Span::styled(
+                "  [Enter] Connect  [Esc] Cancel",
+                self.theme.muted(),
+            )),
+        ];
+
+        let input = Paragraph::new(content).block(block);
+        frame.render_widget(input, area);
+    }
+
+    fn render_sudo_popup(&self, frame: &mut Frame) {
+        let area = centered_rect(50, 7, frame.area());
+        frame.render_widget(Clear, area);
+
+        let title = if let UiMode::SudoPassword { iface, bring_up } = &self.mode {
+            let action = if *bring_up { "up" } else { "down" };
+            format!(" Sudo: {action} {iface} ")
+        } else {
+            " Sudo Password ".to_string()
+        };
+
+        let block = Block::default()
+            .title(title)
+            .title_style(self.theme.title())
+            .borders(Borders::ALL)
+            .border_type(Theme::BORDER_TYPE)
+            .border_style(self.theme.border_focused());
+
+        let mut input = vec![Span::raw("  ")];
+        input.extend(self.sudo_password.spans(Style::default(), true));
+        let content = vec![
+            Line::from(""),
+            Line::from(input),
+            Line::from(""),
+            Line::from(Span::styled(
+                "  [Enter] Authenticate  [Esc] Cancel",
+                self.theme.muted(),
+            )),
+        ];
+
+        let input = Paragraph::new(content).block(block);
+        frame.render_widget(input, area);
+    }
+
+    fn render_sudo_scan_popup(&self, frame: &mut Frame) {
+        let area = centered_rect(50, 7, frame.area());
+        frame.render_widget(Clear, area);
+
+        let block = Block::default()
+            .title(" Sudo: Wi-Fi scan ")
+            .title_style(self.theme.title())
+            .borders(Borders::ALL)
+            .border_type(Theme::BORDER_TYPE)
+            .border_style(self.theme.border_focused());
+
+        let mut input = vec![Span::raw("  ")];
+        input.extend(self.sudo_password.spans(Style::default(), true));
+        let content = vec![
+            Line::from(""),
+            Line::from(input),
+            Line::from(""),
+            Line::from(Span::styled(
+                "  [Enter] Authenticate  [Esc] Cancel",
+                self.theme.muted(),
+            )),
+        ];
+
+        let input = Paragraph::new(content).block(block);
+        frame.render_widget(input, area);
+    }
+
+    fn yank_selected(&mut self) {
+        let text = match self.current_tab() {
+            0 => self.interfaces.selected().map(|i| i.name.clone()),
+            1 => self.networks.selected().map(|n| n.ssid.clone()),
+            _ => None,
+        };
+
+        if let Some(text) = text {
+            match yank(&text) {
+                Ok(backend) => self.messages.push(StatusMessage::success(format!(
+                    "Yanked with {backend}: {text}"
+                ))),
+                Err(e) => self.messages.push(e),
+            }
+        } else {
+            self.messages.push(StatusMessage::info("Nothing to yank"));
+        }
+    }
+
+    fn handle_search_action(&mut self, action: Action) {
+        match action {
+            Action::Back => {
+                if self.focused_search().query.is_empty() {
+                    self.clear_search();
+                } else {
+                    self.search_edit(action);
+                }
+            }
+            Action::Quit => self.clear_search(),
+            Action::Select => {
+                self.mode = UiMode::Normal;
+                if let Some((cur, total)) = self.focused_match_info() {
+                    self.messages
+                        .hint(StatusMessage::info(format!("Match {cur}/{total}")));
+                }
+            }
+            action => self.search_edit(action),
+        }
+    }
+
+    fn handle_password_action(&mut self, action: Action) {
+        match action {
+            Action::Back if self.password_input.is_empty() => self.cancel_password(),
+            Action::Quit => self.cancel_password(),
+            Action::Select => self.confirm_wifi_password(),
+            action => {
+                self.password_input.handle_action(action);
+            }
+        }
+    }
+
+    fn handle_sudo_action(&mut self, action: Action) {
+        match action {
+            Action::Back if self.sudo_password.is_empty() => self.cancel_sudo(),
+            Action::Quit => self.cancel_sudo(),
+            Action::Select => self.confirm_sudo_password(),
+            action => {
+                self.sudo_password.handle_action(action);
+            }
+        }
+    }
+
+    fn handle_sudo_scan_action(&mut self, action: Action) {
+        match action {
+            Action::Back if self.sudo_password.is_empty() => {
+                self.mode = UiMode::Normal;
+                self.tabs.previous(); // Go back to interfaces tab
+            }
+            Action::Quit => {
+                self.mode = UiMode::Normal;
+                self.tabs.previous();
+            }
+            Action::Select => self.confirm_sudo_for_scan(),
+            action => {
+                self.sudo_password.handle_action(action);
+            }
+        }
+    }
+
+    fn handle_normal_action(&mut self, action: Action) -> AppResult<bool> {
+        let prev_tab = self.current_tab();
+
+        match action {
+            // Esc first shows the whole list again
+            Action::Back if self.focused_search().is_filtered() => self.clear_search(),
+            Action::Quit => return Ok(false),
+            Action::Help => self.mode = UiMode::Help,
+            Action::Details => {
+                if let Some(details) = self
+                    .messages
+                    .last_detail()
+                    .and_then(DetailPopup::for_status)
+                {
+                    self.mode = UiMode::Details(details);
+                }
+            }
+            Action::Messages => self.mode = UiMode::Messages(MessagePopup::new(&self.messages)),
+            Action::Refresh => {
+                self.refresh()?;
+                if self.current_tab() == 1 {
+                    self.trigger_wifi_scan();
+                }
+            }
+            Action::Down => match self.current_tab() {
+                0 => self.interfaces.next(),
+                1 => self.networks.next(),
+                _ => {}
+            },
+            Action::Up => match self.current_tab() {
+                0 => self.interfaces.previous(),
+                1 => self.networks.previous(),
+                _ => {}
+            },
+            Action::Left => self.tabs.previous(),
+            Action::Right => self.tabs.next(),
+            Action::Top => match self.current_tab() {
+                0 => self.interfaces.first(),
+                1 => self.networks.first(),
+                _ => {}
+            },
+            Action::Bottom => match self.current_tab() {
+                0 => self.interfaces.last(),
+                1 => self.networks.last(),
+                _ => {}
+            },
+            Action::Select if self.picker => {
+                self.picked = match self.current_tab() {
+                    0 => self.interfaces.selected().map(|i| i.name.clone()),
+                    _ => self.networks.selected().map(|n| n.ssid.clone()),
+                };
+                return Ok(false);
+            }
+            Action::Select => match self.current_tab() {
+                0 => self.request_toggle(),
+                1 => self.connect_to_network(),
+                _ => {}
+            },
+            Action::PageUp => self.half_page_up(),
+            Action::PageDown => self.half_page_down(),
+            Action::FullPageUp => self.full_page_up(),
+            Action::FullPageDown => self.full_page_down(),
+            Action::Search => self.start_search(SearchDirection::Forward),
+            Action::Command => {
+                self.command.open();
+                self.mode = UiMode::Command;
+            }
+            Action::SearchNext => self.next_match(),
+            Action::SearchPrev => self.prev_match(),
+            Action::Yank => self.yank_selected(),
+            Action::JumpTo => {
+                self.mode = UiMode::Jump { forward: true };
+                self.messages.hint(StatusMessage::info("Jump to: "));
+            }
+            Action::JumpBack => {
+                self.mode = UiMode::Jump { forward: false };
+                self.messages.hint(StatusMessage::info("Jump back to: "));
+            }
+            _ => {}
+        }
+
+        // Entering WiFi tab
+        if self.current_tab() == 1 && prev_tab != 1 && self.has_wifi_enabled() && !self.wifi.scanned
+        {
+            if needs_sudo() && !has_sudo_cached() {
+                self.sudo_password.clear();
+                self.mode = UiMode::SudoForScan;
+            } else {
+                self.trigger_wifi_scan();
+            }
+        }
+
+        Ok(true)
+    }
+
+    fn run_command(&mut self, invocation: &Invocation) -> AppResult<()> {
+        match invocation.name {
+            "connect" => {
+                let ssid = match invocation.arg() {
+                    Ok(ssid) => ssid,
+                    Err(e) => {
+                        self.messages.push(StatusMessage::error(e));
+                        return Ok(());
+                    }
+                };
+                let networks = self.networks.items();
+                let found = networks.iter().position(|n| n.ssid == ssid).or_else(|| {
+                    networks
+                        .iter()
+                        .position(|n| n.ssid.eq_ignore_ascii_case(ssid))
+                });
+                match found {
+                    Some(index) => {
+                        self.networks.select(index);
+                        self.connect_to_network();
+                    }
+                    None => self
+                        .messages
+                        .push(StatusMessage::error(format!("No network named {ssid}"))),
+                }
+            }
+            "toggle" => {
+                let name = match invocation.arg() {
+                    Ok(name) => name,
+                    Err(e) => {
+                        self.messages.push(StatusMessage::error(e));
+                        return Ok(());
+                    }
+                };
+                match self.interfaces.items().iter().position(|i| i.name == name) {
+                    Some(index) => {
+                        self.interfaces.select(index);
+                        self.request_toggle();
+                    }
+                    None => self
+                        .messages
+                        .push(StatusMessage::error(format!("No interface named {name}"))),
+                }
+            }
+            "scan" => {
+                self.refresh()?;
+                self.trigger_wifi_scan();
+            }
+            "refresh" => self.refresh()?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn handle_command_action(&mut self, action: Action) -> AppResult<()> {
+        match self.command.handle_action(action) {
+            CommandEvent::Editing => return Ok(()),
+            CommandEvent::Cancelled => {}
+            CommandEvent::Run(invocation) => {
+                self.mode = UiMode::Normal;
+                self.run_command(&invocation)?;
+            }
+            CommandEvent::Error(e) => self.messages.push(StatusMessage::error(e)),
+        }
+        // Commands may open a password prompt
+        if self.mode == UiMode::Command {
+            self.mode = UiMode::Normal;
+        }
+        Ok(())
+    }
+
+    fn confirm_sudo_for_scan(&mut self) {
+        // Cache sudo credentials by running a simple command
+        let pass = self.sudo_password.text().to_string();
+        match network::run_with_sudo("true", &[], Some(&pass)) {
+            Ok(_) => {
+                self.messages.push(StatusMessage::success("Authenticated"));
+                self.trigger_wifi_scan();
+            }
+            Err(e) => self.messages.push(e),
+        }
+        self.mode = UiMode::Normal;
+        self.sudo_password.clear();
+    }
+}
+
+impl App for NetTui {
+    fn title(&self) -> &'static str {
+        "net-tui"
+    }
+
+    fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    fn input_mode(&self) -> bool {
+        matches!(
+            self.mode,
+            UiMode::PasswordInput
+                | UiMode::Search
+                | UiMode::Command
+                | UiMode::Jump { .. }
+                | UiMode::SudoPassword { .. }
+                | UiMode::SudoForScan
+        )
+    }
+
+    fn handle_action_count(&mut self, action: Action, count: usize) -> AppResult<bool> {
+        match action {
+            // `5G` / `5gg` go to line 5
+            Action::Top | Action::Bottom if self.mode == UiMode::Normal => {
+                match self.current_tab() {
+                    0 => self.interfaces.goto_line(count),
+                    1 => self.networks.goto_line(count),
+                    _ => {}
+                }
+                Ok(true)
+            }
+            _ => repeat_action(self, action, count),
+        }
+    }
+
+    fn mouse_capture(&self) -> bool {
+        true
+    }
+
+    fn handle_mouse(&mut self, event: MouseEvent) -> AppResult<bool> {
+        if self.mode != UiMode::Normal || self.tabs.handle_mouse(&event) {
+            return Ok(true);
+        }
+        match self.current_tab() {
+            0 => {
+                self.interfaces.handle_mouse(&event);
+            }
+            1 if self.has_wifi_enabled() => {
+                self.networks.handle_mouse(&event);
+            }
+            _ => {}
+        }
+        Ok(true)
+    }
+
+    fn start(&mut self, events: &Events) {
+        self.tasks.connect(events);
+    }
+
+    fn report_error(&mut self, error: Box<dyn std::error::Error>) -> AppResult<()> {
+        self.messages.report(error)
+    }
+
+    fn output(&self) -> Option<String> {
+        self.picked.clone()
+    }
+
+    fn execute(&mut self, line: &str) -> AppResult<()> {
+        match self.command.parse(line) {
+            CommandEvent::Run(invocation) => self.run_command(&invocation),
+            CommandEvent::Error(e) => Err(e.into()),
+            CommandEvent::Editing | CommandEvent::Cancelled => Ok(()),
+        }
+    }
+
+    fn save_session(&self) -> Option<Value> {
+        Some(json!({
+            "tab": self.current_tab(),
+            "interfaces": self.interfaces.save_session(),
+        }))
+    }
+
+    fn restore_session(&mut self, saved: &Value) {
+        if let Some(tab) = saved["tab"].as_u64().and_then(|t| usize::try_from(t).ok()) {
+            self.tabs.select(tab);
+        }
+        self.interfaces.restore_session(&saved["interfaces"]);
+    }
+
+    fn summary(&self) -> Option<String> {
+        if let Some(network) = self.networks.items().iter().find(|n| n.connected) {
+            return Some(network.ssid.clone());
+        }
+        let online = self
+            .interfaces
+            .items()
+            .iter()
+            .find(|i| i.itype != "loopback" && i.oper_state == "routable");
+        Some(online.map_or_else(|| "offline".to_string(), |i| i.name.clone()))
+    }
+
+    fn tick(&mut self) -> AppResult<()> {
+        for result in self.tasks.finished() {
+            self.finish_task(result);
+        }
+        let now = Instant::now();
+
+        // Delayed refresh after an interface toggle
+        if self.refresh_at.is_some_and(|at| now >= at) {
+            self.refresh_at = None;
+            self.refresh()?;
+        }
+
+        // Periodic interface refresh when on interfaces tab
+        if self.current_tab() == 0 && self.mode == UiMode::Normal && self.iface_timer.due(now) {
+            self.refresh()?;
+        }
+
+        // Two-phase non-blocking WiFi scan (only in Normal mode to avoid blocking input)
+        if self.scan_active() && now >= self.scan_deadline() {
+            if self.wifi.scan_triggered {
+                // Phase 2: fetch results after backend-specific delay
+                self.fetch_wifi_networks();
+            } else {
+                // Phase 1: trigger scan
+                self.trigger_wifi_scan();
+            }
+        }
+        Ok(())
+    }
+
+    fn next_tick(&self) -> Option<Instant> {
+        let iface = (self.current_tab() == 0 && self.mode == UiMode::Normal)
+            .then(|| self.iface_timer.deadline());
+        let scan = self.scan_active().then(|| self.scan_deadline());
+        [
+            self.refresh_at,
+            iface,
+            scan,
+            self.tasks.next_tick(),
+            self.messages.next_expiry(),
+        ]
+        .into_iter()
+        .flatten()
+        .min()
+    }
+
+    fn handle_action(&mut self, action: Action) -> AppResult<bool> {
+        // Jump mode - waiting for character
+        if let UiMode::Jump { forward } = self.mode {
+            match action {
+                Action::Char(c) => {
+                    let found = match self.current_tab() {
+                        0 => self.interfaces.jump_to_char(c, forward),
+                        1 => self.networks.jump_to_char(c, forward),
+                        _ => false,
+                    };
+                    self.messages.hint(StatusMessage::info(if found {
+                        format!("Jumped to '{c}'")
+                    } else {
+                        format!("No match for '{c}'")
+                    }));
+                }
+                _ => {
+                    self.messages.hint(StatusMessage::info("Jump cancelled"));
+                }
+            }
+            self.mode = UiMode::Normal;
+            return Ok(true);
+        }
+
+        match &self.mode {
+            UiMode::Search => {
+                self.handle_search_action(action);
+                Ok(true)
+            }
+            UiMode::Command => {
+                self.handle_command_action(action)?;
+                Ok(true)
+            }
+            UiMode::PasswordInput => {
+                self.handle_password_action(action);
+                Ok(true)
+            }
+            UiMode::SudoPassword { .. } => {
+                self.handle_sudo_action(action);
+                Ok(true)
+            }
+            UiMode::SudoForScan => {
+                self.handle_sudo_scan_action(action);
+                Ok(true)
+            }
+            UiMode::Help => {
+                if matches!(action, Action::Help | Action::Back | Action::Quit) {
+                    self.mode = UiMode::Normal;
+                }
+                Ok(true)
+            }
+            UiMode::Details(_) => {
+                if let UiMode::Details(details) = &mut self.mode
+                    && !details.handle_action(action)
+                {
+                    self.mode = UiMode::Normal;
+                }
+                Ok(true)
+            }
+            UiMode::Confirm(_) => {
+                if let UiMode::Confirm(confirm) = &mut self.mode {
+                    match confirm.handle_action(action) {
+                        ConfirmEvent::Pending => {}
+                        ConfirmEvent::Cancelled => self.mode = UiMode::Normal,
+                        ConfirmEvent::Confirmed => {
+                            self.confirmations.confirmed(confirm);
+                            self.mode = UiMode::Normal;
+                            self.toggle_selected_interface(None);
+                        }
+                    }
+                }
+                Ok(true)
+            }
+            UiMode::Messages(_) => {
+                if let UiMode::Messages(history) = &mut self.mode
+                    && !history.handle_action(action)
+                {
+                    self.mode = UiMode::Normal;
+                }
+                Ok(true)
+            }
+            UiMode::Normal => self.handle_normal_action(action),
+            UiMode::Jump { .. } => unreachable!("Jump mode handled before match"),
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let main_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1), // Tabs
+                Constraint::Min(5),    // Content
+                Constraint::Length(3), // Status box
+            ])
+            .split(frame.area());
+
+        // Tabs
+        self.tabs.render(frame, main_chunks[0], &self.theme);
+
+        // Content based on tab
+        match self.current_tab() {
+            0 => {
+                self.interfaces
+                    .render(frame, main_chunks[1], "", &self.theme, true);
+            }
+            1 => {
+                if !self.wifi.available {
+                    let block = Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(Theme::BORDER_TYPE)
+                        .border_style(self.theme.border());
+                    let msg =
+                        Paragraph::new(" No WiFi backend found (install iwctl, wpa_cli, or nmcli)")
+                            .block(block);
+                    frame.render_widget(msg, main_chunks[1]);
+                } else if !self.has_wifi_enabled() {
+                    let block = Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(Theme::BORDER_TYPE)
+                        .border_style(self.theme.border());
+                    let msg =
+                        Paragraph::new(" Wi-Fi interface is down - enable it in Interfaces tab")
+                            .block(block);
+                    frame.render_widget(msg, main_chunks[1]);
+                } else {
+                    self.networks
+                        .render(frame, main_chunks[1], "", &self.theme, true);
+                }
+            }
+            _ => {}
+        }
+
+        self.render_status_bar(frame, main_chunks[2]);
+
+        if self.mode == UiMode::Help {
+            let bindings = self.keymap.help(&[
+                (&[Action::Down, Action::Up], "Navigate"),
+                (&[Action::Left, Action::Right], "Switch tab"),
+                (&[Action::Top, Action::Bottom], "Top/Bottom"),
+                (&[Action::PageUp, Action::PageDown], "Half page"),
+                (&[Action::FullPageUp, Action::FullPageDown], "Full page"),
+                (&[Action::Search], "Search"),
+                (&[Action::SearchNext, Action::SearchPrev], "Next/Prev match"),
+                (&[Action::Yank], "Yank (copy)"),
+                (&[Action::JumpTo, Action::JumpBack], "Jump to char"),
+                (&[Action::Select], "Toggle/Connect"),
+                (&[Action::Refresh], "Refresh"),
+                (&[Action::Command], "Command line"),
+                (&[Action::Details], "Error details"),
+                (&[Action::Messages], "Message history"),
+                (&[Action::Log], "Debug log"),
+                (&[Action::Quit], "Quit"),
+            ]);
+            HelpPopup::render(frame, &bindings, &self.theme);
+        }
+
+        if self.mode == UiMode::Search {
+            SearchPopup::render(
+                frame,
+                self.search_popup_title(),
+                self.focused_search(),
+                &self.theme,
+            );
+        }
+
+        if self.mode == UiMode::PasswordInput {
+            self.render_password_popup(frame);
+        }
+
+        if matches!(self.mode, UiMode::SudoPassword { .. }) {
+            self.render_sudo_popup(frame);
+        }
+
+        if self.mode == UiMode::SudoForScan {
+            self.render_sudo_scan_popup(frame);
+        }
+
+        if let UiMode::Details(details) = &self.mode {
+            details.render(frame, &self.theme);
+        }
+
+        if let UiMode::Messages(history) = &self.mode {
+            history.render(frame, &self.theme);
+        }
+
+        if let UiMode::Confirm(confirm) = &self.mode {
+            confirm.render(frame, &self.theme);
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use nettui::NetTui;
+use tuigreat::App;
+use tuigreat::runner::{self, FakeRunner};
+use tuigreat::testing::{TestHarness, assert_snapshot};
+
+/// The screen, with the sudo indicator as it is for a user without root.
+fn screen(harness: &TestHarness<NetTui>) -> String {
+    harness.screen().replace(" [ROOT]", " [USER]")
+}
+
+/// The app on a recorded `iwctl` session.
+fn app() -> (TestHarness<NetTui>, Arc<FakeRunner>) {
+    let fake = Arc::new(
+        FakeRunner::load(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/iwctl.txt"
+        ))
+        .unwrap(),
+    );
+    runner::set(fake.clone());
+    let app = NetTui::new().unwrap();
+    (TestHarness::new(app, 80, 12), fake)
+}
+
+#[test]
+fn test_interfaces_from_networkctl() {
+    let (harness, _) = app();
+    let screen = harness.screen();
+    assert!(screen.contains("Network Manager v0.1 (iwd)"));
+    assert!(screen.contains("enp0s31f6           ether  no-carrier    -"));
+    assert!(screen.contains("wlan0                wlan  routable      192.168.1.23"));
+    // Loopback is left out
+    assert!(!screen.contains(" lo "));
+    assert_eq!(harness.app().summary(), Some("wlan0".to_string()));
+}
+
+#[test]
+fn test_wifi_scan() {
+    let (mut harness, fake) = app();
+    harness.press("l");
+    assert!(fake.ran(&["iwctl", "station", "wlan0", "scan"]));
+    assert!(harness.screen().contains("Scanning..."));
+
+    // Results are fetched once iwd had time to scan
+    harness.wait(Duration::from_millis(600));
+    let screen = harness.screen();
+    assert!(screen.contains(">  * 100% WPA HomeNet"));
+    assert!(screen.contains("      75%     Cafe Guest"));
+    assert!(screen.contains("3 networks found"));
+    assert_eq!(harness.app().summary(), Some("HomeNet".to_string()));
+}
+
+#[test]
+fn test_snapshots() {
+    let (mut harness, _) = app();
+    assert_snapshot("interfaces", &screen(&harness));
+    harness.press("l");
+    harness.wait(Duration::from_millis(600));
+    assert_snapshot("wifi", &screen(&harness));
+}
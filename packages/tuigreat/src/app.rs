@@ -1,14 +1,32 @@
 use crossterm::{
-    event::{self, Event, KeyEventKind},
+    cursor::Show,
+    event::{
+        DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture, Event,
+        KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseEvent,
+    },
     execute,
     terminal::{
         EnterAlternateScreen, LeaveAlternateScreen, SetTitle, disable_raw_mode, enable_raw_mode,
     },
 };
-use ratatui::{Terminal, backend::CrosstermBackend};
-use std::io;
+use ratatui::{Frame, Terminal, TerminalOptions, Viewport, backend::CrosstermBackend};
+use serde_json::{Value, json};
+use std::cell::RefCell;
+use std::fs::OpenOptions;
+use std::io::{self, IsTerminal, Write};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::panic;
+use std::path::Path;
+use std::sync::{Mutex, Once, PoisonError};
+use std::thread::{self, ThreadId};
+use std::time::Instant;
 
-use crate::{Action, Theme, keys::KeyHandler};
+use crate::events::{Events, InputThread, LoopEvent, SignalThread};
+use crate::ipc::{self, Request};
+use crate::widgets::LogPopup;
+use crate::{
+    Action, CountedAction, KeySequencer, Keymap, Theme, keys::KeyHandler, notify, session, trace,
+};
 
 pub type AppResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 
@@ -16,67 +34,608 @@ pub trait App {
     fn title(&self) -> &str;
     fn theme(&self) -> &Theme;
 
+    /// Key bindings used to translate key presses into actions.
+    fn keymap(&self) -> &Keymap {
+        Keymap::defaults()
+    }
+
     /// Handle an action and return whether to continue running.
     ///
     /// # Errors
     /// Returns an error if the action handling fails.
     fn handle_action(&mut self, action: Action) -> AppResult<bool>;
+
+    /// Handle an action typed with a count prefix (`5j`, `3+`, `10G`).
+    /// Repeats the action by default; override where the count means
+    /// something else, such as `10G` going to line 10.
+    ///
+    /// # Errors
+    /// Returns an error if the action handling fails.
+    fn handle_action_count(&mut self, action: Action, count: usize) -> AppResult<bool> {
+        repeat_action(self, action, count)
+    }
+
     fn render(&mut self, frame: &mut ratatui::Frame);
 
-    /// Called on each tick of the event loop.
+    /// Called once before the first frame. Keep `events` to wake the loop
+    /// from background threads, e.g. with [`Events::lines`].
+    fn start(&mut self, _events: &Events) {}
+
+    /// Called whenever the loop wakes up: after input, after a wake-up
+    /// from [`Events`] and when [`App::next_tick`] has passed.
     ///
     /// # Errors
     /// Returns an error if the tick processing fails.
     fn tick(&mut self) -> AppResult<()> {
         Ok(())
     }
+
+    /// When to run [`App::tick`] if nothing else happens. `None` sleeps
+    /// until the next input or wake-up.
+    fn next_tick(&self) -> Option<Instant> {
+        None
+    }
+
     /// When true, bypass vim keybindings and pass raw characters
     fn input_mode(&self) -> bool {
         false
     }
+
+    /// When false, digits are passed through instead of starting a count.
+    fn accepts_count(&self) -> bool {
+        true
+    }
+
+    /// Opt in to mouse capture. Mouse events are then passed to
+    /// [`App::handle_mouse`], and the terminal's own text selection needs
+    /// Shift held.
+    fn mouse_capture(&self) -> bool {
+        false
+    }
+
+    /// Handle a mouse event and return whether to continue running.
+    ///
+    /// # Errors
+    /// Returns an error if the event handling fails.
+    fn handle_mouse(&mut self, _event: MouseEvent) -> AppResult<bool> {
+        Ok(true)
+    }
+
+    /// Called with an error from a handler or [`App::tick`]. Return `Ok`
+    /// to keep running, e.g. after showing a backend [`Error`](crate::Error)
+    /// with [`StatusMessage::report`](crate::StatusMessage::report). By
+    /// default the error ends the app.
+    ///
+    /// # Errors
+    /// Returns the error if the app cannot carry on.
+    fn report_error(&mut self, error: Box<dyn std::error::Error>) -> AppResult<()> {
+        Err(error)
+    }
+
+    /// Handle a key press before the keymap sees it. Return `Some` with
+    /// whether to continue running to keep the key, as the
+    /// [`Hub`](crate::hub::Hub) does for switching apps.
+    fn intercept_key(&mut self, _key: KeyEvent) -> Option<AppResult<bool>> {
+        None
+    }
+
+    /// A few words on the app's state, such as the volume or the playing
+    /// song, for the hub's combined status line.
+    fn summary(&self) -> Option<String> {
+        None
+    }
+
+    /// Run a `:` command line sent over the [control socket](crate::ipc),
+    /// e.g. `volume 40`.
+    ///
+    /// # Errors
+    /// Returns an error if the command is unknown or fails.
+    fn execute(&mut self, _line: &str) -> AppResult<()> {
+        Err(format!("{} has no commands", self.title()).into())
+    }
+
+    /// The app's state for `ctl state`.
+    fn state(&self) -> Value {
+        json!({"title": self.title(), "summary": self.summary()})
+    }
+
+    /// Answer a request from the [control socket](crate::ipc) and return
+    /// whether to continue running and the result. Runs the action or
+    /// command, or returns [`App::state`], by default.
+    ///
+    /// # Errors
+    /// Returns an error if the request fails.
+    fn handle_request(&mut self, request: &Request) -> AppResult<(bool, Value)> {
+        ipc::handle(self, request)
+    }
+
+    /// Version of what [`App::save_session`] returns. Bump it when that
+    /// changes shape, and sessions saved before are dropped instead of
+    /// restored.
+    fn session_version(&self) -> u32 {
+        1
+    }
+
+    /// State to keep for the next run, such as the selected tab; see
+    /// [`session`](crate::session).
+    fn save_session(&self) -> Option<Value> {
+        None
+    }
+
+    /// Restore what [`App::save_session`] returned in an earlier run,
+    /// before the first frame. Skip fields that are missing or do not fit
+    /// any more.
+    fn restore_session(&mut self, _session: &Value) {}
+
+    /// Text printed to stdout after the app quits, such as the item picked
+    /// when running [inline](Screen::Inline) from a script.
+    fn output(&self) -> Option<String> {
+        None
+    }
 }
 
-/// Run the TUI application event loop.
+/// Call [`App::handle_action`] `count` times, stopping if the app quits.
+///
+/// # Errors
+/// Returns the first error from the action handler.
+pub fn repeat_action<A: App + ?Sized>(
+    app: &mut A,
+    action: Action,
+    count: usize,
+) -> AppResult<bool> {
+    for _ in 0..count {
+        if !app.handle_action(action)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+pub(crate) fn dispatch<A: App>(app: &mut A, input: CountedAction) -> AppResult<bool> {
+    trace::action(app.title(), input);
+    if log_popup(input.action) {
+        return Ok(true);
+    }
+    let result = match input.count {
+        Some(count) => app.handle_action_count(input.action, count),
+        None => app.handle_action(input.action),
+    };
+    recover(app, result, true)
+}
+
+thread_local! {
+    /// The debug log popup, which every app shows over its own screen.
+    static LOG_POPUP: RefCell<Option<LogPopup>> = const { RefCell::new(None) };
+}
+
+/// Open the debug log on its key, or pass `action` to it while it is open.
+/// Returns whether the popup took the action.
+fn log_popup(action: Action) -> bool {
+    LOG_POPUP.with_borrow_mut(|popup| match popup {
+        Some(open) => {
+            if !open.handle_action(action) {
+                *popup = None;
+            }
+            true
+        }
+        None if action == Action::Log => {
+            *popup = Some(LogPopup::new());
+            true
+        }
+        None => false,
+    })
+}
+
+/// Draw `app`, and the debug log over it if open.
+pub(crate) fn render<A: App>(app: &mut A, frame: &mut Frame) {
+    app.render(frame);
+    LOG_POPUP.with_borrow_mut(|popup| {
+        if let Some(popup) = popup {
+            popup.update(trace::recent());
+            popup.render(frame, app.theme());
+        }
+    });
+}
+
+/// Tick `app`, timing it for the debug log.
+pub(crate) fn tick<A: App>(app: &mut A) -> AppResult<()> {
+    let started = Instant::now();
+    let ticked = app.tick();
+    trace::tick(app.title(), started.elapsed());
+    ticked
+}
+
+/// Hand an error to [`App::report_error`], going on with `fallback` if
+/// the app recovers.
+pub(crate) fn recover<A: App, T>(app: &mut A, result: AppResult<T>, fallback: T) -> AppResult<T> {
+    result.or_else(|error| app.report_error(error).map(|()| fallback))
+}
+
+/// Translate a key press into actions and run them, as the loop does.
+pub(crate) fn handle_key<A: App>(
+    app: &mut A,
+    keys: &mut KeySequencer,
+    key: KeyEvent,
+    now: Instant,
+) -> AppResult<bool> {
+    if let Some(running) = app.intercept_key(key) {
+        keys.reset();
+        return recover(app, running, true);
+    }
+    let inputs = if app.input_mode() {
+        keys.reset();
+        vec![CountedAction::new(KeyHandler::parse_input_mode(key))]
+    } else {
+        let counts = app.accepts_count();
+        keys.feed(app.keymap(), key, now, counts)
+    };
+    for input in inputs {
+        if !dispatch(app, input)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Where the app draws.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Screen {
+    /// The alternate screen, which the shell's screen reappears from on exit.
+    #[default]
+    Full,
+    /// A fixed number of lines below the prompt, like a picker.
+    Inline(u16),
+}
+
+impl Screen {
+    /// Height of `--inline` without a number.
+    pub const INLINE_HEIGHT: u16 = 15;
+
+    /// `--inline` or `--inline=<lines>` from the command line.
+    ///
+    /// # Errors
+    /// Returns an error if the height is not a positive number.
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut screen = Self::Full;
+        for arg in args {
+            if arg == "--inline" {
+                screen = Self::Inline(Self::INLINE_HEIGHT);
+            } else if let Some(height) = arg.strip_prefix("--inline=") {
+                match height.parse() {
+                    Ok(height) if height > 0 => screen = Self::Inline(height),
+                    _ => return Err(format!("invalid --inline height `{height}`")),
+                }
+            }
+        }
+        Ok(screen)
+    }
+
+    #[must_use]
+    pub fn is_inline(self) -> bool {
+        matches!(self, Self::Inline(_))
+    }
+}
+
+/// How [`run_on`] set up the terminal, to undo it again.
+#[derive(Debug, Clone, Copy)]
+struct Setup {
+    screen: Screen,
+    mouse: bool,
+}
+
+impl Setup {
+    /// Put the terminal into raw mode, on the alternate screen unless
+    /// inline.
+    fn enter(self, title: &str) -> io::Result<()> {
+        enable_raw_mode()?;
+        let mut stdout = io::stdout();
+        if self.screen == Screen::Full {
+            execute!(stdout, EnterAlternateScreen)?;
+        }
+        execute!(stdout, EnableFocusChange, SetTitle(title))?;
+        if self.mouse {
+            execute!(stdout, EnableMouseCapture)?;
+        }
+        Ok(())
+    }
+
+    /// Give the terminal back in the state the shell expects. Every step
+    /// is tried, and the first failure is returned.
+    fn leave(self) -> io::Result<()> {
+        let mut stdout = io::stdout();
+        let raw = disable_raw_mode();
+        let captured = if self.mouse {
+            execute!(stdout, DisableMouseCapture)
+        } else {
+            Ok(())
+        };
+        let focus = execute!(stdout, DisableFocusChange, Show);
+        let screen = if self.screen == Screen::Full {
+            execute!(stdout, LeaveAlternateScreen)
+        } else {
+            Ok(())
+        };
+        raw.and(captured).and(focus).and(screen)
+    }
+}
+
+/// Points stdout at the terminal while it is redirected, as in
+/// `date=$(caltui --inline)`, so the app draws on screen and only
+/// [`App::output`] ends up in the pipe.
+struct TerminalStdout {
+    saved: Option<OwnedFd>,
+}
+
+impl TerminalStdout {
+    fn redirect() -> io::Result<Self> {
+        if io::stdout().is_terminal() {
+            return Ok(Self { saved: None });
+        }
+        let tty = OpenOptions::new().read(true).write(true).open("/dev/tty")?;
+        io::stdout().flush()?;
+        // SAFETY: dup and dup2 only copy descriptors, and the copy of the
+        // old stdout is owned by `saved` from here on
+        unsafe {
+            let saved = libc::dup(libc::STDOUT_FILENO);
+            if saved < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let saved = OwnedFd::from_raw_fd(saved);
+            if libc::dup2(tty.as_raw_fd(), libc::STDOUT_FILENO) < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(Self { saved: Some(saved) })
+        }
+    }
+}
+
+impl Drop for TerminalStdout {
+    fn drop(&mut self) {
+        if let Some(saved) = self.saved.take() {
+            let _ = io::stdout().flush();
+            // SAFETY: puts back the descriptor duplicated in `redirect`
+            unsafe { libc::dup2(saved.as_raw_fd(), libc::STDOUT_FILENO) };
+        }
+    }
+}
+
+/// The thread running the loop and how it set up the terminal, while the
+/// terminal is in raw mode.
+static ACTIVE: Mutex<Option<(ThreadId, Setup)>> = Mutex::new(None);
+
+/// Restore the terminal before the panic message is printed, so it is
+/// readable and the shell is usable afterwards. Panics on other threads,
+/// such as background tasks, leave the running app alone.
+fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let mut active = ACTIVE.lock().unwrap_or_else(PoisonError::into_inner);
+            if let Some((thread, setup)) = *active
+                && thread == thread::current().id()
+            {
+                *active = None;
+                let _ = setup.leave();
+            }
+            drop(active);
+            previous(info);
+        }));
+    });
+}
+
+fn set_active(active: Option<(ThreadId, Setup)>) {
+    *ACTIVE.lock().unwrap_or_else(PoisonError::into_inner) = active;
+}
+
+/// Run the TUI application event loop on the alternate screen.
+///
+/// The terminal is restored when the app returns an error or panics.
+/// Ctrl-Z suspends to the shell and `fg` brings the app back.
+///
+/// # Errors
+/// Returns an error if terminal setup, rendering, or event handling fails.
+pub fn run<A: App>(app: A) -> AppResult<()> {
+    run_on(app, Screen::Full)
+}
+
+/// Run the TUI application event loop on `screen`. Once the terminal is
+/// restored, [`App::output`] is printed to stdout.
 ///
 /// # Errors
 /// Returns an error if terminal setup, rendering, or event handling fails.
-pub fn run<A: App>(mut app: A) -> AppResult<()> {
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, SetTitle(app.title()))?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+pub fn run_on<A: App>(mut app: A, screen: Screen) -> AppResult<()> {
+    install_panic_hook();
+    trace::init();
+    let program = program().filter(|_| session::enabled());
+    if let Some(program) = &program
+        && let Some(state) = session::load(program, app.session_version())
+    {
+        app.restore_session(&state);
+    }
+    let stdout = TerminalStdout::redirect()?;
+    let setup = Setup {
+        screen,
+        mouse: app.mouse_capture(),
+    };
+    set_active(Some((thread::current().id(), setup)));
+    let result = setup.enter(app.title()).map_err(Into::into).and_then(|()| {
+        let viewport = match screen {
+            Screen::Full => Viewport::Fullscreen,
+            Screen::Inline(height) => Viewport::Inline(height),
+        };
+        let backend = CrosstermBackend::new(io::stdout());
+        let mut terminal = Terminal::with_options(backend, TerminalOptions { viewport })?;
+        let result = run_loop(&mut terminal, &mut app, setup);
+        if screen.is_inline() {
+            // The prompt comes back where the picker was
+            terminal.clear()?;
+        }
+        result
+    });
+    set_active(None);
+    let restored = setup.leave();
+    drop(stdout);
+
+    if let Some(program) = &program
+        && let Some(state) = app.save_session()
+        && let Err(e) = session::save(program, app.session_version(), &state)
+    {
+        eprintln!("warning: could not save the session: {e}");
+    }
+
+    // Report config problems once the screen is back to normal
+    for warning in Theme::load_warnings() {
+        eprintln!("warning: {warning}");
+    }
+
+    result.and(restored.map_err(Into::into))?;
+    if let Some(output) = app.output() {
+        println!("{output}");
+    }
+    Ok(())
+}
+
+/// The name the program was started as, for its socket, session and log
+/// files.
+pub(crate) fn program() -> Option<String> {
+    let arg = std::env::args_os().next()?;
+    let name = Path::new(&arg).file_name()?.to_str()?;
+    Some(name.to_string())
+}
 
-    let result = run_loop(&mut terminal, &mut app);
+fn is_suspend_key(key: &KeyEvent) -> bool {
+    key.code == KeyCode::Char('z') && key.modifiers.contains(KeyModifiers::CONTROL)
+}
 
-    disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-    terminal.show_cursor()?;
+/// Give the terminal back to the shell, stop until resumed with `fg` and
+/// take it back.
+fn suspend(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    title: &str,
+    setup: Setup,
+) -> io::Result<()> {
+    setup.leave()?;
+    signal_hook::low_level::emulate_default_handler(signal_hook::consts::SIGTSTP)?;
+    resume(terminal, title, setup)
+}
 
-    result
+/// Take the terminal back after being stopped, and repaint all of it.
+fn resume(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    title: &str,
+    setup: Setup,
+) -> io::Result<()> {
+    setup.enter(title)?;
+    terminal.clear()
 }
 
 fn run_loop<A: App>(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     app: &mut A,
+    setup: Setup,
 ) -> AppResult<()> {
+    let (events, rx) = Events::channel();
+    let _input = InputThread::spawn(events.clone());
+    let _signals = SignalThread::spawn(events.clone())?;
+    // Without the socket the app works as before, so failures are ignored
+    let _server = program()
+        .filter(|_| ipc::enabled())
+        .and_then(|program| ipc::Server::spawn(ipc::socket_path(&program), events.clone()).ok());
+    app.start(&events);
+
+    let mut keys = KeySequencer::new();
+    let mut redraw = true;
+    // Whether the loop stopped the process itself, see `suspend`
+    let mut self_stopped = false;
     loop {
-        terminal.draw(|f| app.render(f))?;
+        if redraw {
+            terminal.draw(|f| render(app, f))?;
+        }
 
-        if event::poll(std::time::Duration::from_millis(100))?
-            && let Event::Key(key) = event::read()?
-            && matches!(key.kind, KeyEventKind::Press | KeyEventKind::Repeat)
-        {
-            let action = if app.input_mode() {
-                KeyHandler::parse_input_mode(key)
-            } else {
-                KeyHandler::parse(key)
-            };
-            if !app.handle_action(action)? {
-                return Ok(());
+        // Sleep until an event arrives or the next deadline passes
+        let deadline = [app.next_tick(), keys.deadline(app.keymap())]
+            .into_iter()
+            .flatten()
+            .min();
+        let event = match deadline {
+            Some(deadline) => rx
+                .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                .ok(),
+            None => rx.recv().ok(),
+        };
+
+        match event {
+            // In raw mode the terminal sends Ctrl-Z as a key instead of
+            // stopping the process
+            Some(LoopEvent::Terminal(Event::Key(key)))
+                if key.kind == KeyEventKind::Press && is_suspend_key(&key) =>
+            {
+                keys.reset();
+                suspend(terminal, app.title(), setup)?;
+                self_stopped = true;
+            }
+            Some(LoopEvent::Suspend) => {
+                suspend(terminal, app.title(), setup)?;
+                self_stopped = true;
+            }
+            // The SIGCONT that ended our own stop finds the terminal set up
+            // already; only a stop from outside needs it taken back
+            Some(LoopEvent::Resume) => {
+                if !std::mem::take(&mut self_stopped) {
+                    resume(terminal, app.title(), setup)?;
+                }
             }
+            Some(LoopEvent::Terminal(Event::Key(key)))
+                if matches!(key.kind, KeyEventKind::Press | KeyEventKind::Repeat) =>
+            {
+                if !handle_key(app, &mut keys, key, Instant::now())? {
+                    return Ok(());
+                }
+            }
+            Some(LoopEvent::Terminal(Event::Mouse(event))) => {
+                let running = app.handle_mouse(event);
+                if !recover(app, running, true)? {
+                    return Ok(());
+                }
+            }
+            // Lay out again for the new size before the next draw
+            Some(LoopEvent::Terminal(Event::Resize(..))) => terminal.autoresize()?,
+            Some(LoopEvent::Request(request, reply)) => {
+                let (running, response) = ipc::answer(app, &request);
+                let _ = reply.send(response);
+                if !running {
+                    return Ok(());
+                }
+            }
+            Some(LoopEvent::Wake) | None => {}
+            Some(LoopEvent::InputError(e)) => return Err(e.into()),
+            // Notifications are only sent while the terminal is unfocused
+            Some(LoopEvent::Terminal(Event::FocusGained)) => {
+                notify::set_focused(true);
+                redraw = false;
+                continue;
+            }
+            Some(LoopEvent::Terminal(Event::FocusLost)) => {
+                notify::set_focused(false);
+                redraw = false;
+                continue;
+            }
+            // Key releases and pastes change nothing
+            Some(LoopEvent::Terminal(_)) => {
+                redraw = false;
+                continue;
+            }
+        }
+
+        if let Some(input) = keys.poll_timeout(app.keymap(), Instant::now())
+            && !dispatch(app, input)?
+        {
+            return Ok(());
         }
 
-        app.tick()?;
+        let ticked = tick(app);
+        recover(app, ticked, ())?;
+        redraw = true;
     }
 }
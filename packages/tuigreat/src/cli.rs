@@ -0,0 +1,233 @@
+//! Commands that run without the UI, for scripts, status bars and key
+//! bindings.
+//!
+//! A tool lists its commands as [`CommandSpec`]s named by one or more
+//! words (`sink list`, `connect`) and calls [`Cli::run`] first thing in
+//! `main`. When the first argument names a command, the handler's result
+//! is printed and the process exits; otherwise `main` goes on to start the
+//! UI. `--json` prints the result as JSON instead of plain text:
+//!
+//! ```text
+//! $ voltui sink list
+//! alsa_output.pci-0000_00_1f.3.analog-stereo  Built-in Audio  40  false  true
+//! $ voltui sink list --json
+//! [{"name":"alsa_output.pci-0000_00_1f.3.analog-stereo","description":"Built-in Audio",...}]
+//! ```
+
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use serde_json::Value;
+
+use crate::widgets::CommandSpec;
+use crate::{AppResult, ipc};
+
+/// Exit status for a command that failed.
+pub const EXIT_FAILURE: i32 = 1;
+/// Exit status for an unknown command or missing arguments.
+pub const EXIT_USAGE: i32 = 2;
+
+/// The commands of a tool.
+#[derive(Debug, Clone, Copy)]
+pub struct Cli {
+    program: &'static str,
+    commands: &'static [CommandSpec],
+}
+
+/// A command given on the command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Call {
+    pub name: &'static str,
+    pub args: Vec<String>,
+    /// Whether `--json` was given.
+    pub json: bool,
+    usage: &'static str,
+}
+
+impl Call {
+    /// The argument at `index`, or an error showing the usage.
+    ///
+    /// # Errors
+    /// Returns the usage if the argument is missing.
+    pub fn arg(&self, index: usize) -> Result<&str, String> {
+        self.args
+            .get(index)
+            .map(String::as_str)
+            .ok_or_else(|| self.usage_error())
+    }
+
+    /// Parse the argument at `index`, e.g. a volume.
+    ///
+    /// # Errors
+    /// Returns the usage if the argument is missing or does not parse.
+    pub fn parse<T: FromStr>(&self, index: usize) -> Result<T, String> {
+        self.arg(index)?.parse().map_err(|_| self.usage_error())
+    }
+
+    /// The arguments from `index` on as one string, for names with spaces
+    /// and expressions.
+    ///
+    /// # Errors
+    /// Returns the usage if there are no such arguments.
+    pub fn rest(&self, index: usize) -> Result<String, String> {
+        self.arg(index)?;
+        Ok(self.args[index..].join(" "))
+    }
+
+    #[must_use]
+    pub fn usage_error(&self) -> String {
+        format!("usage: {} {}", self.name, self.usage)
+    }
+}
+
+impl Cli {
+    #[must_use]
+    pub const fn new(program: &'static str, commands: &'static [CommandSpec]) -> Self {
+        Self { program, commands }
+    }
+
+    /// The command in `args` (program name first). `None` when the first
+    /// argument is not a command, so the UI should start; an error when it
+    /// starts one but does not match it.
+    ///
+    /// # Errors
+    /// Returns a message for an unknown command.
+    pub fn parse(&self, args: &[String]) -> Option<Result<Call, String>> {
+        let json = args.iter().any(|arg| arg == "--json");
+        let words: Vec<&str> = args
+            .iter()
+            .skip(1)
+            .filter(|arg| !arg.starts_with("--"))
+            .map(String::as_str)
+            .collect();
+        let first = *words.first()?;
+        if !self
+            .commands
+            .iter()
+            .any(|c| c.name.split(' ').next() == Some(first))
+        {
+            return None;
+        }
+
+        // The longest command whose words start the arguments
+        let spec = self
+            .commands
+            .iter()
+            .filter(|spec| {
+                let name: Vec<&str> = spec.name.split(' ').collect();
+                words.starts_with(&name)
+            })
+            .max_by_key(|spec| spec.name.split(' ').count());
+        Some(match spec {
+            Some(spec) => Ok(Call {
+                name: spec.name,
+                args: words[spec.name.split(' ').count()..]
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect(),
+                json,
+                usage: spec.args,
+            }),
+            None => Err(format!(
+                "unknown command `{}`, see `{} help`",
+                words.join(" "),
+                self.program
+            )),
+        })
+    }
+
+    /// The commands with their arguments, for `help`.
+    #[must_use]
+    pub fn usage(&self) -> String {
+        let mut usage = format!("Usage: {} [command] [--json]\n\nCommands:\n", self.program);
+        let width = self
+            .commands
+            .iter()
+            .map(|c| c.name.len() + c.args.len() + 1)
+            .max()
+            .unwrap_or(0);
+        for spec in self.commands {
+            let command = format!("{} {}", spec.name, spec.args);
+            let _ = writeln!(usage, "  {command:<width$}  {}", spec.description);
+        }
+        let _ = write!(
+            usage,
+            "\nWithout a command the interactive UI starts, and \
+             `{program} ctl <action|:command|state>` controls it while it runs. \
+             With `--debug` it logs the commands it runs to \
+             ~/.local/state/anytui/{program}.log.",
+            program = self.program
+        );
+        usage
+    }
+
+    /// Run the command in `args` with `handler`, print its result and
+    /// exit. Returns without a command, to start the UI.
+    pub fn run(&self, args: &[String], handler: impl FnOnce(&Call) -> AppResult<Value>) {
+        if args.get(1).is_some_and(|arg| arg == "help") {
+            let _ = writeln!(io::stdout(), "{}", self.usage());
+            std::process::exit(0);
+        }
+        if args.get(1).is_some_and(|arg| arg == "ctl") {
+            ipc::ctl(self.program, &args[2..]);
+        }
+        let Some(call) = self.parse(args) else {
+            return;
+        };
+        let status = match call {
+            Ok(call) => match handler(&call) {
+                Ok(value) => {
+                    let text = format_value(&value, call.json);
+                    if !text.is_empty() {
+                        let _ = writeln!(io::stdout(), "{text}");
+                    }
+                    0
+                }
+                Err(e) => {
+                    eprintln!("{}: {e}", self.program);
+                    EXIT_FAILURE
+                }
+            },
+            Err(e) => {
+                eprintln!("{}: {e}", self.program);
+                EXIT_USAGE
+            }
+        };
+        std::process::exit(status);
+    }
+}
+
+/// `value` as JSON, or as plain text: a line per list item, with the
+/// fields of objects separated by tabs, and `key: value` lines for a
+/// single object.
+#[must_use]
+pub fn format_value(value: &Value, json: bool) -> String {
+    if json {
+        return value.to_string();
+    }
+    match value {
+        Value::Array(items) => items
+            .iter()
+            .map(|item| match item {
+                Value::Object(fields) => fields.values().map(scalar).collect::<Vec<_>>().join("\t"),
+                other => scalar(other),
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Value::Object(fields) => fields
+            .iter()
+            .map(|(key, value)| format!("{key}: {}", scalar(value)))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        other => scalar(other),
+    }
+}
+
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
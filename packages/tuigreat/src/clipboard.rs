@@ -1,78 +1,332 @@
+//! Copying to and pasting from the system clipboard.
+//!
+//! Text goes through the first backend that works: `wl-copy` on Wayland,
+//! `xclip` or `xsel` on X11, and finally an OSC 52 escape sequence that
+//! asks the terminal itself to set the clipboard, which also works over
+//! SSH and (with passthrough) inside tmux. `ANYTUI_CLIPBOARD` sets the
+//! order as a comma separated list, e.g. `osc52,wl-copy`, and
+//! `ANYTUI_SELECTION` picks `clipboard`, `primary` or `both`.
+//!
+//! Pasting skips OSC 52, as reading the terminal's answer would race the
+//! event loop for input.
+
+use std::fmt;
 use std::io::Write;
 use std::process::{Command, Stdio};
 
-/// Copy text to system clipboard
-/// Tries wl-copy (Wayland) first, then xclip (X11)
-#[must_use]
-pub fn yank(text: &str) -> bool {
-    if text.is_empty() {
-        return false;
+use crate::{Error, osc, runner};
+
+/// A way to reach the clipboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// `wl-copy` and `wl-paste` (Wayland).
+    WlCopy,
+    /// `xclip` (X11).
+    Xclip,
+    /// `xsel` (X11).
+    Xsel,
+    /// `ESC ] 52 ; c ; base64 BEL`, handled by the terminal.
+    Osc52,
+}
+
+impl Backend {
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "wl-copy" | "wayland" => Some(Self::WlCopy),
+            "xclip" => Some(Self::Xclip),
+            "xsel" => Some(Self::Xsel),
+            "osc52" | "terminal" => Some(Self::Osc52),
+            _ => None,
+        }
     }
 
-    // Try wl-copy first (Wayland) - use stdin to avoid shell injection and ARG_MAX
-    let result = Command::new("wl-copy")
-        .stdin(Stdio::piped())
-        .stdout(Stdio::null())
-        .stderr(Stdio::null())
-        .spawn()
-        .and_then(|mut child| {
-            if let Some(mut stdin) = child.stdin.take() {
-                let _ = stdin.write_all(text.as_bytes());
-            }
-            child.wait()
-        });
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::WlCopy => "wl-copy",
+            Self::Xclip => "xclip",
+            Self::Xsel => "xsel",
+            Self::Osc52 => "osc52",
+        }
+    }
 
-    if result.as_ref().is_ok_and(std::process::ExitStatus::success) {
-        return true;
+    /// The backends in `list` (`osc52,xclip`), skipping unknown names.
+    #[must_use]
+    pub fn parse_list(list: &str) -> Vec<Self> {
+        list.split(',').filter_map(Self::from_name).collect()
     }
 
-    // Try xclip (X11)
-    if let Ok(mut child) = Command::new("xclip")
-        .args(["-selection", "clipboard"])
-        .stdin(Stdio::piped())
-        .stdout(Stdio::null())
-        .stderr(Stdio::null())
-        .spawn()
-        && let Some(stdin) = child.stdin.as_mut()
-        && stdin.write_all(text.as_bytes()).is_ok()
-    {
-        return child.wait().is_ok_and(|s| s.success());
+    /// The backends worth trying for the display servers the session
+    /// has, OSC 52 last.
+    #[must_use]
+    pub fn for_session(wayland: bool, x11: bool) -> Vec<Self> {
+        let mut backends = Vec::new();
+        if wayland {
+            backends.push(Self::WlCopy);
+        }
+        if x11 {
+            backends.extend([Self::Xclip, Self::Xsel]);
+        }
+        backends.push(Self::Osc52);
+        backends
     }
 
-    false
+    /// The program and arguments that copy to `selection`.
+    fn copy_command(self, selection: Selection) -> Option<(&'static str, Vec<&'static str>)> {
+        let primary = selection == Selection::Primary;
+        match self {
+            Self::WlCopy if primary => Some(("wl-copy", vec!["--primary"])),
+            Self::WlCopy => Some(("wl-copy", vec![])),
+            Self::Xclip => Some(("xclip", vec!["-selection", selection.x11_name()])),
+            Self::Xsel => Some(("xsel", vec![selection.xsel_flag(), "--input"])),
+            Self::Osc52 => None,
+        }
+    }
+
+    /// The program and arguments that print `selection`.
+    fn paste_command(self, selection: Selection) -> Option<(&'static str, Vec<&'static str>)> {
+        let primary = selection == Selection::Primary;
+        match self {
+            Self::WlCopy if primary => Some(("wl-paste", vec!["--no-newline", "--primary"])),
+            Self::WlCopy => Some(("wl-paste", vec!["--no-newline"])),
+            Self::Xclip => Some(("xclip", vec!["-selection", selection.x11_name(), "-o"])),
+            Self::Xsel => Some(("xsel", vec![selection.xsel_flag(), "--output"])),
+            Self::Osc52 => None,
+        }
+    }
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Which selection a yank fills.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Selection {
+    /// The clipboard, pasted with Ctrl-V.
+    #[default]
+    Clipboard,
+    /// The primary selection, pasted with the middle mouse button.
+    Primary,
+    /// Both; pastes come from the clipboard.
+    Both,
 }
 
-/// Paste text from system clipboard
-/// Tries wl-paste (Wayland) first, then xclip (X11)
+impl Selection {
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "clipboard" => Some(Self::Clipboard),
+            "primary" => Some(Self::Primary),
+            "both" => Some(Self::Both),
+            _ => None,
+        }
+    }
+
+    /// The single selections this one is made of.
+    fn parts(self) -> &'static [Selection] {
+        match self {
+            Self::Clipboard => &[Self::Clipboard],
+            Self::Primary => &[Self::Primary],
+            Self::Both => &[Self::Clipboard, Self::Primary],
+        }
+    }
+
+    fn x11_name(self) -> &'static str {
+        if self == Self::Primary {
+            "primary"
+        } else {
+            "clipboard"
+        }
+    }
+
+    fn xsel_flag(self) -> &'static str {
+        if self == Self::Primary {
+            "--primary"
+        } else {
+            "--clipboard"
+        }
+    }
+
+    /// The OSC 52 selection parameter.
+    fn osc52_name(self) -> &'static str {
+        match self {
+            Self::Clipboard => "c",
+            Self::Primary => "p",
+            Self::Both => "cp",
+        }
+    }
+}
+
+/// The clipboard backends to try, in order, and the selection to use.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Clipboard {
+    backends: Vec<Backend>,
+    selection: Selection,
+}
+
+impl Clipboard {
+    /// The backends from `ANYTUI_CLIPBOARD`, or those that fit the session,
+    /// and the selection from `ANYTUI_SELECTION`.
+    #[must_use]
+    pub fn detect() -> Self {
+        let backends = std::env::var("ANYTUI_CLIPBOARD")
+            .map(|list| Backend::parse_list(&list))
+            .ok()
+            .filter(|backends| !backends.is_empty())
+            .unwrap_or_else(|| {
+                let set = |var| std::env::var_os(var).is_some_and(|v| !v.is_empty());
+                Backend::for_session(set("WAYLAND_DISPLAY"), set("DISPLAY"))
+            });
+        let selection = std::env::var("ANYTUI_SELECTION")
+            .ok()
+            .and_then(|name| Selection::from_name(&name))
+            .unwrap_or_default();
+        Self {
+            backends,
+            selection,
+        }
+    }
+
+    #[must_use]
+    pub fn with_backends(mut self, backends: Vec<Backend>) -> Self {
+        self.backends = backends;
+        self
+    }
+
+    #[must_use]
+    pub fn with_selection(mut self, selection: Selection) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    #[must_use]
+    pub fn backends(&self) -> &[Backend] {
+        &self.backends
+    }
+
+    #[must_use]
+    pub fn selection(&self) -> Selection {
+        self.selection
+    }
+
+    /// Copy `text` with the first backend that works.
+    ///
+    /// # Errors
+    /// Returns an error naming the backends tried if none of them worked.
+    pub fn copy(&self, text: &str) -> Result<Backend, Error> {
+        if text.is_empty() {
+            return Err(Error::backend("Nothing to copy"));
+        }
+        if self.backends.is_empty() {
+            return Err(Error::backend(
+                "No clipboard backend (see ANYTUI_CLIPBOARD)",
+            ));
+        }
+        self.backends
+            .iter()
+            .copied()
+            .find(|&backend| self.copy_with(backend, text))
+            .ok_or_else(|| Error::backend(format!("No clipboard worked (tried {})", self.tried())))
+    }
+
+    /// The clipboard's text from the first backend that has any.
+    #[must_use]
+    pub fn paste(&self) -> Option<String> {
+        let selection = match self.selection {
+            Selection::Both => Selection::Clipboard,
+            selection => selection,
+        };
+        self.backends.iter().find_map(|backend| {
+            let (program, args) = backend.paste_command(selection)?;
+            runner::Command::new(program)
+                .args(args)
+                .run()
+                .ok()
+                .filter(|text| !text.is_empty())
+        })
+    }
+
+    fn copy_with(&self, backend: Backend, text: &str) -> bool {
+        if backend == Backend::Osc52 {
+            return osc::write(&osc52(text, self.selection)).is_ok();
+        }
+        self.selection.parts().iter().all(|&selection| {
+            backend
+                .copy_command(selection)
+                .is_some_and(|(program, args)| pipe_to(program, &args, text))
+        })
+    }
+
+    fn tried(&self) -> String {
+        let names: Vec<&str> = self.backends.iter().map(|b| b.name()).collect();
+        names.join(", ")
+    }
+}
+
+/// The OSC 52 sequence that sets `selection` to `text`.
+#[must_use]
+pub fn osc52(text: &str, selection: Selection) -> String {
+    format!(
+        "\x1b]52;{};{}\x07",
+        selection.osc52_name(),
+        base64(text.as_bytes())
+    )
+}
+
+/// Copy text to the system clipboard and return the backend that took it.
+///
+/// # Errors
+/// Returns an error if no [`Clipboard`] backend worked.
+pub fn yank(text: &str) -> Result<Backend, Error> {
+    Clipboard::detect().copy(text)
+}
+
+/// Paste text from the system clipboard.
 #[must_use]
 pub fn paste() -> Option<String> {
-    // Try wl-paste first (Wayland)
-    if let Ok(out) = Command::new("wl-paste")
-        .arg("--no-newline")
-        .stdin(Stdio::null())
+    Clipboard::detect().paste()
+}
+
+/// Write `text` to the program's stdin and wait for it to exit. The copy
+/// tools fork to serve the selection, so only the parent is waited on and
+/// its output is thrown away.
+fn pipe_to(program: &str, args: &[&str], text: &str) -> bool {
+    // Use stdin to avoid shell injection and ARG_MAX
+    if let Ok(mut child) = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
         .stderr(Stdio::null())
-        .output()
-        && out.status.success()
+        .spawn()
     {
-        let text = String::from_utf8_lossy(&out.stdout).to_string();
-        if !text.is_empty() {
-            return Some(text);
-        }
+        let written = child
+            .stdin
+            .take()
+            .is_some_and(|mut stdin| stdin.write_all(text.as_bytes()).is_ok());
+        return child.wait().is_ok_and(|s| s.success()) && written;
     }
+    false
+}
 
-    // Try xclip (X11)
-    if let Ok(out) = Command::new("xclip")
-        .args(["-selection", "clipboard", "-o"])
-        .stdin(Stdio::null())
-        .stderr(Stdio::null())
-        .output()
-        && out.status.success()
-    {
-        let text = String::from_utf8_lossy(&out.stdout).to_string();
-        if !text.is_empty() {
-            return Some(text);
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, &b)| {
+            group | (u32::from(b) << (16 - 8 * i))
+        });
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let index = (group >> (18 - 6 * i)) & 0x3f;
+                encoded.push(char::from(ALPHABET[index as usize]));
+            } else {
+                encoded.push('=');
+            }
         }
     }
-
-    None
+    encoded
 }
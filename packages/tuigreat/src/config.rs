@@ -0,0 +1,315 @@
+//! Shared configuration files under `~/.config/anytui/`.
+//!
+//! Config files use a small TOML subset: `[section]` headers, `key = value`
+//! pairs with string, integer, boolean and (possibly multi-line) array
+//! values, and `#` comments. That is all the suite needs, so we parse it
+//! ourselves instead of pulling in a full TOML stack.
+
+use std::fmt;
+use std::path::PathBuf;
+
+/// A parsed config value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    String(String),
+    Integer(i64),
+    Bool(bool),
+    Array(Vec<Value>),
+}
+
+impl Value {
+    /// Get the value as a string slice, if it is a string.
+    #[must_use]
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Get the value as a list of strings.
+    /// A single string is treated as a one-element list.
+    #[must_use]
+    pub fn as_str_list(&self) -> Option<Vec<&str>> {
+        match self {
+            Self::String(s) => Some(vec![s.as_str()]),
+            Self::Array(items) => items.iter().map(Self::as_str).collect(),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::String(s) => write!(f, "\"{s}\""),
+            Self::Integer(n) => write!(f, "{n}"),
+            Self::Bool(b) => write!(f, "{b}"),
+            Self::Array(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+/// A `[section]` and its entries, in file order.
+/// Entries before the first header belong to a section with an empty name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Section {
+    pub name: String,
+    pub entries: Vec<(String, Value)>,
+}
+
+/// A parsed config file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigFile {
+    pub sections: Vec<Section>,
+}
+
+impl ConfigFile {
+    /// Parse config text.
+    ///
+    /// # Errors
+    /// Returns a message with the offending line number on malformed input.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut sections = vec![Section::default()];
+        let mut lines = text.lines().enumerate();
+
+        while let Some((idx, raw)) = lines.next() {
+            let lineno = idx + 1;
+            let line = strip_comment(raw).trim().to_string();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(header) = line.strip_prefix('[') {
+                let name = header
+                    .strip_suffix(']')
+                    .ok_or_else(|| format!("line {lineno}: unterminated section header"))?;
+                sections.push(Section {
+                    name: unquote_key(name.trim()),
+                    entries: Vec::new(),
+                });
+                continue;
+            }
+
+            let (key, value) = split_key_value(&line)
+                .ok_or_else(|| format!("line {lineno}: expected `key = value`"))?;
+
+            // Arrays may continue over several lines until the brackets balance
+            let mut value = value.to_string();
+            while value.starts_with('[') && !brackets_closed(&value) {
+                let Some((_, next)) = lines.next() else {
+                    return Err(format!("line {lineno}: unterminated array"));
+                };
+                value.push(' ');
+                value.push_str(strip_comment(next).trim());
+            }
+
+            let parsed = parse_value(&value).map_err(|e| format!("line {lineno}: {e}"))?;
+            if let Some(section) = sections.last_mut() {
+                section.entries.push((unquote_key(key), parsed));
+            }
+        }
+
+        Ok(Self { sections })
+    }
+
+    /// Get all sections with the given name (empty name for top-level keys).
+    pub fn sections<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Section> + 'a {
+        self.sections.iter().filter(move |s| s.name == name)
+    }
+
+    /// Look up the last value for `key` in the named section.
+    #[must_use]
+    pub fn get(&self, section: &str, key: &str) -> Option<&Value> {
+        self.sections
+            .iter()
+            .filter(|s| s.name == section)
+            .flat_map(|s| s.entries.iter())
+            .rfind(|(k, _)| k == key)
+            .map(|(_, v)| v)
+    }
+}
+
+/// Directory holding the suite's config files
+/// (`$XDG_CONFIG_HOME/anytui`, falling back to `~/.config/anytui`).
+#[must_use]
+pub fn config_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")))
+        .map(|d| d.join("anytui"))
+}
+
+/// Load and parse a config file from [`config_dir`].
+///
+/// Returns `None` if the file does not exist, and `Some(Err)` if it exists
+/// but could not be read or parsed.
+#[must_use]
+pub fn load(file: &str) -> Option<Result<ConfigFile, String>> {
+    let path = config_dir()?.join(file);
+    if !path.exists() {
+        return None;
+    }
+    Some(
+        std::fs::read_to_string(&path)
+            .map_err(|e| format!("{}: {e}", path.display()))
+            .and_then(|text| ConfigFile::parse(&text).map_err(|e| format!("{file}: {e}"))),
+    )
+}
+
+/// Remove a trailing `#` comment, ignoring `#` inside quoted strings.
+fn strip_comment(line: &str) -> &str {
+    let mut quote: Option<char> = None;
+    let mut escape = false;
+    for (i, c) in line.char_indices() {
+        match quote {
+            Some(_) if escape => escape = false,
+            Some('"') if c == '\\' => escape = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '#' => return &line[..i],
+            None => {}
+        }
+    }
+    line
+}
+
+/// Split `key = value` at the first `=` outside a quoted key.
+fn split_key_value(line: &str) -> Option<(&str, &str)> {
+    let mut quote: Option<char> = None;
+    for (i, c) in line.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '=' => {
+                let key = line[..i].trim();
+                let value = line[i + 1..].trim();
+                if key.is_empty() || value.is_empty() {
+                    return None;
+                }
+                return Some((key, value));
+            }
+            None => {}
+        }
+    }
+    None
+}
+
+fn unquote_key(key: &str) -> String {
+    for q in ['"', '\''] {
+        if let Some(inner) = key.strip_prefix(q).and_then(|k| k.strip_suffix(q)) {
+            return inner.to_string();
+        }
+    }
+    key.to_string()
+}
+
+fn brackets_closed(value: &str) -> bool {
+    let mut depth = 0i32;
+    let mut quote: Option<char> = None;
+    for c in value.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '[' => depth += 1,
+            None if c == ']' => depth -= 1,
+            None => {}
+        }
+    }
+    depth <= 0
+}
+
+fn parse_value(text: &str) -> Result<Value, String> {
+    let mut chars = text.trim().chars().peekable();
+    let value = parse_value_from(&mut chars)?;
+    if chars.any(|c| !c.is_whitespace()) {
+        return Err(format!("trailing characters after value `{value}`"));
+    }
+    Ok(value)
+}
+
+fn parse_value_from(chars: &mut std::iter::Peekable<std::str::Chars>) -> Result<Value, String> {
+    while chars.peek().is_some_and(|c| c.is_whitespace()) {
+        chars.next();
+    }
+    match chars.peek().copied() {
+        Some('"') => {
+            chars.next();
+            let mut s = String::new();
+            loop {
+                match chars.next() {
+                    Some('"') => return Ok(Value::String(s)),
+                    Some('\\') => match chars.next() {
+                        Some('n') => s.push('\n'),
+                        Some('t') => s.push('\t'),
+                        Some(c) => s.push(c),
+                        None => return Err("unterminated string".to_string()),
+                    },
+                    Some(c) => s.push(c),
+                    None => return Err("unterminated string".to_string()),
+                }
+            }
+        }
+        Some('\'') => {
+            chars.next();
+            let mut s = String::new();
+            loop {
+                match chars.next() {
+                    Some('\'') => return Ok(Value::String(s)),
+                    Some(c) => s.push(c),
+                    None => return Err("unterminated string".to_string()),
+                }
+            }
+        }
+        Some('[') => {
+            chars.next();
+            let mut items = Vec::new();
+            loop {
+                while chars.peek().is_some_and(|c| c.is_whitespace() || *c == ',') {
+                    chars.next();
+                }
+                if chars.peek() == Some(&']') {
+                    chars.next();
+                    return Ok(Value::Array(items));
+                }
+                if chars.peek().is_none() {
+                    return Err("unterminated array".to_string());
+                }
+                items.push(parse_value_from(chars)?);
+            }
+        }
+        Some(_) => {
+            let mut word = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() || c == ',' || c == ']' {
+                    break;
+                }
+                word.push(c);
+                chars.next();
+            }
+            match word.as_str() {
+                "true" => Ok(Value::Bool(true)),
+                "false" => Ok(Value::Bool(false)),
+                _ => word
+                    .replace('_', "")
+                    .parse::<i64>()
+                    .map(Value::Integer)
+                    .map_err(|_| format!("invalid value `{word}`")),
+            }
+        }
+        None => Err("missing value".to_string()),
+    }
+}
@@ -0,0 +1,108 @@
+//! Which destructive operations ask before running.
+//!
+//! Each app names its operations and asks for a default set of them. The
+//! `confirm` list in `~/.config/anytui/confirm.toml` replaces that set,
+//! globally or per app:
+//!
+//! ```toml
+//! [global]
+//! confirm = ["remove", "restart", "down"]
+//!
+//! [kaltui]
+//! confirm = ["clear"]
+//!
+//! [blutui]
+//! confirm = []  # never ask
+//! ```
+
+use crate::config::{self, ConfigFile};
+use crate::widgets::ConfirmPopup;
+
+/// Name of the confirmation config file in the config dir.
+pub const CONFIRM_FILE: &str = "confirm.toml";
+
+/// The operations of an app that need confirming.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Confirmations {
+    ask: Vec<String>,
+    warnings: Vec<String>,
+}
+
+impl Confirmations {
+    /// Ask before `defaults` unless `confirm.toml` says otherwise.
+    #[must_use]
+    pub fn load(app: &str, defaults: &[&str]) -> Self {
+        match config::load(CONFIRM_FILE) {
+            None => Self::new(defaults),
+            Some(Ok(file)) => Self::from_config(&file, app, defaults),
+            Some(Err(e)) => {
+                let mut confirmations = Self::new(defaults);
+                confirmations.warnings.push(e);
+                confirmations
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn new(operations: &[&str]) -> Self {
+        Self {
+            ask: operations.iter().map(ToString::to_string).collect(),
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Apply the `[global]` and then the `[<app>]` section of a config.
+    #[must_use]
+    pub fn from_config(file: &ConfigFile, app: &str, defaults: &[&str]) -> Self {
+        let mut confirmations = Self::new(defaults);
+        for name in ["global", app] {
+            for section in file.sections(name) {
+                for (key, value) in &section.entries {
+                    if key != "confirm" {
+                        confirmations.warn(format!("[{name}] unknown setting `{key}`"));
+                        continue;
+                    }
+                    match value.as_str_list() {
+                        Some(ops) => {
+                            confirmations.ask = ops.into_iter().map(str::to_string).collect();
+                        }
+                        None => confirmations
+                            .warn(format!("[{name}] `confirm` must be a list of operations")),
+                    }
+                }
+            }
+        }
+        confirmations
+    }
+
+    fn warn(&mut self, message: String) {
+        self.warnings.push(format!("{CONFIRM_FILE}: {message}"));
+    }
+
+    /// Problems found while loading the config.
+    #[must_use]
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    #[must_use]
+    pub fn required(&self, operation: &str) -> bool {
+        self.ask.iter().any(|op| op == operation)
+    }
+
+    /// A popup asking to confirm `operation`, or `None` to run it straight
+    /// away.
+    #[must_use]
+    pub fn ask(&self, operation: &str, message: impl Into<String>) -> Option<ConfirmPopup> {
+        self.required(operation)
+            .then(|| ConfirmPopup::new(operation, message))
+    }
+
+    /// Note a confirmed popup. With "don't ask again" ticked, its operation
+    /// runs without asking for the rest of the session.
+    pub fn confirmed(&mut self, popup: &ConfirmPopup) {
+        if popup.dont_ask() {
+            self.ask.retain(|op| op != popup.operation());
+        }
+    }
+}
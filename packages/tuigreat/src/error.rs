@@ -0,0 +1,161 @@
+//! Errors from the programs and services behind an app.
+//!
+//! Backends return an [`Error`] instead of a bare string so the app can
+//! show it with the right [`StatusLevel`] and keep the full output of the
+//! failing command for a [`DetailPopup`](crate::widgets::DetailPopup).
+
+use std::fmt;
+use std::io;
+use std::process::Output;
+use std::time::Duration;
+
+use crate::StatusLevel;
+
+/// Lower-case phrases in stderr that mean the user lacks the rights.
+const PERMISSION_HINTS: &[&str] = &["permission denied", "not authorized", "not permitted"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The program is not installed.
+    MissingBinary(String),
+    /// The command ran but was refused, e.g. without sudo or polkit rights.
+    PermissionDenied { command: String, stderr: String },
+    /// The command failed. `message` is a one-line summary, `stderr` the
+    /// full error output (stdout for tools that report errors there).
+    Backend {
+        command: String,
+        message: String,
+        stderr: String,
+    },
+    /// Output of `what` could not be understood.
+    Parse { what: String, message: String },
+    /// The command did not finish in time.
+    Timeout { command: String, after: Duration },
+}
+
+impl Error {
+    /// A failure that did not come from running a command.
+    pub fn backend(message: impl Into<String>) -> Self {
+        Self::Backend {
+            command: String::new(),
+            message: message.into(),
+            stderr: String::new(),
+        }
+    }
+
+    pub fn parse(what: impl Into<String>, message: impl fmt::Display) -> Self {
+        Self::Parse {
+            what: what.into(),
+            message: message.to_string(),
+        }
+    }
+
+    /// The error of a command that could not be run at all.
+    #[must_use]
+    pub fn from_io(command: &str, error: &io::Error) -> Self {
+        let program = command.split_whitespace().next().unwrap_or(command);
+        match error.kind() {
+            io::ErrorKind::NotFound => Self::MissingBinary(program.to_string()),
+            io::ErrorKind::PermissionDenied => Self::PermissionDenied {
+                command: command.to_string(),
+                stderr: error.to_string(),
+            },
+            io::ErrorKind::TimedOut => Self::Timeout {
+                command: command.to_string(),
+                after: Duration::ZERO,
+            },
+            _ => Self::Backend {
+                command: command.to_string(),
+                message: error.to_string(),
+                stderr: String::new(),
+            },
+        }
+    }
+
+    /// The error of a command that exited unsuccessfully, or `None` if it
+    /// succeeded. Without stderr, stdout is taken as the error output.
+    #[must_use]
+    pub fn from_output(command: &str, output: &Output) -> Option<Self> {
+        if output.status.success() {
+            return None;
+        }
+        let mut stderr = String::from_utf8_lossy(&output.stderr)
+            .trim_end()
+            .to_string();
+        if stderr.is_empty() {
+            stderr = String::from_utf8_lossy(&output.stdout)
+                .trim_end()
+                .to_string();
+        }
+        let lower = stderr.to_lowercase();
+        if PERMISSION_HINTS.iter().any(|hint| lower.contains(hint)) {
+            return Some(Self::PermissionDenied {
+                command: command.to_string(),
+                stderr,
+            });
+        }
+        let message = match stderr.lines().find(|line| !line.trim().is_empty()) {
+            Some(line) => line.trim().to_string(),
+            None => match output.status.code() {
+                Some(code) => format!("`{command}` exited with status {code}"),
+                None => format!("`{command}` was killed"),
+            },
+        };
+        Some(Self::Backend {
+            command: command.to_string(),
+            message,
+            stderr,
+        })
+    }
+
+    /// How the error is shown in the status bar. Missing tools and slow
+    /// services are worth a warning, the rest are errors.
+    #[must_use]
+    pub fn level(&self) -> StatusLevel {
+        match self {
+            Self::MissingBinary(_) | Self::Timeout { .. } => StatusLevel::Warning,
+            Self::PermissionDenied { .. } | Self::Backend { .. } | Self::Parse { .. } => {
+                StatusLevel::Error
+            }
+        }
+    }
+
+    /// The failing command line and its full error output, if there is
+    /// more to see than the one-line message.
+    #[must_use]
+    pub fn detail(&self) -> Option<String> {
+        let (command, stderr) = match self {
+            Self::PermissionDenied { command, stderr }
+            | Self::Backend {
+                command, stderr, ..
+            } => (command, stderr),
+            Self::MissingBinary(_) | Self::Parse { .. } | Self::Timeout { .. } => return None,
+        };
+        if stderr.is_empty() {
+            return None;
+        }
+        if command.is_empty() {
+            return Some(stderr.clone());
+        }
+        Some(format!("$ {command}\n{stderr}"))
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingBinary(program) => write!(f, "`{program}` not found, is it installed?"),
+            Self::PermissionDenied { command, .. } => write!(f, "Permission denied: {command}"),
+            Self::Backend { message, .. } => f.write_str(message),
+            Self::Parse { what, message } => write!(f, "Could not parse {what}: {message}"),
+            Self::Timeout { command, after } if after.is_zero() => {
+                write!(f, "`{command}` timed out")
+            }
+            Self::Timeout { command, after } => {
+                write!(f, "`{command}` timed out after {}s", after.as_secs())
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
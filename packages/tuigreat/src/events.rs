@@ -0,0 +1,276 @@
+//! Event sources that wake the app loop.
+//!
+//! The loop sleeps until a key press, a wake-up from an [`Events`] handle or
+//! the deadline from [`App::next_tick`](crate::App::next_tick), so idle apps
+//! use no CPU and background results show up immediately.
+
+use std::io::{self, BufRead, BufReader, Read};
+use std::process::{Child, Command, Stdio};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crossterm::event::{self, Event};
+use serde_json::Value;
+use signal_hook::consts::{SIGCONT, SIGTSTP};
+use signal_hook::iterator::{Handle, Signals};
+
+use crate::ipc::Request;
+
+/// How often the input thread checks whether the loop has stopped.
+const INPUT_POLL: Duration = Duration::from_millis(200);
+
+pub(crate) enum LoopEvent {
+    Terminal(Event),
+    Wake,
+    InputError(io::Error),
+    /// `SIGTSTP` from outside, e.g. `kill -TSTP`.
+    Suspend,
+    /// `SIGCONT` after the process was stopped.
+    Resume,
+    /// A request from the control socket and where to send the response.
+    Request(Request, Sender<Value>),
+}
+
+/// Handle for waking the app loop from background threads.
+///
+/// Apps get one in [`App::start`](crate::App::start). The default handle is
+/// not attached to a loop and its wake-ups go nowhere.
+#[derive(Debug, Clone, Default)]
+pub struct Events {
+    tx: Option<Sender<LoopEvent>>,
+}
+
+impl Events {
+    pub(crate) fn channel() -> (Self, Receiver<LoopEvent>) {
+        let (tx, rx) = mpsc::channel();
+        (Self { tx: Some(tx) }, rx)
+    }
+
+    fn send(&self, event: LoopEvent) -> bool {
+        self.tx.as_ref().is_some_and(|tx| tx.send(event).is_ok())
+    }
+
+    /// Wake the loop so it calls [`App::tick`](crate::App::tick) and redraws.
+    pub fn wake(&self) {
+        self.send(LoopEvent::Wake);
+    }
+
+    /// Pass a control socket request to the loop. The response arrives on
+    /// the returned receiver, or `None` if no loop is attached.
+    pub(crate) fn request(&self, request: Request) -> Option<Receiver<Value>> {
+        let (tx, rx) = mpsc::channel();
+        self.send(LoopEvent::Request(request, tx)).then_some(rx)
+    }
+
+    /// Relay a channel through a thread that wakes the loop for every
+    /// message. Read the returned receiver with `try_recv` in `tick`.
+    #[must_use]
+    pub fn forward<T: Send + 'static>(&self, rx: Receiver<T>) -> Receiver<T> {
+        let (tx, relayed) = mpsc::channel();
+        let events = self.clone();
+        thread::spawn(move || {
+            for message in rx {
+                if tx.send(message).is_err() {
+                    return;
+                }
+                events.wake();
+            }
+            // Wake once more so the app sees the channel disconnect
+            drop(tx);
+            events.wake();
+        });
+        relayed
+    }
+
+    /// Read lines from `reader` on a thread, waking the loop for each one.
+    /// The receiver disconnects at end of input.
+    #[must_use]
+    pub fn read_lines<R: Read + Send + 'static>(&self, reader: R) -> Receiver<String> {
+        let (tx, rx) = mpsc::channel();
+        let events = self.clone();
+        thread::spawn(move || {
+            for line in BufReader::new(reader).lines() {
+                let Ok(line) = line else { break };
+                if tx.send(line).is_err() {
+                    return;
+                }
+                events.wake();
+            }
+            drop(tx);
+            events.wake();
+        });
+        rx
+    }
+
+    /// Spawn a long-running command (`pactl subscribe`) and receive its
+    /// output lines. The receiver disconnects when the command exits, and
+    /// dropping the [`Lines`] stops it.
+    ///
+    /// # Errors
+    /// Returns an error if the command cannot be started.
+    pub fn lines(&self, command: &mut Command) -> io::Result<Lines> {
+        let mut child = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        let Some(stdout) = child.stdout.take() else {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(io::ErrorKind::BrokenPipe.into());
+        };
+        let rx = self.read_lines(stdout);
+        Ok(Lines { child, rx })
+    }
+}
+
+/// The output of a command started with [`Events::lines`]. The command is
+/// killed and reaped when this is dropped.
+#[derive(Debug)]
+pub struct Lines {
+    child: Child,
+    rx: Receiver<String>,
+}
+
+impl Lines {
+    #[must_use]
+    pub fn receiver(&self) -> &Receiver<String> {
+        &self.rx
+    }
+
+    /// The process ID of the command.
+    #[must_use]
+    pub fn id(&self) -> u32 {
+        self.child.id()
+    }
+}
+
+impl Drop for Lines {
+    fn drop(&mut self) {
+        // Fails harmlessly if the command already exited
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// A repeating timer for periodic background work.
+///
+/// Return [`Interval::deadline`] from `next_tick` and check
+/// [`Interval::due`] in `tick`.
+#[derive(Debug, Clone, Copy)]
+pub struct Interval {
+    period: Duration,
+    next: Instant,
+}
+
+impl Interval {
+    /// A timer that first fires one `period` from now.
+    #[must_use]
+    pub fn new(period: Duration) -> Self {
+        Self {
+            period,
+            next: Instant::now() + period,
+        }
+    }
+
+    /// Whether the timer has fired; if so, start the next period.
+    pub fn due(&mut self, now: Instant) -> bool {
+        if now < self.next {
+            return false;
+        }
+        self.next = now + self.period;
+        true
+    }
+
+    /// Start the period again from `now`.
+    pub fn reset(&mut self, now: Instant) {
+        self.next = now + self.period;
+    }
+
+    /// When the timer fires next.
+    #[must_use]
+    pub fn deadline(&self) -> Instant {
+        self.next
+    }
+}
+
+/// Reads terminal events on a thread until dropped.
+pub(crate) struct InputThread {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl InputThread {
+    pub(crate) fn spawn(events: Events) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = Arc::clone(&stop);
+        let handle = thread::spawn(move || {
+            while !stopped.load(Ordering::Relaxed) {
+                let event = match event::poll(INPUT_POLL) {
+                    Ok(false) => continue,
+                    Ok(true) => event::read().map(LoopEvent::Terminal),
+                    Err(e) => Err(e),
+                };
+                let event = event.unwrap_or_else(LoopEvent::InputError);
+                let failed = matches!(event, LoopEvent::InputError(_));
+                if !events.send(event) || failed {
+                    return;
+                }
+            }
+        });
+        Self {
+            stop,
+            handle: Some(handle),
+        }
+    }
+}
+
+impl Drop for InputThread {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Turns job control signals into loop events until dropped.
+pub(crate) struct SignalThread {
+    handle: Handle,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl SignalThread {
+    pub(crate) fn spawn(events: Events) -> io::Result<Self> {
+        let mut signals = Signals::new([SIGTSTP, SIGCONT])?;
+        let handle = signals.handle();
+        let thread = thread::spawn(move || {
+            for signal in signals.forever() {
+                let event = if signal == SIGTSTP {
+                    LoopEvent::Suspend
+                } else {
+                    LoopEvent::Resume
+                };
+                if !events.send(event) {
+                    return;
+                }
+            }
+        });
+        Ok(Self {
+            handle,
+            thread: Some(thread),
+        })
+    }
+}
+
+impl Drop for SignalThread {
+    fn drop(&mut self) {
+        self.handle.close();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
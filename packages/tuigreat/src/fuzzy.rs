@@ -0,0 +1,134 @@
+//! fzf-style fuzzy matching.
+//!
+//! A pattern matches when its characters appear in order in the text,
+//! ignoring case. Matches are scored like fzf's v1 algorithm: the shortest
+//! window ending at the first full match is found, then each matched
+//! character earns points, with bonuses for word starts and runs of
+//! consecutive characters and penalties for gaps in between.
+
+const SCORE_MATCH: i64 = 16;
+const PENALTY_GAP_START: i64 = -3;
+const PENALTY_GAP_EXTENSION: i64 = -1;
+/// After a separator such as a space, `/`, `-` or `_`, or at the start.
+const BONUS_BOUNDARY: i64 = 8;
+/// A lowercase to uppercase or letter to digit transition.
+const BONUS_CAMEL: i64 = 7;
+const BONUS_CONSECUTIVE: i64 = 4;
+/// The first pattern character's bonus counts this many times.
+const BONUS_FIRST_MULTIPLIER: i64 = 2;
+
+/// A successful match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    /// Higher is better.
+    pub score: i64,
+    /// Indices of the matched characters in the text, in chars.
+    pub positions: Vec<usize>,
+}
+
+/// Match `pattern` against `text`, ignoring case.
+///
+/// An empty pattern matches everything with a score of zero.
+#[must_use]
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<FuzzyMatch> {
+    let pattern: Vec<char> = pattern.chars().map(fold).collect();
+    if pattern.is_empty() {
+        return Some(FuzzyMatch {
+            score: 0,
+            positions: Vec::new(),
+        });
+    }
+    let chars: Vec<char> = text.chars().collect();
+    let folded: Vec<char> = chars.iter().copied().map(fold).collect();
+
+    // Forward: the earliest point where the whole pattern has been seen
+    let mut next = 0;
+    let mut end = None;
+    for (i, &c) in folded.iter().enumerate() {
+        if c == pattern[next] {
+            next += 1;
+            if next == pattern.len() {
+                end = Some(i);
+                break;
+            }
+        }
+    }
+    let end = end?;
+
+    // Backward: the latest start that still fits the pattern before `end`
+    let mut remaining = pattern.len();
+    let mut start = end;
+    for i in (0..=end).rev() {
+        if folded[i] == pattern[remaining - 1] {
+            remaining -= 1;
+            if remaining == 0 {
+                start = i;
+                break;
+            }
+        }
+    }
+
+    let mut score = 0;
+    let mut positions = Vec::with_capacity(pattern.len());
+    let mut next = 0;
+    let mut in_gap = false;
+    for (i, &c) in folded.iter().enumerate().take(end + 1).skip(start) {
+        if next < pattern.len() && c == pattern[next] {
+            let bonus = bonus(&chars, i);
+            score += SCORE_MATCH;
+            score += if positions.last().is_some_and(|&p| p + 1 == i) {
+                bonus.max(BONUS_CONSECUTIVE)
+            } else if next == 0 {
+                bonus * BONUS_FIRST_MULTIPLIER
+            } else {
+                bonus
+            };
+            positions.push(i);
+            next += 1;
+            in_gap = false;
+        } else {
+            score += if in_gap {
+                PENALTY_GAP_EXTENSION
+            } else {
+                PENALTY_GAP_START
+            };
+            in_gap = true;
+        }
+    }
+
+    Some(FuzzyMatch { score, positions })
+}
+
+/// Positions of the first case-insensitive occurrence of `pattern` in
+/// `text`, in chars.
+#[must_use]
+pub fn substring_match(pattern: &str, text: &str) -> Option<Vec<usize>> {
+    let pattern: Vec<char> = pattern.chars().map(fold).collect();
+    let folded: Vec<char> = text.chars().map(fold).collect();
+    if pattern.len() > folded.len() {
+        return None;
+    }
+    let start = (0..=folded.len() - pattern.len())
+        .find(|&i| folded[i..i + pattern.len()] == pattern[..])?;
+    Some((start..start + pattern.len()).collect())
+}
+
+fn fold(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+fn bonus(chars: &[char], i: usize) -> i64 {
+    let Some(&prev) = i.checked_sub(1).and_then(|p| chars.get(p)) else {
+        return BONUS_BOUNDARY;
+    };
+    let c = chars[i];
+    if !prev.is_alphanumeric() && c.is_alphanumeric() {
+        BONUS_BOUNDARY
+    } else if (prev.is_lowercase() && c.is_uppercase())
+        || (!prev.is_ascii_digit() && c.is_ascii_digit())
+    {
+        BONUS_CAMEL
+    } else {
+        0
+    }
+}
@@ -0,0 +1,362 @@
+//! Several apps in one terminal, switched like tabs.
+//!
+//! A [`Hub`] is an [`App`] made of other apps. The selected one gets the
+//! keys and draws between the hub's tab bar and a status line summing up
+//! every app; Alt-1 to Alt-9 or a click on a tab switch between them. Apps
+//! in the background keep running: each one ticks when its own
+//! [`App::next_tick`] passes, or on every wake-up if it has none.
+//! Requests from the [control socket](crate::ipc) go to the app they name,
+//! or else to the selected one. The hub's [session](crate::session) keeps
+//! the selected tab and the session of every app.
+
+use std::time::Instant;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent};
+use ratatui::{
+    Frame, Terminal,
+    backend::TestBackend,
+    buffer::Buffer,
+    layout::{Constraint, Direction, Layout, Position, Rect},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+
+use serde_json::{Map, Value, json};
+
+use crate::app::AppResult;
+use crate::events::Events;
+use crate::ipc::{Op, Request};
+use crate::widgets::Tabs;
+use crate::{Action, App, Keymap, MessageLog, StatusMessage, Theme};
+use crate::{session, trace};
+
+/// An app in the hub and the name on its tab.
+struct Pane {
+    name: String,
+    app: Box<dyn App>,
+}
+
+/// Apps shown one at a time under a tab bar.
+pub struct Hub {
+    panes: Vec<Pane>,
+    tabs: Tabs,
+    theme: Theme,
+    messages: MessageLog,
+    // Off-screen terminal the selected app draws into, to place it between
+    // the tab bar and the status line
+    screen: Terminal<TestBackend>,
+    // Screen area of the selected app, for mouse events
+    area: Rect,
+}
+
+impl Default for Hub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hub {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            panes: Vec::new(),
+            tabs: Tabs::new(Vec::new()),
+            theme: Theme::default(),
+            messages: MessageLog::new(),
+            screen: Terminal::new(TestBackend::new(0, 0)).expect("the test backend cannot fail"),
+            area: Rect::default(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_app(mut self, name: impl Into<String>, app: impl App + 'static) -> Self {
+        self.add(name, app);
+        self
+    }
+
+    /// Add `app` with `name` on its tab, after the apps added so far.
+    pub fn add(&mut self, name: impl Into<String>, app: impl App + 'static) {
+        self.panes.push(Pane {
+            name: name.into(),
+            app: Box::new(app),
+        });
+        let names = self.panes.iter().map(|p| p.name.clone()).collect();
+        let selected = self.tabs.selected();
+        self.tabs = Tabs::new(names).with_app_title("anytui");
+        self.tabs.select(selected);
+    }
+
+    /// Show `message` in the status line, e.g. an app that failed to start.
+    pub fn push_message(&mut self, message: impl Into<StatusMessage>) {
+        self.messages.push(message);
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.panes.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.panes.is_empty()
+    }
+
+    /// Index of the app that has the keys.
+    #[must_use]
+    pub fn selected(&self) -> usize {
+        self.tabs.selected()
+    }
+
+    pub fn select(&mut self, index: usize) {
+        self.tabs.select(index);
+    }
+
+    fn active(&self) -> Option<&dyn App> {
+        self.panes.get(self.selected()).map(|p| p.app.as_ref())
+    }
+
+    fn active_mut(&mut self) -> Option<&mut Box<dyn App>> {
+        let selected = self.selected();
+        self.panes.get_mut(selected).map(|p| &mut p.app)
+    }
+
+    /// The hub's own message, or else each app's summary.
+    fn status_line(&self) -> Line<'static> {
+        if let Some(message) = self.messages.current() {
+            return message.to_line(&self.theme);
+        }
+        let mut spans = vec![Span::raw(" ")];
+        for pane in &self.panes {
+            let Some(summary) = pane.app.summary() else {
+                continue;
+            };
+            if spans.len() > 1 {
+                spans.push(Span::styled(" │ ", self.theme.border()));
+            }
+            spans.push(Span::styled(format!("{} ", pane.name), self.theme.muted()));
+            spans.push(Span::styled(summary, self.theme.normal()));
+        }
+        Line::from(spans)
+    }
+}
+
+/// Render `app` into `area` of `buffer` through the off-screen `screen`.
+/// Returns where `app` put the cursor, in `buffer`.
+fn draw_into(
+    screen: &mut Terminal<TestBackend>,
+    app: &mut dyn App,
+    buffer: &mut Buffer,
+    area: Rect,
+) -> Option<Position> {
+    if area.is_empty() {
+        return None;
+    }
+    if screen.backend().buffer().area.as_size() != area.as_size() {
+        screen.backend_mut().resize(area.width, area.height);
+    }
+    // The cursor only moves off the screen if the app sets it
+    let hidden = Position::new(u16::MAX, u16::MAX);
+    screen.set_cursor_position(hidden).ok()?;
+    let frame = screen.draw(|f| app.render(f)).ok()?;
+    for y in 0..area.height {
+        for x in 0..area.width {
+            if let Some(cell) = frame.buffer.cell((x, y))
+                && let Some(target) = buffer.cell_mut((area.x + x, area.y + y))
+            {
+                target.clone_from(cell);
+            }
+        }
+    }
+    let cursor = screen.get_cursor_position().ok()?;
+    (cursor != hidden).then(|| Position::new(area.x + cursor.x, area.y + cursor.y))
+}
+
+impl App for Hub {
+    fn title(&self) -> &'static str {
+        "anytui"
+    }
+
+    fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    fn keymap(&self) -> &Keymap {
+        match self.active() {
+            Some(app) => app.keymap(),
+            None => Keymap::defaults(),
+        }
+    }
+
+    fn handle_action(&mut self, action: Action) -> AppResult<bool> {
+        match self.active_mut() {
+            Some(app) => app.handle_action(action),
+            None => Ok(action != Action::Quit),
+        }
+    }
+
+    fn handle_action_count(&mut self, action: Action, count: usize) -> AppResult<bool> {
+        match self.active_mut() {
+            Some(app) => app.handle_action_count(action, count),
+            None => Ok(action != Action::Quit),
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Min(0),
+                Constraint::Length(1),
+            ])
+            .split(frame.area());
+        self.tabs.render(frame, chunks[0], &self.theme);
+        self.area = chunks[1];
+        let selected = self.selected();
+        if let Some(pane) = self.panes.get_mut(selected) {
+            let cursor = draw_into(
+                &mut self.screen,
+                pane.app.as_mut(),
+                frame.buffer_mut(),
+                chunks[1],
+            );
+            if let Some(cursor) = cursor {
+                frame.set_cursor_position(cursor);
+            }
+        }
+        frame.render_widget(Paragraph::new(self.status_line()), chunks[2]);
+    }
+
+    fn start(&mut self, events: &Events) {
+        for pane in &mut self.panes {
+            pane.app.start(events);
+        }
+    }
+
+    fn tick(&mut self) -> AppResult<()> {
+        let now = Instant::now();
+        let selected = self.selected();
+        for (i, pane) in self.panes.iter_mut().enumerate() {
+            if i == selected || pane.app.next_tick().is_none_or(|at| at <= now) {
+                let started = Instant::now();
+                let ticked = pane.app.tick();
+                trace::tick(&pane.name, started.elapsed());
+                ticked.or_else(|error| pane.app.report_error(error))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn next_tick(&self) -> Option<Instant> {
+        self.panes
+            .iter()
+            .filter_map(|pane| pane.app.next_tick())
+            .chain(self.messages.next_expiry())
+            .min()
+    }
+
+    fn input_mode(&self) -> bool {
+        self.active().is_some_and(App::input_mode)
+    }
+
+    fn accepts_count(&self) -> bool {
+        self.active().is_none_or(App::accepts_count)
+    }
+
+    fn mouse_capture(&self) -> bool {
+        self.panes.iter().any(|pane| pane.app.mouse_capture())
+    }
+
+    fn handle_mouse(&mut self, event: MouseEvent) -> AppResult<bool> {
+        if self.tabs.handle_mouse(&event) {
+            return Ok(true);
+        }
+        let area = self.area;
+        if !area.contains(Position::new(event.column, event.row)) {
+            return Ok(true);
+        }
+        match self.active_mut() {
+            Some(app) if app.mouse_capture() => app.handle_mouse(MouseEvent {
+                column: event.column - area.x,
+                row: event.row - area.y,
+                ..event
+            }),
+            _ => Ok(true),
+        }
+    }
+
+    fn report_error(&mut self, error: Box<dyn std::error::Error>) -> AppResult<()> {
+        match self.active_mut() {
+            Some(app) => app.report_error(error),
+            None => Err(error),
+        }
+    }
+
+    fn handle_request(&mut self, request: &Request) -> AppResult<(bool, Value)> {
+        if let Some(name) = &request.app {
+            let pane = self
+                .panes
+                .iter_mut()
+                .find(|pane| pane.name == *name)
+                .ok_or_else(|| format!("{name} is not open in the hub"))?;
+            return pane.app.handle_request(&Request::new(request.op.clone()));
+        }
+        if request.op == Op::State {
+            let apps: Map<String, Value> = self
+                .panes
+                .iter()
+                .map(|pane| (pane.name.clone(), pane.app.state()))
+                .collect();
+            let selected = self.panes.get(self.selected()).map(|pane| &pane.name);
+            return Ok((true, json!({"selected": selected, "apps": apps})));
+        }
+        match self.active_mut() {
+            Some(app) => app.handle_request(request),
+            None => Err("no app is open".into()),
+        }
+    }
+
+    fn save_session(&self) -> Option<Value> {
+        let apps: Map<String, Value> = self
+            .panes
+            .iter()
+            .filter_map(|pane| {
+                let state = pane.app.save_session()?;
+                let version = pane.app.session_version();
+                Some((pane.name.clone(), session::wrap(&state, version)))
+            })
+            .collect();
+        let selected = self.panes.get(self.selected()).map(|pane| &pane.name);
+        Some(json!({"selected": selected, "apps": apps}))
+    }
+
+    fn restore_session(&mut self, saved: &Value) {
+        for pane in &mut self.panes {
+            let version = pane.app.session_version();
+            if let Some(state) = session::unwrap(&saved["apps"][&pane.name], version) {
+                pane.app.restore_session(state);
+            }
+        }
+        if let Some(name) = saved["selected"].as_str()
+            && let Some(index) = self.panes.iter().position(|pane| pane.name == name)
+        {
+            self.select(index);
+        }
+    }
+
+    fn output(&self) -> Option<String> {
+        self.active()?.output()
+    }
+
+    fn intercept_key(&mut self, key: KeyEvent) -> Option<AppResult<bool>> {
+        // Alt-1 to Alt-9 switch apps
+        if key.modifiers.contains(KeyModifiers::ALT)
+            && let KeyCode::Char(c) = key.code
+            && let Some(index) = ('1'..='9').position(|digit| digit == c)
+        {
+            self.select(index);
+            return Some(Ok(true));
+        }
+        self.active_mut()?.intercept_key(key)
+    }
+}
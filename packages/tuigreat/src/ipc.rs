@@ -0,0 +1,389 @@
+//! Control a running app over a Unix socket, for media keys and window
+//! manager bindings.
+//!
+//! While the UI runs it listens on `$XDG_RUNTIME_DIR/anytui/<program>.sock`
+//! (set `ANYTUI_IPC=off` to disable). Clients write one JSON object per
+//! line and read one back:
+//!
+//! ```text
+//! {"action":"volume_up","count":2}   {"ok":true,"result":null}
+//! {"command":"volume 40"}            {"ok":true,"result":null}
+//! {"state":true}                     {"ok":true,"result":{"title":"vol-tui","summary":"40%"}}
+//! {"action":"bogus"}                 {"ok":false,"error":"unknown action `bogus`"}
+//! ```
+//!
+//! An `"app"` field picks a tool inside the [`Hub`](crate::Hub). The
+//! request runs on the UI thread between two frames, so the screen shows
+//! the result at once. `<program> ctl` is the client, see [`ctl`].
+
+use std::fs::{self, DirBuilder, Permissions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use serde_json::{Value, json};
+
+use crate::app::AppResult;
+use crate::cli::{self, EXIT_FAILURE, EXIT_USAGE};
+use crate::events::Events;
+use crate::{Action, App, CountedAction, trace};
+
+/// How long a client waits for the app to answer.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The program whose socket tools fall back to when they run in the hub.
+const HUB: &str = "anytui";
+
+/// What a request asks of the app.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Op {
+    /// Run an action as if its key was pressed, with an optional count.
+    Action(Action, Option<usize>),
+    /// Run a `:` command line, e.g. `volume 40`.
+    Command(String),
+    /// Describe the app, see [`App::state`].
+    State,
+}
+
+/// A request from a client.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    /// The tool in the hub to send it to; the selected one if `None`.
+    pub app: Option<String>,
+    pub op: Op,
+}
+
+impl Request {
+    #[must_use]
+    pub fn new(op: Op) -> Self {
+        Self { app: None, op }
+    }
+
+    #[must_use]
+    pub fn with_app(mut self, app: impl Into<String>) -> Self {
+        self.app = Some(app.into());
+        self
+    }
+
+    /// The request in a line from a client.
+    ///
+    /// # Errors
+    /// Returns a message if the line is not a request.
+    pub fn from_json(line: &str) -> Result<Self, String> {
+        let value: Value = serde_json::from_str(line).map_err(|e| format!("invalid JSON: {e}"))?;
+        let app = value["app"].as_str().map(ToString::to_string);
+        let op = if let Some(name) = value["action"].as_str() {
+            let count = match &value["count"] {
+                Value::Null => None,
+                count => Some(
+                    count
+                        .as_u64()
+                        .and_then(|c| usize::try_from(c).ok())
+                        .filter(|&c| c > 0)
+                        .ok_or("count must be a positive number")?,
+                ),
+            };
+            Op::Action(parse_action(name)?, count)
+        } else if let Some(line) = value["command"].as_str() {
+            Op::Command(line.to_string())
+        } else if value["state"].as_bool() == Some(true) {
+            Op::State
+        } else {
+            return Err("expected \"action\", \"command\" or \"state\"".to_string());
+        };
+        Ok(Self { app, op })
+    }
+
+    #[must_use]
+    pub fn to_json(&self) -> Value {
+        let mut value = match &self.op {
+            Op::Action(action, None) => json!({"action": action.name()}),
+            Op::Action(action, Some(count)) => json!({"action": action.name(), "count": count}),
+            Op::Command(line) => json!({"command": line}),
+            Op::State => json!({"state": true}),
+        };
+        if let Some(app) = &self.app {
+            value["app"] = json!(app);
+        }
+        value
+    }
+
+    /// The request in the words after `ctl`: an action and a count
+    /// (`volume-up 5`), a command (`:volume 40` or `run volume 40`) or
+    /// `state`, and `--app <tool>` for the hub.
+    ///
+    /// # Errors
+    /// Returns a message for an unknown action or a missing request.
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut app = None;
+        let mut words = Vec::new();
+        let mut rest = args.iter();
+        while let Some(arg) = rest.next() {
+            if arg == "--app" {
+                app = Some(rest.next().ok_or("--app needs a tool")?.clone());
+            } else if let Some(name) = arg.strip_prefix("--app=") {
+                app = Some(name.to_string());
+            } else if arg != "--json" {
+                words.push(arg.as_str());
+            }
+        }
+
+        let op = match words.as_slice() {
+            [] => return Err("missing action, command or `state`".to_string()),
+            ["state"] => Op::State,
+            ["run", line @ ..] if !line.is_empty() => Op::Command(line.join(" ")),
+            [first, line @ ..] if first.starts_with(':') => {
+                let mut command = vec![first.trim_start_matches(':')];
+                command.extend(line);
+                match command.join(" ").trim() {
+                    "" => return Err("missing command after `:`".to_string()),
+                    command => Op::Command(command.to_string()),
+                }
+            }
+            [name] => Op::Action(parse_action(name)?, None),
+            [name, count] => {
+                let count = count
+                    .parse()
+                    .ok()
+                    .filter(|&c| c > 0)
+                    .ok_or_else(|| format!("invalid count `{count}`"))?;
+                Op::Action(parse_action(name)?, Some(count))
+            }
+            _ => return Err(format!("unexpected `{}`", words[2..].join(" "))),
+        };
+        Ok(Self { app, op })
+    }
+}
+
+/// A bindable action by its config name, with `-` for `_`.
+fn parse_action(name: &str) -> Result<Action, String> {
+    Action::from_name(&name.replace('-', "_")).ok_or_else(|| format!("unknown action `{name}`"))
+}
+
+/// Answer `request` the way every app does unless it overrides
+/// [`App::handle_request`]: run the action as if its key was pressed, or
+/// the command, or describe the state. Returns whether to continue running
+/// and the result.
+///
+/// # Errors
+/// Returns the error from the action or command.
+pub fn handle<A: App + ?Sized>(app: &mut A, request: &Request) -> AppResult<(bool, Value)> {
+    match &request.op {
+        Op::Action(action, count) => {
+            let input = CountedAction {
+                action: *action,
+                count: *count,
+            };
+            // Logged like a key, but never taken by the debug log popup
+            trace::action(app.title(), input);
+            let running = match count {
+                Some(count) => app.handle_action_count(*action, *count)?,
+                None => app.handle_action(*action)?,
+            };
+            Ok((running, Value::Null))
+        }
+        Op::Command(line) => app.execute(line).map(|()| (true, Value::Null)),
+        Op::State => Ok((true, app.state())),
+    }
+}
+
+/// Answer `request` for the loop: whether to continue running, and the
+/// response for the client. Errors only go to the client, so a bad request
+/// cannot end the app.
+pub(crate) fn answer<A: App>(app: &mut A, request: &Request) -> (bool, Value) {
+    match app.handle_request(request) {
+        Ok((running, result)) => (running, json!({"ok": true, "result": result})),
+        Err(error) => (true, json!({"ok": false, "error": error.to_string()})),
+    }
+}
+
+/// Where `program` listens.
+#[must_use]
+pub fn socket_path(program: &str) -> PathBuf {
+    let dir = std::env::var_os("XDG_RUNTIME_DIR")
+        .filter(|dir| !dir.is_empty())
+        .map_or_else(
+            || {
+                // SAFETY: getuid cannot fail
+                let uid = unsafe { libc::getuid() };
+                std::env::temp_dir().join(format!("anytui-{uid}"))
+            },
+            |dir| PathBuf::from(dir).join("anytui"),
+        );
+    dir.join(format!("{program}.sock"))
+}
+
+/// Check that `dir` is fit to hold sockets: a directory, not a link,
+/// owned by us and closed to everyone else. Without `XDG_RUNTIME_DIR` it is
+/// in `/tmp`, where another user could have made it first.
+///
+/// # Errors
+/// Returns a `PermissionDenied` error if it is not, or the error reading it.
+pub fn check_private(dir: &Path) -> io::Result<()> {
+    let metadata = fs::symlink_metadata(dir)?;
+    // SAFETY: getuid cannot fail
+    let uid = unsafe { libc::getuid() };
+    if metadata.is_dir() && metadata.uid() == uid && metadata.mode() & 0o777 == 0o700 {
+        return Ok(());
+    }
+    Err(io::Error::new(
+        io::ErrorKind::PermissionDenied,
+        format!("{} is not a private directory", dir.display()),
+    ))
+}
+
+/// Connect to the socket at `path` if its directory is private.
+fn connect(path: &Path) -> io::Result<UnixStream> {
+    if let Some(dir) = path.parent() {
+        check_private(dir)?;
+    }
+    UnixStream::connect(path)
+}
+
+/// Whether `ANYTUI_IPC` leaves the socket on.
+pub(crate) fn enabled() -> bool {
+    !std::env::var("ANYTUI_IPC").is_ok_and(|v| matches!(v.as_str(), "off" | "0" | "false"))
+}
+
+/// Accepts clients on a thread and passes their requests to the loop,
+/// until dropped.
+pub(crate) struct Server {
+    path: PathBuf,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Server {
+    /// Listen at `path`. Fails if another instance is listening there.
+    pub(crate) fn spawn(path: PathBuf, events: Events) -> io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            DirBuilder::new().recursive(true).mode(0o700).create(dir)?;
+            check_private(dir)?;
+        }
+        if UnixStream::connect(&path).is_ok() {
+            return Err(io::ErrorKind::AddrInUse.into());
+        }
+        // Left behind by an instance that did not exit cleanly
+        let _ = fs::remove_file(&path);
+        let listener = UnixListener::bind(&path)?;
+        fs::set_permissions(&path, Permissions::from_mode(0o600))?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = Arc::clone(&stop);
+        let thread = thread::spawn(move || {
+            for stream in listener.incoming() {
+                if stopped.load(Ordering::Relaxed) {
+                    return;
+                }
+                if let Ok(stream) = stream {
+                    let events = events.clone();
+                    thread::spawn(move || serve(stream, &events));
+                }
+            }
+        });
+        Ok(Self {
+            path,
+            stop,
+            thread: Some(thread),
+        })
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        // Wake the accepting thread so it sees the flag
+        let _ = UnixStream::connect(&self.path);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Answer the requests of one client until it hangs up.
+fn serve(stream: UnixStream, events: &Events) {
+    let Ok(mut writer) = stream.try_clone() else {
+        return;
+    };
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else { return };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match Request::from_json(&line) {
+            Ok(request) => events
+                .request(request)
+                .and_then(|reply| reply.recv_timeout(REPLY_TIMEOUT).ok())
+                .unwrap_or_else(|| json!({"ok": false, "error": "the app did not answer"})),
+            Err(e) => json!({"ok": false, "error": e}),
+        };
+        if writeln!(writer, "{response}").is_err() {
+            return;
+        }
+    }
+}
+
+/// Send `request` to the running `program` and return its result. A tool
+/// that is not running on its own is looked for in the hub.
+///
+/// # Errors
+/// Returns an error if the program is not running or the request fails.
+pub fn send(program: &str, request: &Request) -> AppResult<Value> {
+    let path = socket_path(program);
+    let not_running = |e: io::Error| match e.kind() {
+        io::ErrorKind::PermissionDenied => e.to_string(),
+        _ => format!("{program} is not running"),
+    };
+    let (stream, request) = match connect(&path) {
+        Ok(stream) => (stream, request.clone()),
+        Err(_) if program != HUB && request.app.is_none() => {
+            let stream = connect(&socket_path(HUB)).map_err(not_running)?;
+            (stream, request.clone().with_app(program))
+        }
+        Err(e) => return Err(not_running(e).into()),
+    };
+    stream.set_read_timeout(Some(REPLY_TIMEOUT))?;
+    writeln!(&stream, "{}", request.to_json())?;
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line)?;
+    let response: Value = serde_json::from_str(&line).map_err(|_| "no answer")?;
+    if response["ok"].as_bool() == Some(true) {
+        Ok(response["result"].clone())
+    } else {
+        let error = response["error"].as_str().unwrap_or("request failed");
+        Err(error.to_string().into())
+    }
+}
+
+/// `<program> ctl ...`: send the request in `args` (the words after
+/// `ctl`) to the running UI, print the result like the other commands and
+/// exit.
+pub fn ctl(program: &str, args: &[String]) -> ! {
+    let status = match Request::from_args(args) {
+        Ok(request) => match send(program, &request) {
+            Ok(value) => {
+                let json = args.iter().any(|arg| arg == "--json");
+                let text = cli::format_value(&value, json);
+                if !text.is_empty() {
+                    println!("{text}");
+                }
+                0
+            }
+            Err(e) => {
+                eprintln!("{program}: {e}");
+                EXIT_FAILURE
+            }
+        },
+        Err(e) => {
+            eprintln!("{program}: {e}, see `{program} help`");
+            EXIT_USAGE
+        }
+    };
+    std::process::exit(status);
+}
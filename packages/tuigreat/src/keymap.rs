@@ -0,0 +1,487 @@
+//! User-configurable key bindings.
+//!
+//! Bindings are loaded from `~/.config/anytui/keys.toml`. The `[global]`
+//! section applies to every app, and a section named after the app (e.g.
+//! `[voltui]`) is applied on top of it:
+//!
+//! ```toml
+//! timeout = 1000  # ms to wait for the next key of a sequence
+//!
+//! [global]
+//! down = ["n", "Down"]
+//! up = ["e", "Up"]
+//! search_next = "k"
+//! top = ["gg", "Home"]
+//!
+//! [mustui]
+//! mute = ["m", "Space"]
+//! ```
+//!
+//! Listing keys for an action replaces that action's default keys. A key
+//! taken from another action's defaults moves to the new action. Sequences
+//! are written as consecutive characters (`gg`) or as space-separated
+//! chords (`C-w j`).
+
+use std::fmt;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::Action;
+use crate::config::{self, ConfigFile};
+
+/// Config file name inside the anytui config directory.
+pub const KEYS_FILE: &str = "keys.toml";
+
+/// A key together with its modifiers, e.g. `C-u`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    #[must_use]
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        // Shift is already encoded in the character itself ('G' vs 'g')
+        let modifiers = if matches!(code, KeyCode::Char(_)) {
+            modifiers - KeyModifiers::SHIFT
+        } else {
+            modifiers
+        };
+        Self { code, modifiers }
+    }
+
+    #[must_use]
+    pub fn plain(code: KeyCode) -> Self {
+        Self::new(code, KeyModifiers::NONE)
+    }
+
+    /// Parse a chord like `j`, `G`, `C-u`, `A-Enter`, `Space` or `PageDown`.
+    ///
+    /// # Errors
+    /// Returns a message if the key name or a modifier prefix is unknown.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = s;
+        // Modifier prefixes; a lone "-" or a trailing "-" is the minus key
+        while rest.len() > 2 {
+            let Some((prefix, tail)) = rest.split_once('-') else {
+                break;
+            };
+            let modifier = match prefix.to_ascii_lowercase().as_str() {
+                "c" | "ctrl" => KeyModifiers::CONTROL,
+                "a" | "alt" | "m" | "meta" => KeyModifiers::ALT,
+                "s" | "shift" => KeyModifiers::SHIFT,
+                _ => break,
+            };
+            modifiers |= modifier;
+            rest = tail;
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "enter" | "return" | "cr" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "backspace" | "bs" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "space" => KeyCode::Char(' '),
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n @ 1..=24) => KeyCode::F(n),
+                    _ => return Err(format!("unknown key `{s}`")),
+                },
+            },
+        };
+        Ok(Self::new(code, modifiers))
+    }
+
+    #[must_use]
+    pub fn from_event(key: KeyEvent) -> Self {
+        Self::new(key.code, key.modifiers)
+    }
+
+    /// Parse a key sequence like `gg`, `C-w j` or a single chord.
+    ///
+    /// # Errors
+    /// Returns a message if any chord in the sequence is unknown.
+    pub fn parse_sequence(s: &str) -> Result<Vec<Self>, String> {
+        let mut keys = Vec::new();
+        for token in s.split_whitespace() {
+            match Self::parse(token) {
+                Ok(chord) => keys.push(chord),
+                // Runs of plain characters like "gg" are one chord per char;
+                // capitalised words are misspelt key names
+                Err(_) if !token.starts_with(|c: char| c.is_ascii_uppercase()) => {
+                    keys.extend(token.chars().map(|c| Self::plain(KeyCode::Char(c))));
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        if keys.is_empty() {
+            return Err(format!("empty key sequence `{s}`"));
+        }
+        Ok(keys)
+    }
+
+    /// Format a sequence for display: `gg` for plain characters,
+    /// space-separated chords otherwise.
+    #[must_use]
+    pub fn format_sequence(keys: &[Self]) -> String {
+        let compact = keys.len() > 1
+            && keys
+                .iter()
+                .all(|k| k.modifiers.is_empty() && matches!(k.code, KeyCode::Char(c) if c != ' '));
+        let parts: Vec<String> = keys.iter().map(ToString::to_string).collect();
+        parts.join(if compact { "" } else { " " })
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "C-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "A-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "S-")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::Enter => write!(f, "Enter"),
+            KeyCode::Esc => write!(f, "Esc"),
+            KeyCode::Backspace => write!(f, "Backspace"),
+            KeyCode::Delete => write!(f, "Del"),
+            KeyCode::Insert => write!(f, "Ins"),
+            KeyCode::Tab => write!(f, "Tab"),
+            KeyCode::BackTab => write!(f, "BackTab"),
+            KeyCode::Up => write!(f, "Up"),
+            KeyCode::Down => write!(f, "Down"),
+            KeyCode::Left => write!(f, "Left"),
+            KeyCode::Right => write!(f, "Right"),
+            KeyCode::Home => write!(f, "Home"),
+            KeyCode::End => write!(f, "End"),
+            KeyCode::PageUp => write!(f, "PageUp"),
+            KeyCode::PageDown => write!(f, "PageDown"),
+            KeyCode::F(n) => write!(f, "F{n}"),
+            other => write!(f, "{other:?}"),
+        }
+    }
+}
+
+/// Default bindings, in the order their keys are shown in help.
+const DEFAULT_BINDINGS: &[(&str, Action)] = &[
+    ("q", Action::Quit),
+    ("C-c", Action::Quit),
+    // Vim navigation
+    ("j", Action::Down),
+    ("Down", Action::Down),
+    ("k", Action::Up),
+    ("Up", Action::Up),
+    ("h", Action::Left),
+    ("Left", Action::Left),
+    ("l", Action::Right),
+    ("Right", Action::Right),
+    ("Enter", Action::Select),
+    // Jump navigation
+    ("gg", Action::Top),
+    ("0", Action::Top),
+    ("^", Action::Top),
+    ("Home", Action::Top),
+    ("G", Action::Bottom),
+    ("$", Action::Bottom),
+    ("End", Action::Bottom),
+    ("C-u", Action::PageUp),
+    ("C-d", Action::PageDown),
+    ("C-b", Action::FullPageUp),
+    ("PageUp", Action::FullPageUp),
+    ("C-f", Action::FullPageDown),
+    ("PageDown", Action::FullPageDown),
+    // Search
+    ("/", Action::Search),
+    ("n", Action::SearchNext),
+    ("N", Action::SearchPrev),
+    (":", Action::Command),
+    // Jump to char (vim-style f/F)
+    ("f", Action::JumpTo),
+    ("F", Action::JumpBack),
+    ("?", Action::Help),
+    ("!", Action::Details),
+    ("M", Action::Messages),
+    ("D", Action::Log),
+    ("r", Action::Refresh),
+    // Audio controls (only + for volume, = passes through for calculators etc)
+    ("+", Action::VolumeUp),
+    ("-", Action::VolumeDown),
+    ("_", Action::VolumeDown),
+    ("m", Action::Mute),
+    ("Esc", Action::Back),
+    ("Backspace", Action::Back),
+    ("d", Action::Delete),
+    ("Delete", Action::Delete),
+    ("y", Action::Yank),
+    // Paste (vim style + terminal paste)
+    ("p", Action::Paste),
+    ("C-v", Action::Paste),
+    ("C-V", Action::Paste),
+    // Marks (vim-style visual line mode)
+    ("v", Action::Mark),
+    ("V", Action::Visual),
+    // Tables
+    ("s", Action::Sort),
+];
+
+/// How long to wait for the next key of an unfinished sequence.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_millis(1000);
+
+static DEFAULT_KEYMAP: LazyLock<Keymap> = LazyLock::new(Keymap::default);
+
+/// Result of looking up a (possibly partial) key sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SequenceMatch {
+    /// No binding starts with these keys.
+    None,
+    /// The keys start one or more longer bindings.
+    Pending,
+    /// The keys are bound and nothing longer starts with them.
+    Complete(Action),
+    /// The keys are bound, but also start a longer binding.
+    Ambiguous(Action),
+}
+
+/// Maps key sequences to actions.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<(Vec<KeyChord>, Action)>,
+    timeout: Duration,
+    warnings: Vec<String>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = DEFAULT_BINDINGS
+            .iter()
+            .filter_map(|(key, action)| {
+                KeyChord::parse_sequence(key)
+                    .ok()
+                    .map(|keys| (keys, *action))
+            })
+            .collect();
+        Self {
+            bindings,
+            timeout: DEFAULT_TIMEOUT,
+            warnings: Vec::new(),
+        }
+    }
+}
+
+impl Keymap {
+    /// Shared instance of the built-in default keymap.
+    #[must_use]
+    pub fn defaults() -> &'static Self {
+        &DEFAULT_KEYMAP
+    }
+
+    /// Load the keymap for `app` from `keys.toml`, falling back to the
+    /// defaults for anything missing or invalid.
+    #[must_use]
+    pub fn load(app: &str) -> Self {
+        match config::load(KEYS_FILE) {
+            None => Self::default(),
+            Some(Ok(file)) => Self::from_config(&file, app),
+            Some(Err(e)) => {
+                let mut keymap = Self::default();
+                keymap.warnings.push(e);
+                keymap
+            }
+        }
+    }
+
+    /// Build a keymap from the `[global]` and `[<app>]` sections of a config.
+    #[must_use]
+    pub fn from_config(file: &ConfigFile, app: &str) -> Self {
+        let mut keymap = Self::default();
+        match file.get("", "timeout") {
+            None => {}
+            Some(config::Value::Integer(ms)) if *ms > 0 => {
+                keymap.timeout = Duration::from_millis(ms.unsigned_abs());
+            }
+            Some(other) => keymap.warn(format!("`timeout` must be milliseconds, got {other}")),
+        }
+        for name in ["global", app] {
+            for section in file.sections(name) {
+                keymap.apply_section(&section.name, &section.entries);
+            }
+        }
+        keymap
+    }
+
+    fn apply_section(&mut self, name: &str, entries: &[(String, config::Value)]) {
+        // Keys bound in this section, to detect conflicts within it
+        let mut claimed: Vec<(Vec<KeyChord>, Action)> = Vec::new();
+
+        for (action_name, value) in entries {
+            let Some(action) = Action::from_name(action_name) else {
+                self.warn(format!("[{name}] unknown action `{action_name}`"));
+                continue;
+            };
+            let Some(keys) = value.as_str_list() else {
+                self.warn(format!(
+                    "[{name}] `{action_name}` must be a key or list of keys"
+                ));
+                continue;
+            };
+
+            self.bindings.retain(|(_, a)| *a != action);
+            for key in keys {
+                let keys = match KeyChord::parse_sequence(key) {
+                    Ok(keys) => keys,
+                    Err(e) => {
+                        self.warn(format!("[{name}] {e}"));
+                        continue;
+                    }
+                };
+                let shown = KeyChord::format_sequence(&keys);
+                if let Some((_, other)) = claimed.iter().find(|(k, _)| *k == keys) {
+                    if *other != action {
+                        self.warn(format!(
+                            "[{name}] `{shown}` is bound to both `{}` and `{}`",
+                            other.name(),
+                            action.name()
+                        ));
+                    }
+                    continue;
+                }
+                claimed.push((keys.clone(), action));
+
+                // Take the key over from whichever action had it before
+                if let Some(pos) = self.bindings.iter().position(|(k, _)| *k == keys) {
+                    let (_, previous) = self.bindings.remove(pos);
+                    if !self.bindings.iter().any(|(_, a)| *a == previous)
+                        && !entries.iter().any(|(n, _)| n == previous.name())
+                    {
+                        self.warn(format!(
+                            "[{name}] `{shown}` was the last key for `{}`, which is now unbound",
+                            previous.name()
+                        ));
+                    }
+                }
+                self.bindings.push((keys, action));
+            }
+        }
+    }
+
+    fn warn(&mut self, message: String) {
+        self.warnings.push(format!("{KEYS_FILE}: {message}"));
+    }
+
+    /// Problems found while loading the config (unknown names, conflicts...).
+    #[must_use]
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// How long an unfinished sequence waits for its next key.
+    #[must_use]
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Translate a single key event into an action, ignoring sequences.
+    /// Use [`KeySequencer`](crate::KeySequencer) for sequences and counts.
+    #[must_use]
+    pub fn parse(&self, key: KeyEvent) -> Action {
+        let chord = KeyChord::from_event(key);
+        if let Some(action) = self.lookup_single(chord) {
+            return action;
+        }
+        // Unbound modified keys fall back to their plain binding (A-j acts as j)
+        if !chord.modifiers.is_empty()
+            && let Some(action) = self.lookup_single(KeyChord::plain(chord.code))
+        {
+            return action;
+        }
+        match key.code {
+            // Pass through other characters
+            KeyCode::Char(c) => Action::Char(c),
+            _ => Action::None,
+        }
+    }
+
+    fn lookup_single(&self, chord: KeyChord) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(k, _)| k.as_slice() == [chord])
+            .map(|(_, a)| *a)
+    }
+
+    /// Look up the keys pressed so far.
+    #[must_use]
+    pub fn lookup(&self, keys: &[KeyChord]) -> SequenceMatch {
+        let exact = self
+            .bindings
+            .iter()
+            .find(|(k, _)| k.as_slice() == keys)
+            .map(|(_, a)| *a);
+        let longer = self
+            .bindings
+            .iter()
+            .any(|(k, _)| k.len() > keys.len() && k.starts_with(keys));
+        match (exact, longer) {
+            (Some(action), false) => SequenceMatch::Complete(action),
+            (Some(action), true) => SequenceMatch::Ambiguous(action),
+            (None, true) => SequenceMatch::Pending,
+            (None, false) => SequenceMatch::None,
+        }
+    }
+
+    /// All key sequences bound to an action, in binding order.
+    pub fn keys_for(&self, action: Action) -> impl Iterator<Item = &[KeyChord]> + '_ {
+        self.bindings
+            .iter()
+            .filter(move |(_, a)| *a == action)
+            .map(|(k, _)| k.as_slice())
+    }
+
+    /// Short label for the primary key of each action, joined with `/`
+    /// (e.g. `j/k` for `[Down, Up]`). Unbound pass-through characters are
+    /// shown as themselves.
+    #[must_use]
+    pub fn label(&self, actions: &[Action]) -> String {
+        actions
+            .iter()
+            .map(|&action| match (self.keys_for(action).next(), action) {
+                (Some(keys), _) => KeyChord::format_sequence(keys),
+                (None, Action::Char(c)) => KeyChord::plain(KeyCode::Char(c)).to_string(),
+                (None, _) => "-".to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// Build help popup rows from `(actions, description)` pairs using the
+    /// keys that are actually bound.
+    #[must_use]
+    pub fn help<'a>(&self, entries: &[(&[Action], &'a str)]) -> Vec<(String, &'a str)> {
+        entries
+            .iter()
+            .map(|(actions, desc)| (self.label(actions), *desc))
+            .collect()
+    }
+}
@@ -1,5 +1,7 @@
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
+use crate::keymap::Keymap;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Action {
     Quit,
@@ -11,7 +13,7 @@ pub enum Action {
     Select,
     Back,
     // Jump navigation
-    Top,          // g, gg, 0, ^
+    Top,          // gg, 0, ^
     Bottom,       // G, $
     PageUp,       // Ctrl+u (half page)
     PageDown,     // Ctrl+d (half page)
@@ -23,11 +25,19 @@ pub enum Action {
     Search,     // /
     SearchNext, // n
     SearchPrev, // N
+    Command,    // :
     // Yank/paste
     Yank,  // y
     Paste, // p
+    // Multi-selection
+    Mark,   // v - mark or unmark the selected item
+    Visual, // V - mark a range
+    Sort,   // s - sort a table by the next column
     // Other
     Help,
+    Details,  // ! - show the full output behind an error
+    Messages, // M - show the message history
+    Log,      // D - show the debug log
     Refresh,
     VolumeUp,
     VolumeDown,
@@ -35,98 +45,158 @@ pub enum Action {
     Delete,
     // Pass-through
     Char(char),
+    Edit(Edit),
     None,
 }
 
-pub struct KeyHandler;
+/// Cursor movement and deletion inside a text input, produced by
+/// [`KeyHandler::parse_input_mode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edit {
+    Left,          // Left
+    Right,         // Right
+    WordLeft,      // C-Left, A-b
+    WordRight,     // C-Right, A-f
+    Home,          // Home, C-a
+    End,           // End, C-e
+    Delete,        // Delete
+    DeleteWord,    // C-w, A-Backspace
+    DeleteToStart, // C-u
+    DeleteToEnd,   // C-k
+}
 
-impl KeyHandler {
+impl Action {
+    /// Actions that can be bound to keys in a keymap, in help/display order.
+    pub const BINDABLE: &'static [Action] = &[
+        Action::Quit,
+        Action::Up,
+        Action::Down,
+        Action::Left,
+        Action::Right,
+        Action::Select,
+        Action::Back,
+        Action::Top,
+        Action::Bottom,
+        Action::PageUp,
+        Action::PageDown,
+        Action::FullPageUp,
+        Action::FullPageDown,
+        Action::JumpTo,
+        Action::JumpBack,
+        Action::Search,
+        Action::SearchNext,
+        Action::SearchPrev,
+        Action::Command,
+        Action::Yank,
+        Action::Paste,
+        Action::Mark,
+        Action::Visual,
+        Action::Sort,
+        Action::Help,
+        Action::Details,
+        Action::Messages,
+        Action::Log,
+        Action::Refresh,
+        Action::VolumeUp,
+        Action::VolumeDown,
+        Action::Mute,
+        Action::Delete,
+    ];
+
+    /// Config name of the action (e.g. `"page_down"`), as used in `keys.toml`.
     #[must_use]
-    pub fn parse(key: KeyEvent) -> Action {
-        match key.code {
-            // Quit
-            KeyCode::Char('q') => Action::Quit,
-            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Action::Quit,
-
-            // Vim navigation
-            KeyCode::Char('j') | KeyCode::Down => Action::Down,
-            KeyCode::Char('k') | KeyCode::Up => Action::Up,
-            KeyCode::Char('h') | KeyCode::Left => Action::Left,
-            KeyCode::Char('l') | KeyCode::Right => Action::Right,
-
-            // Selection
-            KeyCode::Enter => Action::Select,
-
-            // Jump navigation (vim style)
-            KeyCode::Char('g' | '0' | '^') | KeyCode::Home => Action::Top,
-            KeyCode::Char('G' | '$') | KeyCode::End => Action::Bottom,
-            // Half page (Ctrl+u/d)
-            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => Action::PageUp,
-            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => Action::PageDown,
-            // Full page (Ctrl+b/f)
-            KeyCode::Char('b') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                Action::FullPageUp
-            }
-            KeyCode::Char('f') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                Action::FullPageDown
-            }
-            KeyCode::PageUp => Action::FullPageUp,
-            KeyCode::PageDown => Action::FullPageDown,
-
-            // Search (vim style)
-            KeyCode::Char('/') => Action::Search,
-            KeyCode::Char('n') => Action::SearchNext,
-            KeyCode::Char('N') => Action::SearchPrev,
-
-            // Jump to char (vim-style f/F)
-            KeyCode::Char('F') => Action::JumpBack,
-
-            // Help
-            KeyCode::Char('?') => Action::Help,
-            KeyCode::Char('r') => Action::Refresh,
-
-            // Audio controls (only + for volume, = passes through for calculators etc)
-            KeyCode::Char('+') => Action::VolumeUp,
-            KeyCode::Char('-' | '_') => Action::VolumeDown,
-            KeyCode::Char('m') => Action::Mute,
-
-            // Delete/backspace
-            KeyCode::Esc | KeyCode::Backspace => Action::Back,
-            KeyCode::Delete | KeyCode::Char('d') => Action::Delete,
-
-            // Yank (vim style copy)
-            KeyCode::Char('y') => Action::Yank,
-
-            // Jump to (focus/playing item)
-            KeyCode::Char('f') => Action::JumpTo,
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Quit => "quit",
+            Self::Up => "up",
+            Self::Down => "down",
+            Self::Left => "left",
+            Self::Right => "right",
+            Self::Select => "select",
+            Self::Back => "back",
+            Self::Top => "top",
+            Self::Bottom => "bottom",
+            Self::PageUp => "page_up",
+            Self::PageDown => "page_down",
+            Self::FullPageUp => "full_page_up",
+            Self::FullPageDown => "full_page_down",
+            Self::JumpTo => "jump_to",
+            Self::JumpBack => "jump_back",
+            Self::Search => "search",
+            Self::SearchNext => "search_next",
+            Self::SearchPrev => "search_prev",
+            Self::Command => "command",
+            Self::Yank => "yank",
+            Self::Paste => "paste",
+            Self::Mark => "mark",
+            Self::Visual => "visual",
+            Self::Sort => "sort",
+            Self::Help => "help",
+            Self::Details => "details",
+            Self::Messages => "messages",
+            Self::Log => "log",
+            Self::Refresh => "refresh",
+            Self::VolumeUp => "volume_up",
+            Self::VolumeDown => "volume_down",
+            Self::Mute => "mute",
+            Self::Delete => "delete",
+            Self::Char(_) => "char",
+            Self::Edit(_) => "edit",
+            Self::None => "none",
+        }
+    }
 
-            // Paste (vim style + terminal paste)
-            KeyCode::Char('p') => Action::Paste,
-            KeyCode::Char('v') if key.modifiers.contains(KeyModifiers::CONTROL) => Action::Paste,
-            KeyCode::Char('V')
-                if key
-                    .modifiers
-                    .contains(KeyModifiers::CONTROL | KeyModifiers::SHIFT) =>
-            {
-                Action::Paste
-            }
+    /// Look up a bindable action by its config name.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::BINDABLE.iter().copied().find(|a| a.name() == name)
+    }
+}
 
-            // Pass through other characters
-            KeyCode::Char(c) => Action::Char(c),
+pub struct KeyHandler;
 
-            _ => Action::None,
-        }
+impl KeyHandler {
+    /// Parse a key using the built-in default keymap.
+    #[must_use]
+    pub fn parse(key: KeyEvent) -> Action {
+        Keymap::defaults().parse(key)
     }
 
-    /// Parse keys in input mode - pass through most characters as-is
+    /// Parse keys in input mode - pass through most characters as-is,
+    /// and editing keys as [`Action::Edit`]
     #[must_use]
     pub fn parse_input_mode(key: KeyEvent) -> Action {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
         match key.code {
             // Esc cancels/aborts input, Backspace deletes character
             KeyCode::Esc => Action::Quit,
+            KeyCode::Backspace if ctrl || alt => Action::Edit(Edit::DeleteWord),
             KeyCode::Backspace => Action::Back,
             KeyCode::Enter => Action::Select,
-            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Action::Quit,
+            // Tab completes and Up/Down walk history in the command line
+            KeyCode::Tab => Action::Char('\t'),
+            KeyCode::Up => Action::Up,
+            KeyCode::Down => Action::Down,
+            KeyCode::Left if ctrl || alt => Action::Edit(Edit::WordLeft),
+            KeyCode::Right if ctrl || alt => Action::Edit(Edit::WordRight),
+            KeyCode::Left => Action::Edit(Edit::Left),
+            KeyCode::Right => Action::Edit(Edit::Right),
+            KeyCode::Home => Action::Edit(Edit::Home),
+            KeyCode::End => Action::Edit(Edit::End),
+            KeyCode::Delete => Action::Edit(Edit::Delete),
+            KeyCode::Char(c) if ctrl => match c {
+                'c' => Action::Quit,
+                'a' => Action::Edit(Edit::Home),
+                'e' => Action::Edit(Edit::End),
+                'w' => Action::Edit(Edit::DeleteWord),
+                'u' => Action::Edit(Edit::DeleteToStart),
+                'k' => Action::Edit(Edit::DeleteToEnd),
+                'v' | 'V' => Action::Paste,
+                _ => Action::None,
+            },
+            KeyCode::Char('b') if alt => Action::Edit(Edit::WordLeft),
+            KeyCode::Char('f') if alt => Action::Edit(Edit::WordRight),
             KeyCode::Char(c) => Action::Char(c),
             _ => Action::None,
         }
@@ -1,26 +1,50 @@
 pub mod app;
+pub mod cli;
 pub mod clipboard;
+pub mod config;
+pub mod confirm;
+pub mod error;
+pub mod events;
+pub mod fuzzy;
+pub mod hub;
+pub mod ipc;
+pub mod keymap;
 pub mod keys;
+pub mod messages;
+pub mod notify;
+pub mod osc;
+pub mod runner;
+pub mod sequence;
+pub mod session;
 pub mod status;
+pub mod tasks;
+pub mod testing;
 pub mod theme;
+pub mod trace;
 pub mod widgets;
 
-pub use app::{App, AppResult};
-pub use clipboard::{paste, yank};
-pub use keys::{Action, KeyHandler};
+pub use app::{App, AppResult, Screen, repeat_action};
+pub use cli::Cli;
+pub use clipboard::{Clipboard, paste, yank};
+pub use confirm::Confirmations;
+pub use error::Error;
+pub use events::{Events, Interval};
+pub use fuzzy::{FuzzyMatch, fuzzy_match};
+pub use hub::Hub;
+pub use keymap::{KeyChord, Keymap, SequenceMatch};
+pub use keys::{Action, Edit, KeyHandler};
+pub use messages::MessageLog;
+pub use notify::Notifier;
+pub use sequence::{CountedAction, KeySequencer};
 pub use status::{StatusLevel, StatusMessage, status_line};
-pub use theme::Theme;
-
-use std::process::{Command, Stdio};
+pub use tasks::{Progress, Tasks};
+pub use theme::{ColorDepth, Theme};
 
 /// Check if a command is available on the system PATH.
 #[must_use]
 pub fn which(cmd: &str) -> bool {
-    Command::new("which")
+    runner::Command::new("which")
         .arg(cmd)
-        .stdout(Stdio::null())
-        .stderr(Stdio::null())
-        .status()
-        .map(|s| s.success())
-        .unwrap_or(false)
+        .output()
+        .is_ok_and(|output| output.status.success())
 }
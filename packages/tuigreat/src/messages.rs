@@ -0,0 +1,167 @@
+//! A history of the status messages an app has shown.
+//!
+//! Apps [`push`](MessageLog::push) every message through a [`MessageLog`]
+//! instead of overwriting a single status. The newest one stays in the
+//! status line until it expires, and the whole history can be opened with
+//! a [`MessagePopup`](crate::widgets::MessagePopup).
+
+use std::collections::VecDeque;
+use std::time::{Instant, SystemTime};
+
+use ratatui::text::Line;
+
+use crate::{AppResult, Error, StatusMessage, Theme};
+
+/// How many messages are kept before the oldest are dropped.
+pub const CAPACITY: usize = 100;
+
+/// A message in the history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub message: StatusMessage,
+    /// When the message was last shown.
+    pub at: SystemTime,
+    /// How many times in a row it was shown.
+    pub count: usize,
+}
+
+impl Entry {
+    /// How long ago the message was shown, e.g. `"42s"` or `"3m"`.
+    #[must_use]
+    pub fn age(&self, now: SystemTime) -> String {
+        let secs = now.duration_since(self.at).unwrap_or_default().as_secs();
+        match secs {
+            0..60 => format!("{secs}s"),
+            60..3600 => format!("{}m", secs / 60),
+            3600..86400 => format!("{}h", secs / 3600),
+            _ => format!("{}d", secs / 86400),
+        }
+    }
+}
+
+/// The message in the status line and the history behind it.
+#[derive(Debug, Clone, Default)]
+pub struct MessageLog {
+    entries: VecDeque<Entry>,
+    /// Shown in the status line, until the deadline if there is one.
+    current: Option<(StatusMessage, Option<Instant>)>,
+}
+
+impl MessageLog {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Show `message` and add it to the history. A repeat of the newest
+    /// entry is counted instead of added again; an empty message just
+    /// clears the status line.
+    pub fn push(&mut self, message: impl Into<StatusMessage>) {
+        let message = message.into();
+        if message.is_empty() {
+            self.clear();
+            return;
+        }
+        let deadline = Instant::now() + message.level.lifetime();
+        match self.entries.back_mut() {
+            Some(last) if last.message.text == message.text => {
+                last.message = message.clone();
+                last.at = SystemTime::now();
+                last.count += 1;
+            }
+            _ => {
+                if self.entries.len() == CAPACITY {
+                    self.entries.pop_front();
+                }
+                self.entries.push_back(Entry {
+                    message: message.clone(),
+                    at: SystemTime::now(),
+                    count: 1,
+                });
+            }
+        }
+        self.current = Some((message, Some(deadline)));
+    }
+
+    /// Show `message` until it is replaced, without adding it to the
+    /// history. For prompts and progress such as `Jump to:` or a match
+    /// count.
+    pub fn hint(&mut self, message: impl Into<StatusMessage>) {
+        self.current = Some((message.into(), None));
+    }
+
+    /// Push `error` if it is an [`Error`] from a backend.
+    ///
+    /// # Errors
+    /// Returns `error` unchanged if it is not an [`Error`].
+    pub fn report(&mut self, error: Box<dyn std::error::Error>) -> AppResult<()> {
+        self.push(*error.downcast::<Error>()?);
+        Ok(())
+    }
+
+    /// Clear the status line. The history is kept.
+    pub fn clear(&mut self) {
+        self.current = None;
+    }
+
+    /// The message in the status line, unless it has expired.
+    #[must_use]
+    pub fn current(&self) -> Option<&StatusMessage> {
+        self.current_at(Instant::now())
+    }
+
+    /// The message in the status line as of `now`.
+    #[must_use]
+    pub fn current_at(&self, now: Instant) -> Option<&StatusMessage> {
+        match &self.current {
+            Some((message, deadline)) if deadline.is_none_or(|d| now < d) => Some(message),
+            _ => None,
+        }
+    }
+
+    /// The status line, empty once the message has expired.
+    #[must_use]
+    pub fn to_line(&self, theme: &Theme) -> Line<'static> {
+        self.current()
+            .map(|message| message.to_line(theme))
+            .unwrap_or_default()
+    }
+
+    /// When the current message expires, to redraw the status line then.
+    /// Add it to [`App::next_tick`](crate::App::next_tick).
+    #[must_use]
+    pub fn next_expiry(&self) -> Option<Instant> {
+        let (_, deadline) = self.current.as_ref()?;
+        deadline.filter(|d| Instant::now() < *d)
+    }
+
+    /// The message to show details for: the current one, or else the
+    /// newest message in the history that has any.
+    #[must_use]
+    pub fn last_detail(&self) -> Option<&StatusMessage> {
+        self.current()
+            .filter(|message| message.detail.is_some())
+            .or_else(|| {
+                self.entries
+                    .iter()
+                    .rev()
+                    .map(|entry| &entry.message)
+                    .find(|message| message.detail.is_some())
+            })
+    }
+
+    /// The history, oldest first.
+    pub fn entries(&self) -> impl DoubleEndedIterator<Item = &Entry> + ExactSizeIterator {
+        self.entries.iter()
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
@@ -0,0 +1,245 @@
+//! Desktop notifications for events that happen while the app is in a
+//! background terminal.
+//!
+//! Notifications go to the freedesktop notification daemon over D-Bus
+//! (through `gdbus`), or as an OSC 9 / OSC 777 escape sequence for the
+//! terminal to show when there is no session bus. They are only sent while
+//! the terminal is not focused, so nothing pops up for what is already on
+//! screen. The escape sequences [pass through](crate::osc) tmux and
+//! screen. `ANYTUI_NOTIFY` picks the backend: `dbus`, `osc9`, `osc777` or
+//! `off`.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::time::Duration;
+
+use crate::runner::Command;
+use crate::{Error, osc};
+
+const DEST: &str = "org.freedesktop.Notifications";
+const OBJECT_PATH: &str = "/org/freedesktop/Notifications";
+/// How long to wait for the notification daemon.
+const DBUS_TIMEOUT: Duration = Duration::from_secs(2);
+
+const FOCUS_UNKNOWN: u8 = 0;
+const FOCUS_GAINED: u8 = 1;
+const FOCUS_LOST: u8 = 2;
+
+/// Whether the terminal has focus, as reported by focus events.
+static FOCUS: AtomicU8 = AtomicU8::new(FOCUS_UNKNOWN);
+
+/// Record a focus change reported by the terminal.
+pub fn set_focused(focused: bool) {
+    let state = if focused { FOCUS_GAINED } else { FOCUS_LOST };
+    FOCUS.store(state, Ordering::Relaxed);
+}
+
+/// Whether the terminal is known to have focus. Terminals that never
+/// report focus count as unfocused, so their notifications still arrive.
+#[must_use]
+pub fn is_focused() -> bool {
+    FOCUS.load(Ordering::Relaxed) == FOCUS_GAINED
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Urgency {
+    Low,
+    #[default]
+    Normal,
+    Critical,
+}
+
+/// Where notifications are sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// `org.freedesktop.Notifications` on the session bus.
+    DBus,
+    /// `ESC ] 9 ; text BEL` (iTerm2, kitty, Windows Terminal...).
+    Osc9,
+    /// `ESC ] 777 ; notify ; title ; body BEL` (foot, urxvt, Ghostty...).
+    Osc777,
+    Off,
+}
+
+impl Backend {
+    /// The backend named by `ANYTUI_NOTIFY`, or D-Bus when there is a
+    /// session bus and `gdbus`, or else the escape sequence the terminal
+    /// most likely understands.
+    #[must_use]
+    pub fn detect() -> Self {
+        if let Ok(name) = std::env::var("ANYTUI_NOTIFY")
+            && let Some(backend) = Self::from_name(&name)
+        {
+            return backend;
+        }
+        if std::env::var_os("DBUS_SESSION_BUS_ADDRESS").is_some() && crate::which("gdbus") {
+            return Self::DBus;
+        }
+        Self::osc_for_terminal(
+            &std::env::var("TERM").unwrap_or_default(),
+            &std::env::var("TERM_PROGRAM").unwrap_or_default(),
+        )
+    }
+
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "dbus" => Some(Self::DBus),
+            "osc9" => Some(Self::Osc9),
+            "osc777" => Some(Self::Osc777),
+            "off" | "none" => Some(Self::Off),
+            _ => None,
+        }
+    }
+
+    /// OSC 777 for the terminals known to prefer it, OSC 9 otherwise.
+    #[must_use]
+    pub fn osc_for_terminal(term: &str, term_program: &str) -> Self {
+        let osc777 = term.starts_with("foot")
+            || term.contains("rxvt")
+            || matches!(term_program, "ghostty" | "WezTerm");
+        if osc777 { Self::Osc777 } else { Self::Osc9 }
+    }
+}
+
+/// A notification to show.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    pub summary: String,
+    pub body: String,
+    pub urgency: Urgency,
+}
+
+impl Notification {
+    pub fn new(summary: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            summary: summary.into(),
+            body: body.into(),
+            urgency: Urgency::default(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_urgency(mut self, urgency: Urgency) -> Self {
+        self.urgency = urgency;
+        self
+    }
+
+    /// The escape sequence for `backend`, or `None` for D-Bus and off.
+    #[must_use]
+    pub fn osc(&self, backend: Backend) -> Option<String> {
+        let summary = printable(&self.summary);
+        let body = printable(&self.body);
+        match backend {
+            Backend::Osc9 if body.is_empty() => Some(format!("\x1b]9;{summary}\x07")),
+            Backend::Osc9 => Some(format!("\x1b]9;{summary}: {body}\x07")),
+            Backend::Osc777 => Some(format!(
+                "\x1b]777;notify;{};{body}\x07",
+                summary.replace(';', ",")
+            )),
+            Backend::DBus | Backend::Off => None,
+        }
+    }
+}
+
+/// Sends an app's notifications.
+#[derive(Debug, Clone)]
+pub struct Notifier {
+    app: String,
+    backend: Backend,
+}
+
+impl Notifier {
+    /// A notifier for `app` using the [detected](Backend::detect) backend.
+    #[must_use]
+    pub fn new(app: &str) -> Self {
+        Self {
+            app: app.to_string(),
+            backend: Backend::detect(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_backend(mut self, backend: Backend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    #[must_use]
+    pub fn backend(&self) -> Backend {
+        self.backend
+    }
+
+    /// Show `summary` and `body` unless the terminal has focus. Failures
+    /// are ignored: a missed notification is not worth interrupting for.
+    pub fn notify(&self, summary: impl Into<String>, body: impl Into<String>) {
+        if !is_focused() {
+            let _ = self.send(&Notification::new(summary, body));
+        }
+    }
+
+    /// Send `notification` now, falling back to an escape sequence when
+    /// the notification daemon does not answer.
+    ///
+    /// # Errors
+    /// Returns an error if the notification could not be shown.
+    pub fn send(&self, notification: &Notification) -> Result<(), Error> {
+        match self.backend {
+            Backend::Off => Ok(()),
+            Backend::DBus => self.send_dbus(notification).or_else(|_| {
+                let term = std::env::var("TERM").unwrap_or_default();
+                let program = std::env::var("TERM_PROGRAM").unwrap_or_default();
+                write_osc(notification, Backend::osc_for_terminal(&term, &program))
+            }),
+            backend => write_osc(notification, backend),
+        }
+    }
+
+    fn send_dbus(&self, notification: &Notification) -> Result<(), Error> {
+        let urgency = match notification.urgency {
+            Urgency::Low => 0,
+            Urgency::Normal => 1,
+            Urgency::Critical => 2,
+        };
+        Command::new("gdbus")
+            .args([
+                "call",
+                "--session",
+                "--dest",
+                DEST,
+                "--object-path",
+                OBJECT_PATH,
+            ])
+            .args(["--method", &format!("{DEST}.Notify")])
+            .arg(gvariant_string(&self.app))
+            .arg("0")
+            .arg("''")
+            .arg(gvariant_string(&notification.summary))
+            .arg(gvariant_string(&notification.body))
+            .arg("[]")
+            .arg(format!("{{'urgency': <byte {urgency}>}}"))
+            .arg("-1")
+            .timeout(DBUS_TIMEOUT)
+            .run()
+            .map(|_| ())
+    }
+}
+
+fn write_osc(notification: &Notification, backend: Backend) -> Result<(), Error> {
+    let Some(sequence) = notification.osc(backend) else {
+        return Ok(());
+    };
+    osc::write(&sequence).map_err(|e| Error::backend(format!("Could not write notification: {e}")))
+}
+
+/// `text` as a quoted GVariant string, as `gdbus call` expects.
+fn gvariant_string(text: &str) -> String {
+    let escaped = text.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("\"{escaped}\"")
+}
+
+/// `text` without control characters that would end the escape sequence.
+fn printable(text: &str) -> String {
+    text.chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect()
+}
@@ -0,0 +1,62 @@
+//! Escape sequences written straight to the terminal, such as OSC 52
+//! clipboard writes and OSC 9 notifications.
+//!
+//! tmux and GNU screen swallow sequences they do not know, so inside one
+//! of them the sequence is wrapped in a DCS passthrough that hands it on
+//! to the outer terminal. tmux only forwards these with
+//! `set -g allow-passthrough on`.
+
+use std::io::{self, Write};
+
+/// A terminal multiplexer between the app and the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Multiplexer {
+    None,
+    Tmux,
+    Screen,
+}
+
+impl Multiplexer {
+    /// The multiplexer the app runs in, from `TMUX` and `STY`.
+    #[must_use]
+    pub fn detect() -> Self {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    /// The multiplexer that the environment variables looked up with `var`
+    /// point to. `TERM` is not a sign of screen: tmux also sets
+    /// `screen-256color`, and SSH passes it on without `TMUX`.
+    #[must_use]
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Self {
+        let set = |name| var(name).is_some_and(|value| !value.is_empty());
+        if set("TMUX") {
+            Self::Tmux
+        } else if set("STY") {
+            Self::Screen
+        } else {
+            Self::None
+        }
+    }
+
+    /// `sequence` wrapped so that it reaches the outer terminal.
+    #[must_use]
+    pub fn wrap(self, sequence: &str) -> String {
+        match self {
+            Self::None => sequence.to_string(),
+            // Every ESC inside the passthrough is doubled
+            Self::Tmux => format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b")),
+            Self::Screen => format!("\x1bP{sequence}\x1b\\"),
+        }
+    }
+}
+
+/// Write `sequence` to the terminal, through the
+/// [detected](Multiplexer::detect) multiplexer.
+///
+/// # Errors
+/// Returns an error if stdout cannot be written.
+pub fn write(sequence: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    stdout.write_all(Multiplexer::detect().wrap(sequence).as_bytes())?;
+    stdout.flush()
+}
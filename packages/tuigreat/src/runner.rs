@@ -0,0 +1,520 @@
+//! Running external programs through a replaceable [`CommandRunner`].
+//!
+//! Backends build a [`Command`] like [`std::process::Command`] and call
+//! [`Command::output`], which goes to the runner of the current thread:
+//! the real system by default, or a [`FakeRunner`] replaying canned output
+//! in tests. [`Tasks`](crate::Tasks) hand the runner on to their threads.
+//!
+//! Setting `ANYTUI_RECORD=path` appends every command and its output to
+//! `path` in the fixture format read by [`FakeRunner::load`]:
+//!
+//! ```text
+//! # Lines starting with # are comments
+//! $ pactl get-default-sink
+//! > alsa_output.pci-0000_00_1f.3.analog-stereo
+//! $ pactl set-default-sink 'No Such Sink'
+//! ! Failure: No such entity
+//! ? 1
+//! ```
+//!
+//! `$` starts a command, `>` and `!` lines are its stdout and stderr and
+//! `?` is the exit status, 0 if left out. Arguments with spaces or quotes
+//! are single-quoted, with `''` for a quote inside.
+
+use std::cell::RefCell;
+use std::fmt::Write as _;
+use std::fs::OpenOptions;
+use std::io::{self, Write as _};
+use std::os::unix::process::ExitStatusExt;
+use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Output, Stdio};
+use std::sync::{Arc, LazyLock, Mutex, PoisonError, mpsc};
+use std::time::{Duration, Instant};
+
+use crate::{Error, trace};
+
+/// Set to a file path to record every command and its output.
+pub const RECORD_VAR: &str = "ANYTUI_RECORD";
+
+/// Runs a program to completion and returns its output.
+pub trait CommandRunner: Send + Sync {
+    /// Run `program` with `args`, writing `stdin` to it if given.
+    ///
+    /// # Errors
+    /// Returns an error if the program cannot be started, e.g. because it
+    /// is not installed.
+    fn output(&self, program: &str, args: &[String], stdin: Option<&str>) -> io::Result<Output>;
+}
+
+/// Runs programs for real.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemRunner;
+
+impl CommandRunner for SystemRunner {
+    fn output(&self, program: &str, args: &[String], stdin: Option<&str>) -> io::Result<Output> {
+        let mut command = std::process::Command::new(program);
+        command.args(args);
+        let Some(input) = stdin else {
+            return command.output();
+        };
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        if let Some(mut pipe) = child.stdin.take() {
+            pipe.write_all(input.as_bytes())?;
+        }
+        child.wait_with_output()
+    }
+}
+
+/// Passes commands to another runner and appends them with their output
+/// to a fixture file.
+pub struct RecordingRunner<R> {
+    inner: R,
+    path: PathBuf,
+    // Serialises appends from background threads
+    lock: Mutex<()>,
+}
+
+impl<R: CommandRunner> RecordingRunner<R> {
+    pub fn new(inner: R, path: impl Into<PathBuf>) -> Self {
+        Self {
+            inner,
+            path: path.into(),
+            lock: Mutex::new(()),
+        }
+    }
+}
+
+impl<R: CommandRunner> CommandRunner for RecordingRunner<R> {
+    fn output(&self, program: &str, args: &[String], stdin: Option<&str>) -> io::Result<Output> {
+        let output = self.inner.output(program, args, stdin)?;
+        let mut argv = vec![program.to_string()];
+        argv.extend(args.iter().cloned());
+        let entry = format_reply(&argv, &output);
+        let _guard = self.lock.lock().unwrap_or_else(PoisonError::into_inner);
+        // Recording is best effort; the command itself succeeded
+        if let Ok(mut file) = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+        {
+            let _ = file.write_all(entry.as_bytes());
+        }
+        Ok(output)
+    }
+}
+
+/// Canned output for a command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reply {
+    argv: Vec<String>,
+    stdout: String,
+    stderr: String,
+    status: i32,
+}
+
+impl Reply {
+    /// Reply to `argv`, the program followed by its arguments. An argument
+    /// of `*` matches any single argument.
+    #[must_use]
+    pub fn new(argv: &[&str]) -> Self {
+        Self {
+            argv: argv.iter().map(ToString::to_string).collect(),
+            stdout: String::new(),
+            stderr: String::new(),
+            status: 0,
+        }
+    }
+
+    #[must_use]
+    pub fn stdout(mut self, stdout: &str) -> Self {
+        self.stdout = stdout.to_string();
+        self
+    }
+
+    #[must_use]
+    pub fn stderr(mut self, stderr: &str) -> Self {
+        self.stderr = stderr.to_string();
+        self
+    }
+
+    /// Exit status, 0 by default.
+    #[must_use]
+    pub fn status(mut self, status: i32) -> Self {
+        self.status = status;
+        self
+    }
+
+    fn matches(&self, argv: &[String]) -> bool {
+        self.argv.len() == argv.len()
+            && self
+                .argv
+                .iter()
+                .zip(argv)
+                .all(|(pattern, arg)| pattern == "*" || pattern == arg)
+    }
+
+    fn to_output(&self) -> Output {
+        Output {
+            // Wait status: the exit code lives in the second byte
+            status: ExitStatus::from_raw(self.status << 8),
+            stdout: self.stdout.clone().into_bytes(),
+            stderr: self.stderr.clone().into_bytes(),
+        }
+    }
+}
+
+/// Replays canned [`Reply`]s and records the commands it was asked to run.
+///
+/// The most recently added matching reply wins, so a test can change what
+/// a command returns part way through, e.g. after setting a volume.
+/// Commands without a reply fail as if the program was not installed.
+#[derive(Debug, Default)]
+pub struct FakeRunner {
+    replies: Mutex<Vec<Reply>>,
+    calls: Mutex<Vec<Vec<String>>>,
+}
+
+impl FakeRunner {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load replies from a fixture file, see the [module docs](self).
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or a line is malformed.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let text = std::fs::read_to_string(path.as_ref())?;
+        let runner = Self::new();
+        for reply in
+            parse_fixture(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?
+        {
+            runner.add(reply);
+        }
+        Ok(runner)
+    }
+
+    #[must_use]
+    pub fn with(self, reply: Reply) -> Self {
+        self.add(reply);
+        self
+    }
+
+    pub fn add(&self, reply: Reply) {
+        self.replies
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(reply);
+    }
+
+    /// Command lines run so far, program first.
+    #[must_use]
+    pub fn calls(&self) -> Vec<Vec<String>> {
+        self.calls
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Whether a command line starting with `prefix` has been run.
+    #[must_use]
+    pub fn ran(&self, prefix: &[&str]) -> bool {
+        self.calls()
+            .iter()
+            .any(|argv| argv.len() >= prefix.len() && argv.iter().zip(prefix).all(|(a, p)| a == p))
+    }
+}
+
+impl CommandRunner for FakeRunner {
+    fn output(&self, program: &str, args: &[String], _stdin: Option<&str>) -> io::Result<Output> {
+        let mut argv = vec![program.to_string()];
+        argv.extend(args.iter().cloned());
+        let reply = self
+            .replies
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .rev()
+            .find(|reply| reply.matches(&argv))
+            .map(Reply::to_output);
+        let line = format_argv(&argv);
+        self.calls
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(argv);
+        reply.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no recorded reply for `{line}`"),
+            )
+        })
+    }
+}
+
+static DEFAULT_RUNNER: LazyLock<Arc<dyn CommandRunner>> =
+    LazyLock::new(|| match std::env::var_os(RECORD_VAR) {
+        Some(path) => Arc::new(RecordingRunner::new(SystemRunner, path)),
+        None => Arc::new(SystemRunner),
+    });
+
+thread_local! {
+    static RUNNER: RefCell<Option<Arc<dyn CommandRunner>>> = const { RefCell::new(None) };
+}
+
+/// The runner commands on this thread go to.
+#[must_use]
+pub fn current() -> Arc<dyn CommandRunner> {
+    RUNNER
+        .with_borrow(Clone::clone)
+        .unwrap_or_else(|| Arc::clone(&DEFAULT_RUNNER))
+}
+
+/// Send this thread's commands, and those of tasks it spawns, to `runner`.
+pub fn set(runner: Arc<dyn CommandRunner>) {
+    RUNNER.set(Some(runner));
+}
+
+/// Go back to running programs for real on this thread.
+pub fn reset() {
+    RUNNER.set(None);
+}
+
+/// A program invocation, built like [`std::process::Command`] and run
+/// through the [current](current()) runner.
+#[derive(Debug, Clone)]
+pub struct Command {
+    program: String,
+    args: Vec<String>,
+    stdin: Option<String>,
+    timeout: Option<Duration>,
+    secrets: Vec<String>,
+}
+
+impl Command {
+    #[must_use]
+    pub fn new(program: &str) -> Self {
+        Self {
+            program: program.to_string(),
+            args: Vec::new(),
+            stdin: None,
+            timeout: None,
+            secrets: Vec::new(),
+        }
+    }
+
+    pub fn arg(&mut self, arg: impl AsRef<str>) -> &mut Self {
+        self.args.push(arg.as_ref().to_string());
+        self
+    }
+
+    pub fn args<I, S>(&mut self, args: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.args
+            .extend(args.into_iter().map(|a| a.as_ref().to_string()));
+        self
+    }
+
+    /// Text written to the program's standard input, e.g. a password.
+    pub fn stdin(&mut self, input: &str) -> &mut Self {
+        self.stdin = Some(input.to_string());
+        self
+    }
+
+    /// Hide `secret`, e.g. a password passed as an argument, from the
+    /// command line shown in errors.
+    pub fn redact(&mut self, secret: &str) -> &mut Self {
+        if !secret.is_empty() {
+            self.secrets.push(secret.to_string());
+        }
+        self
+    }
+
+    /// Give up on [`Command::run`] after `after`. The program is left to
+    /// finish in the background.
+    pub fn timeout(&mut self, after: Duration) -> &mut Self {
+        self.timeout = Some(after);
+        self
+    }
+
+    /// Run to completion and collect the output.
+    ///
+    /// # Errors
+    /// Returns an error if the program cannot be started.
+    pub fn output(&self) -> io::Result<Output> {
+        let started = Instant::now();
+        let output = current().output(&self.program, &self.args, self.stdin.as_deref());
+        trace::command(self.argv(), started.elapsed(), &output);
+        output
+    }
+
+    /// Run to completion and return the standard output.
+    ///
+    /// # Errors
+    /// Returns an [`Error`] if the program is missing, exits unsuccessfully
+    /// (keeping its stderr) or runs past the [timeout](Command::timeout).
+    pub fn run(&self) -> Result<String, Error> {
+        let output = match self.timeout {
+            Some(after) => self.output_within(after)?,
+            None => self.output(),
+        };
+        let output = output.map_err(|e| Error::from_io(&self.line(), &e))?;
+        match Error::from_output(&self.line(), &output) {
+            Some(error) => Err(error),
+            None => Ok(String::from_utf8_lossy(&output.stdout).into_owned()),
+        }
+    }
+
+    fn output_within(&self, after: Duration) -> Result<io::Result<Output>, Error> {
+        let runner = current();
+        let (program, args, stdin) = (self.program.clone(), self.args.clone(), self.stdin.clone());
+        let argv = self.argv();
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let started = Instant::now();
+            let output = runner.output(&program, &args, stdin.as_deref());
+            // Logged once it finishes, even after the caller gave up
+            trace::command(argv, started.elapsed(), &output);
+            let _ = tx.send(output);
+        });
+        rx.recv_timeout(after).map_err(|_| Error::Timeout {
+            command: self.line(),
+            after,
+        })
+    }
+
+    /// The command line as typed in a shell, with
+    /// [redacted](Command::redact) secrets masked.
+    #[must_use]
+    pub fn line(&self) -> String {
+        format_argv(&self.argv())
+    }
+
+    /// The program and arguments with secrets masked, as in
+    /// [`Command::line`].
+    fn argv(&self) -> Vec<String> {
+        let mut argv = vec![self.program.clone()];
+        argv.extend(self.args.iter().map(|arg| {
+            self.secrets
+                .iter()
+                .fold(arg.clone(), |arg, secret| arg.replace(secret, "***"))
+        }));
+        argv
+    }
+}
+
+/// Quote `argv` as a fixture command line.
+fn format_argv(argv: &[String]) -> String {
+    argv.iter()
+        .map(|arg| {
+            if arg.is_empty() || arg.contains(|c: char| c.is_whitespace() || c == '\'') {
+                format!("'{}'", arg.replace('\'', "''"))
+            } else {
+                arg.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn parse_argv(line: &str) -> Result<Vec<String>, String> {
+    let mut argv = Vec::new();
+    let mut chars = line.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let Some(&first) = chars.peek() else {
+            return Ok(argv);
+        };
+        let mut arg = String::new();
+        if first == '\'' {
+            chars.next();
+            loop {
+                match chars.next() {
+                    Some('\'') if chars.next_if_eq(&'\'').is_some() => arg.push('\''),
+                    Some('\'') => break,
+                    Some(c) => arg.push(c),
+                    None => return Err(format!("unterminated quote in `{line}`")),
+                }
+            }
+        } else {
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                arg.push(c);
+            }
+        }
+        argv.push(arg);
+    }
+}
+
+fn format_reply(argv: &[String], output: &Output) -> String {
+    let mut entry = format!("$ {}\n", format_argv(argv));
+    for (prefix, bytes) in [('>', &output.stdout), ('!', &output.stderr)] {
+        let text = String::from_utf8_lossy(bytes);
+        for line in text.lines() {
+            let _ = writeln!(entry, "{prefix} {line}");
+        }
+    }
+    if let Some(code) = output.status.code()
+        && code != 0
+    {
+        let _ = writeln!(entry, "? {code}");
+    }
+    entry
+}
+
+/// Parse the fixture format described in the [module docs](self).
+///
+/// # Errors
+/// Returns a message naming the first malformed line.
+pub fn parse_fixture(text: &str) -> Result<Vec<Reply>, String> {
+    let mut replies: Vec<Reply> = Vec::new();
+    for (n, line) in text.lines().enumerate() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut chars = line.chars();
+        let tag = chars.next().unwrap_or_default();
+        let rest = chars.as_str();
+        let rest = rest.strip_prefix(' ').unwrap_or(rest);
+        if tag == '$' {
+            let argv = parse_argv(rest).map_err(|e| format!("line {}: {e}", n + 1))?;
+            replies.push(Reply {
+                argv,
+                stdout: String::new(),
+                stderr: String::new(),
+                status: 0,
+            });
+            continue;
+        }
+        let Some(reply) = replies.last_mut() else {
+            return Err(format!(
+                "line {}: output before the first `$` command",
+                n + 1
+            ));
+        };
+        match tag {
+            '>' => {
+                reply.stdout.push_str(rest);
+                reply.stdout.push('\n');
+            }
+            '!' => {
+                reply.stderr.push_str(rest);
+                reply.stderr.push('\n');
+            }
+            '?' => {
+                reply.status = rest
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("line {}: bad exit status `{rest}`", n + 1))?;
+            }
+            _ => return Err(format!("line {}: expected `$`, `>`, `!` or `?`", n + 1)),
+        }
+    }
+    Ok(replies)
+}
@@ -0,0 +1,166 @@
+//! Stateful key input: multi-key sequences (`gg`) and count prefixes (`5j`).
+
+use std::time::Instant;
+
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::Action;
+use crate::keymap::{KeyChord, Keymap, SequenceMatch};
+
+/// Largest count accepted; further digits are ignored.
+const MAX_COUNT: usize = 99_999;
+
+/// An action together with the count typed before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CountedAction {
+    pub action: Action,
+    pub count: Option<usize>,
+}
+
+impl CountedAction {
+    #[must_use]
+    pub fn new(action: Action) -> Self {
+        Self {
+            action,
+            count: None,
+        }
+    }
+}
+
+/// Collects key presses until they form a complete binding.
+///
+/// Digits typed before a binding become its count, `Esc` cancels a pending
+/// sequence, and a sequence that is bound but also starts a longer one
+/// (`g` next to `gg`) fires once [`Keymap::timeout`] passes.
+#[derive(Debug, Default)]
+pub struct KeySequencer {
+    pending: Vec<KeyChord>,
+    count: Option<usize>,
+    last_key: Option<Instant>,
+}
+
+impl KeySequencer {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed a key press and return the actions it completes, if any.
+    /// With `counts` false, digits are treated like any other key.
+    pub fn feed(
+        &mut self,
+        keymap: &Keymap,
+        key: KeyEvent,
+        now: Instant,
+        counts: bool,
+    ) -> Vec<CountedAction> {
+        let chord = KeyChord::from_event(key);
+
+        if chord == KeyChord::plain(KeyCode::Esc) && self.is_pending() {
+            self.reset();
+            return Vec::new();
+        }
+
+        if counts
+            && self.pending.is_empty()
+            && let Some(digit) = self.count_digit(keymap, chord)
+        {
+            let count = self.count.unwrap_or(0).saturating_mul(10) + digit;
+            self.count = Some(count.min(MAX_COUNT));
+            self.last_key = Some(now);
+            return Vec::new();
+        }
+
+        self.pending.push(chord);
+        match keymap.lookup(&self.pending) {
+            SequenceMatch::Complete(action) => vec![self.finish(action)],
+            SequenceMatch::Pending | SequenceMatch::Ambiguous(_) => {
+                self.last_key = Some(now);
+                Vec::new()
+            }
+            SequenceMatch::None if self.pending.len() == 1 => {
+                let action = keymap.parse(key);
+                vec![self.finish(action)]
+            }
+            SequenceMatch::None => {
+                // The new key does not continue the sequence: fire what was
+                // typed so far if it is bound, then start over with the key
+                self.pending.pop();
+                let mut actions = Vec::new();
+                if let SequenceMatch::Ambiguous(action) = keymap.lookup(&self.pending) {
+                    actions.push(self.finish(action));
+                } else {
+                    self.reset();
+                }
+                actions.extend(self.feed(keymap, key, now, counts));
+                actions
+            }
+        }
+    }
+
+    /// Resolve a sequence that has waited longer than the keymap timeout.
+    pub fn poll_timeout(&mut self, keymap: &Keymap, now: Instant) -> Option<CountedAction> {
+        if self.pending.is_empty()
+            || self
+                .last_key
+                .is_none_or(|t| now.duration_since(t) < keymap.timeout())
+        {
+            return None;
+        }
+        if let SequenceMatch::Ambiguous(action) = keymap.lookup(&self.pending) {
+            Some(self.finish(action))
+        } else {
+            self.reset();
+            None
+        }
+    }
+
+    /// When [`KeySequencer::poll_timeout`] resolves the pending sequence.
+    #[must_use]
+    pub fn deadline(&self, keymap: &Keymap) -> Option<Instant> {
+        if self.pending.is_empty() {
+            return None;
+        }
+        self.last_key.map(|t| t + keymap.timeout())
+    }
+
+    /// Whether a count or an unfinished sequence is waiting for more keys.
+    #[must_use]
+    pub fn is_pending(&self) -> bool {
+        self.count.is_some() || !self.pending.is_empty()
+    }
+
+    /// The keys typed so far, e.g. `5g`, for display.
+    #[must_use]
+    pub fn pending(&self) -> String {
+        let count = self.count.map(|n| n.to_string()).unwrap_or_default();
+        format!("{count}{}", KeyChord::format_sequence(&self.pending))
+    }
+
+    /// Drop any count and unfinished sequence.
+    pub fn reset(&mut self) {
+        self.pending.clear();
+        self.count = None;
+        self.last_key = None;
+    }
+
+    /// Digits start a count unless bound themselves (`0` is Top), and
+    /// always continue one.
+    fn count_digit(&self, keymap: &Keymap, chord: KeyChord) -> Option<usize> {
+        let KeyCode::Char(c) = chord.code else {
+            return None;
+        };
+        let digit = usize::try_from(c.to_digit(10)?).ok()?;
+        let starts = digit > 0 && keymap.lookup(&[chord]) == SequenceMatch::None;
+        (chord.modifiers.is_empty() && (self.count.is_some() || starts)).then_some(digit)
+    }
+
+    fn finish(&mut self, action: Action) -> CountedAction {
+        let counted = CountedAction {
+            action,
+            count: self.count,
+        };
+        self.reset();
+        counted
+    }
+}
@@ -0,0 +1,75 @@
+//! Session state kept between runs, such as the selected tab or the
+//! month on screen.
+//!
+//! Apps return their state as JSON from [`App::save_session`] and get it
+//! back in [`App::restore_session`] on the next launch. It is stored in
+//! `$XDG_STATE_HOME/anytui/<program>.json` (`~/.local/state/anytui`)
+//! together with [`App::session_version`]: a file from another version,
+//! or one that does not parse, is ignored, so changing what an app saves
+//! never stops it from starting. Set `ANYTUI_STATE=off` to neither load
+//! nor save.
+//!
+//! [`App::save_session`]: crate::App::save_session
+//! [`App::restore_session`]: crate::App::restore_session
+//! [`App::session_version`]: crate::App::session_version
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use serde_json::{Value, json};
+
+/// The directory session files are kept in (`$XDG_STATE_HOME/anytui`,
+/// falling back to `~/.local/state/anytui`).
+#[must_use]
+pub fn state_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_STATE_HOME")
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".local/state")))
+        .map(|d| d.join("anytui"))
+}
+
+/// Whether `ANYTUI_STATE` leaves sessions on.
+#[must_use]
+pub fn enabled() -> bool {
+    !std::env::var("ANYTUI_STATE").is_ok_and(|v| matches!(v.as_str(), "off" | "0" | "false"))
+}
+
+/// The state `program` saved with `version`, if there is any.
+#[must_use]
+pub fn load(program: &str, version: u32) -> Option<Value> {
+    let text = fs::read_to_string(state_dir()?.join(format!("{program}.json"))).ok()?;
+    let file: Value = serde_json::from_str(&text).ok()?;
+    unwrap(&file, version).cloned()
+}
+
+/// Save `state` for the next run of `program`.
+///
+/// # Errors
+/// Returns an error if the file cannot be written.
+pub fn save(program: &str, version: u32, state: &Value) -> io::Result<()> {
+    let dir = state_dir().ok_or(io::ErrorKind::NotFound)?;
+    fs::create_dir_all(&dir)?;
+    // Write a copy and move it over, so a crash cannot leave half a file.
+    // Each process writes its own copy when two instances exit together.
+    let path = dir.join(format!("{program}.json"));
+    let partial = dir.join(format!("{program}.json.{}.tmp", std::process::id()));
+    fs::write(&partial, wrap(state, version).to_string())?;
+    fs::rename(partial, path)
+}
+
+/// `state` marked with the `version` it was saved with.
+#[must_use]
+pub fn wrap(state: &Value, version: u32) -> Value {
+    json!({"version": version, "state": state})
+}
+
+/// The state in `saved`, if it was [wrapped](wrap) with `version`.
+#[must_use]
+pub fn unwrap(saved: &Value, version: u32) -> Option<&Value> {
+    if saved["version"].as_u64() != Some(u64::from(version)) {
+        return None;
+    }
+    Some(&saved["state"]).filter(|state| !state.is_null())
+}
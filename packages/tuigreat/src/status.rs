@@ -1,11 +1,13 @@
 //! Status message helpers for consistent styling across TUI apps.
 
+use std::time::Duration;
+
 use ratatui::{
     style::Style,
     text::{Line, Span},
 };
 
-use crate::Theme;
+use crate::{AppResult, Error, Theme};
 
 /// Status message severity level.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
@@ -23,7 +25,9 @@ pub enum StatusLevel {
 
 impl StatusLevel {
     /// Detect status level from message content.
-    /// Looks for common prefixes like "Error:", "Failed:", etc.
+    /// Looks for common prefixes like "Error:", "Failed:", etc. Prefer
+    /// building a [`StatusMessage`] with an explicit level, or from an
+    /// [`Error`].
     #[must_use]
     pub fn from_text(text: &str) -> Self {
         let t = text.trim_start();
@@ -50,50 +54,59 @@ impl StatusLevel {
             Self::Error => theme.error(),
         }
     }
+
+    /// How long a message stays in a [`MessageLog`](crate::MessageLog)'s
+    /// status line. Problems stay longer so there is time to read them.
+    #[must_use]
+    pub fn lifetime(self) -> Duration {
+        match self {
+            Self::Info | Self::Success => Duration::from_secs(5),
+            Self::Warning => Duration::from_secs(15),
+            Self::Error => Duration::from_secs(30),
+        }
+    }
 }
 
 /// A styled status message.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct StatusMessage {
     pub text: String,
     pub level: StatusLevel,
+    /// Full output behind an error, shown on request.
+    pub detail: Option<String>,
 }
 
 impl StatusMessage {
-    /// Create a new info-level status message.
-    #[must_use]
-    pub fn info(text: impl Into<String>) -> Self {
+    fn new(text: impl Into<String>, level: StatusLevel) -> Self {
         Self {
             text: text.into(),
-            level: StatusLevel::Info,
+            level,
+            detail: None,
         }
     }
 
+    /// Create a new info-level status message.
+    #[must_use]
+    pub fn info(text: impl Into<String>) -> Self {
+        Self::new(text, StatusLevel::Info)
+    }
+
     /// Create a new success-level status message.
     #[must_use]
     pub fn success(text: impl Into<String>) -> Self {
-        Self {
-            text: text.into(),
-            level: StatusLevel::Success,
-        }
+        Self::new(text, StatusLevel::Success)
     }
 
     /// Create a new warning-level status message.
     #[must_use]
     pub fn warning(text: impl Into<String>) -> Self {
-        Self {
-            text: text.into(),
-            level: StatusLevel::Warning,
-        }
+        Self::new(text, StatusLevel::Warning)
     }
 
     /// Create a new error-level status message.
     #[must_use]
     pub fn error(text: impl Into<String>) -> Self {
-        Self {
-            text: text.into(),
-            level: StatusLevel::Error,
-        }
+        Self::new(text, StatusLevel::Error)
     }
 
     /// Get the appropriate style for this status level.
@@ -102,13 +115,28 @@ impl StatusMessage {
         self.level.style(theme)
     }
 
-    /// Convert to a Line with proper styling.
+    /// Convert to a Line with proper styling, noting when there is more
+    /// detail behind the message.
     #[must_use]
     pub fn to_line(&self, theme: &Theme) -> Line<'static> {
-        Line::from(vec![
+        let mut spans = vec![
             Span::raw(" "),
             Span::styled(self.text.clone(), self.style(theme)),
-        ])
+        ];
+        if self.detail.is_some() {
+            spans.push(Span::styled("  (! for details)", theme.muted()));
+        }
+        Line::from(spans)
+    }
+
+    /// Show `error` if it is an [`Error`] from a backend. Other errors are
+    /// handed back, to end the app as before.
+    ///
+    /// # Errors
+    /// Returns `error` unchanged if it is not an [`Error`].
+    pub fn report(&mut self, error: Box<dyn std::error::Error>) -> AppResult<()> {
+        *self = Self::from(*error.downcast::<Error>()?);
+        Ok(())
     }
 
     /// Check if the message is empty.
@@ -121,6 +149,7 @@ impl StatusMessage {
     pub fn clear(&mut self) {
         self.text.clear();
         self.level = StatusLevel::Info;
+        self.detail = None;
     }
 }
 
@@ -130,6 +159,16 @@ impl From<String> for StatusMessage {
     }
 }
 
+impl From<Error> for StatusMessage {
+    fn from(error: Error) -> Self {
+        Self {
+            text: error.to_string(),
+            level: error.level(),
+            detail: error.detail(),
+        }
+    }
+}
+
 impl From<&str> for StatusMessage {
     fn from(text: &str) -> Self {
         Self::info(text)
@@ -0,0 +1,173 @@
+//! Background tasks for slow backend commands.
+//!
+//! Submit blocking work with [`Tasks::spawn`] instead of running it inside
+//! `handle_action`, collect results with [`Tasks::finished`] in `tick`, and
+//! show [`Tasks::indicator`] in the status line while work is running.
+
+use std::io;
+use std::process::Output;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::Events;
+use crate::runner::{self, Command};
+
+const SPINNER: &[char] = &['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+const SPINNER_FRAME: Duration = Duration::from_millis(100);
+
+/// Progress reporting from inside a running task.
+#[derive(Debug, Clone)]
+pub struct Progress {
+    label: Arc<Mutex<String>>,
+    events: Events,
+}
+
+impl Progress {
+    /// Replace the task's label, e.g. with the current step.
+    pub fn set(&self, text: impl Into<String>) {
+        *self.label.lock().unwrap_or_else(PoisonError::into_inner) = text.into();
+        self.events.wake();
+    }
+}
+
+/// Sends the result when the task ends, or nothing if it panicked, so the
+/// task never stays busy.
+struct Completion<T> {
+    id: u64,
+    tx: Sender<(u64, Option<T>)>,
+    events: Events,
+    result: Option<T>,
+}
+
+impl<T> Completion<T> {
+    fn finish(mut self, result: T) {
+        self.result = Some(result);
+    }
+}
+
+impl<T> Drop for Completion<T> {
+    fn drop(&mut self) {
+        let _ = self.tx.send((self.id, self.result.take()));
+        self.events.wake();
+    }
+}
+
+/// Runs closures on background threads and hands their results back to
+/// the app loop.
+#[derive(Debug)]
+pub struct Tasks<T> {
+    events: Events,
+    tx: Sender<(u64, Option<T>)>,
+    rx: Receiver<(u64, Option<T>)>,
+    running: Vec<(u64, Arc<Mutex<String>>)>,
+    next_id: u64,
+    started: Instant,
+}
+
+impl<T: Send + 'static> Default for Tasks<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Send + 'static> Tasks<T> {
+    #[must_use]
+    pub fn new() -> Self {
+        let (tx, rx) = mpsc::channel();
+        Self {
+            events: Events::default(),
+            tx,
+            rx,
+            running: Vec::new(),
+            next_id: 0,
+            started: Instant::now(),
+        }
+    }
+
+    /// Wake the app loop when tasks finish or report progress. Call from
+    /// [`App::start`](crate::App::start).
+    pub fn connect(&mut self, events: &Events) {
+        self.events = events.clone();
+    }
+
+    /// Run `job` on a background thread, labelled `label` while it runs.
+    pub fn spawn<F>(&mut self, label: impl Into<String>, job: F)
+    where
+        F: FnOnce(&Progress) -> T + Send + 'static,
+    {
+        let id = self.next_id;
+        self.next_id += 1;
+        let label = Arc::new(Mutex::new(label.into()));
+        if self.running.is_empty() {
+            self.started = Instant::now();
+        }
+        self.running.push((id, Arc::clone(&label)));
+
+        let progress = Progress {
+            label,
+            events: self.events.clone(),
+        };
+        let completion = Completion {
+            id,
+            tx: self.tx.clone(),
+            events: self.events.clone(),
+            result: None,
+        };
+        let runner = runner::current();
+        thread::spawn(move || {
+            runner::set(runner);
+            let result = job(&progress);
+            completion.finish(result);
+        });
+    }
+
+    /// Run a command on a background thread and map its output to a result.
+    /// Like other commands it goes through the [runner](crate::runner) of
+    /// this thread.
+    pub fn spawn_command<F>(&mut self, label: impl Into<String>, command: Command, done: F)
+    where
+        F: FnOnce(io::Result<Output>) -> T + Send + 'static,
+    {
+        self.spawn(label, move |_| done(command.output()));
+    }
+
+    /// Results of tasks that have finished since the last call.
+    pub fn finished(&mut self) -> Vec<T> {
+        let mut results = Vec::new();
+        while let Ok((id, result)) = self.rx.try_recv() {
+            self.running.retain(|(task, _)| *task != id);
+            results.extend(result);
+        }
+        results
+    }
+
+    /// Whether any task is still running.
+    #[must_use]
+    pub fn is_busy(&self) -> bool {
+        !self.running.is_empty()
+    }
+
+    /// Spinner and label of the latest running task, e.g. ` ⠙ Connecting...`,
+    /// with the leading space used by status messages.
+    #[must_use]
+    pub fn indicator(&self) -> Option<String> {
+        let (_, label) = self.running.last()?;
+        let label = label.lock().unwrap_or_else(PoisonError::into_inner);
+        let frame = self.started.elapsed().as_millis() / SPINNER_FRAME.as_millis();
+        let spinner = SPINNER[usize::try_from(frame).unwrap_or(0) % SPINNER.len()];
+        let others = match self.running.len() - 1 {
+            0 => String::new(),
+            n => format!(" (+{n})"),
+        };
+        Some(format!(" {spinner} {}{others}", label.trim_start()))
+    }
+
+    /// When the spinner should advance. Return this from
+    /// [`App::next_tick`](crate::App::next_tick) while busy.
+    #[must_use]
+    pub fn next_tick(&self) -> Option<Instant> {
+        self.is_busy().then(|| Instant::now() + SPINNER_FRAME)
+    }
+}
@@ -0,0 +1,336 @@
+//! Drive an [`App`] without a terminal, for tests.
+//!
+//! [`TestHarness`] feeds actions, key presses and mouse events through the
+//! same dispatch as [`run`](crate::app::run), ticks the app and renders it
+//! into a [`TestBackend`]. Screens can be compared with snapshot files in
+//! the calling crate's `tests/snapshots` directory; run the tests with
+//! `ANYTUI_UPDATE_SNAPSHOTS=1` to write or update them.
+//!
+//! ```no_run
+//! # use tuigreat::{App, testing::TestHarness};
+//! # fn demo(app: impl App) {
+//! let mut harness = TestHarness::new(app, 40, 10);
+//! harness.press("j j").press("/").type_text("wifi");
+//! harness.assert_snapshot("search_wifi");
+//! # }
+//! ```
+
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::sync::mpsc::Receiver;
+use std::time::{Duration, Instant};
+
+use crossterm::event::{
+    KeyEvent, KeyEventKind, KeyEventState, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use ratatui::{Terminal, backend::TestBackend, buffer::Buffer};
+use serde_json::Value;
+
+use crate::app::{AppResult, dispatch, handle_key, recover, render, tick};
+use crate::events::{Events, LoopEvent};
+use crate::ipc::{self, Request};
+use crate::{Action, App, CountedAction, KeyChord, KeySequencer};
+
+/// Set to write snapshots instead of comparing against them.
+pub const UPDATE_SNAPSHOTS_VAR: &str = "ANYTUI_UPDATE_SNAPSHOTS";
+
+/// An [`App`] rendered into an in-memory terminal.
+///
+/// Every input is followed by a tick and a redraw, like one pass of the
+/// event loop. Errors from the app panic, failing the test.
+pub struct TestHarness<A: App> {
+    app: A,
+    terminal: Terminal<TestBackend>,
+    keys: KeySequencer,
+    wakes: Receiver<LoopEvent>,
+    running: bool,
+}
+
+impl<A: App> TestHarness<A> {
+    /// Start `app` on a `width` x `height` screen and draw the first frame.
+    ///
+    /// # Panics
+    /// Panics if the app panics while rendering.
+    pub fn new(mut app: A, width: u16, height: u16) -> Self {
+        let (events, wakes) = Events::channel();
+        app.start(&events);
+        let terminal =
+            Terminal::new(TestBackend::new(width, height)).expect("the test backend cannot fail");
+        let mut harness = Self {
+            app,
+            terminal,
+            keys: KeySequencer::new(),
+            wakes,
+            running: true,
+        };
+        harness.draw();
+        harness
+    }
+
+    #[must_use]
+    pub fn app(&self) -> &A {
+        &self.app
+    }
+
+    /// Change the app directly, e.g. to load fixture data. Call
+    /// [`TestHarness::draw`] afterwards to see the result.
+    pub fn app_mut(&mut self) -> &mut A {
+        &mut self.app
+    }
+
+    /// Whether the app is still running, i.e. has not quit.
+    #[must_use]
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Run an action as if its key was pressed.
+    pub fn action(&mut self, action: Action) -> &mut Self {
+        self.dispatch(CountedAction::new(action))
+    }
+
+    /// Run an action with a count, as typed with a prefix like `5j`.
+    pub fn action_count(&mut self, action: Action, count: usize) -> &mut Self {
+        self.dispatch(CountedAction {
+            action,
+            count: Some(count),
+        })
+    }
+
+    pub fn actions(&mut self, actions: &[Action]) -> &mut Self {
+        for &action in actions {
+            self.action(action);
+        }
+        self
+    }
+
+    /// Press keys written like the keymap config: `j`, `gg`, `5j`, `C-w j`,
+    /// `Esc`. Chords are separated by spaces.
+    ///
+    /// # Panics
+    /// Panics if a key name is unknown.
+    pub fn press(&mut self, keys: &str) -> &mut Self {
+        let chords = KeyChord::parse_sequence(keys).unwrap_or_else(|e| panic!("{e}"));
+        for chord in chords {
+            self.key(KeyEvent {
+                code: chord.code,
+                modifiers: chord.modifiers,
+                kind: KeyEventKind::Press,
+                state: KeyEventState::NONE,
+            });
+        }
+        self
+    }
+
+    /// Type `text` one character at a time, spaces included.
+    pub fn type_text(&mut self, text: &str) -> &mut Self {
+        for c in text.chars() {
+            self.key(KeyEvent::from(crossterm::event::KeyCode::Char(c)));
+        }
+        self
+    }
+
+    /// Press a single key.
+    pub fn key(&mut self, key: KeyEvent) -> &mut Self {
+        if self.running {
+            let running = handle_key(&mut self.app, &mut self.keys, key, Instant::now());
+            self.running = Self::check(running);
+            self.tick();
+        }
+        self
+    }
+
+    /// Fire a key sequence that is waiting for more keys, as the loop does
+    /// once the keymap timeout passes (a lone `g` next to `gg`).
+    pub fn flush_keys(&mut self) -> &mut Self {
+        let later = Instant::now() + self.app.keymap().timeout();
+        if let Some(input) = self.keys.poll_timeout(self.app.keymap(), later) {
+            self.dispatch(input);
+        }
+        self
+    }
+
+    pub fn mouse(&mut self, event: MouseEvent) -> &mut Self {
+        if self.running {
+            let running = self.app.handle_mouse(event);
+            self.running = Self::check(recover(&mut self.app, running, true));
+            self.tick();
+        }
+        self
+    }
+
+    /// Answer a control socket request as the loop does, and return the
+    /// response sent to the client.
+    pub fn request(&mut self, request: &Request) -> Value {
+        let (running, response) = ipc::answer(&mut self.app, request);
+        if self.running {
+            self.running = running;
+            self.tick();
+        }
+        response
+    }
+
+    /// Left click at a screen position.
+    pub fn click(&mut self, column: u16, row: u16) -> &mut Self {
+        self.mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        })
+    }
+
+    /// Run [`App::tick`] and redraw.
+    pub fn tick(&mut self) -> &mut Self {
+        if self.running {
+            let ticked = tick(&mut self.app);
+            Self::check(recover(&mut self.app, ticked, ()));
+        }
+        self.draw()
+    }
+
+    /// Wait up to `timeout` for the app to wake the loop, e.g. when a
+    /// background task finishes, then tick. Returns whether it woke.
+    pub fn wait(&mut self, timeout: Duration) -> bool {
+        let woke = self.wakes.recv_timeout(timeout).is_ok();
+        while self.wakes.try_recv().is_ok() {}
+        self.tick();
+        woke
+    }
+
+    /// Change the screen size and redraw.
+    pub fn resize(&mut self, width: u16, height: u16) -> &mut Self {
+        self.terminal.backend_mut().resize(width, height);
+        self.draw()
+    }
+
+    /// Render the app.
+    ///
+    /// # Panics
+    /// Panics if the app panics while rendering.
+    pub fn draw(&mut self) -> &mut Self {
+        let app = &mut self.app;
+        self.terminal
+            .draw(|frame| render(app, frame))
+            .expect("the test backend cannot fail");
+        self
+    }
+
+    /// The last rendered frame, with styles.
+    #[must_use]
+    pub fn buffer(&self) -> &Buffer {
+        self.terminal.backend().buffer()
+    }
+
+    /// The last rendered frame as text, one line per row with trailing
+    /// spaces removed.
+    #[must_use]
+    pub fn screen(&self) -> String {
+        screen_text(self.buffer())
+    }
+
+    /// Compare the screen with `tests/snapshots/{name}.txt`, or write it
+    /// when `ANYTUI_UPDATE_SNAPSHOTS` is set.
+    ///
+    /// # Panics
+    /// Panics with the differing lines if the screen does not match, or if
+    /// the snapshot is missing.
+    pub fn assert_snapshot(&self, name: &str) {
+        assert_snapshot(name, &self.screen());
+    }
+
+    fn dispatch(&mut self, input: CountedAction) -> &mut Self {
+        if self.running {
+            self.running = Self::check(dispatch(&mut self.app, input));
+            self.tick();
+        }
+        self
+    }
+
+    fn check<T>(result: AppResult<T>) -> T {
+        result.unwrap_or_else(|e| panic!("app returned an error: {e}"))
+    }
+}
+
+/// A buffer as text, one line per row with trailing spaces removed.
+#[must_use]
+pub fn screen_text(buffer: &Buffer) -> String {
+    let area = buffer.area;
+    let mut text = String::new();
+    for y in area.top()..area.bottom() {
+        let mut line = String::new();
+        let mut x = area.left();
+        while x < area.right() {
+            let cell = &buffer[(x, y)];
+            line.push_str(cell.symbol());
+            // Wide characters cover the next cell too
+            x += u16::try_from(symbol_width(cell.symbol()))
+                .unwrap_or(1)
+                .max(1);
+        }
+        let _ = writeln!(text, "{}", line.trim_end());
+    }
+    text
+}
+
+fn symbol_width(symbol: &str) -> usize {
+    ratatui::text::Span::raw(symbol).width()
+}
+
+/// Compare `actual` with the snapshot `name` of the crate under test.
+///
+/// # Panics
+/// Panics if they differ or the snapshot is missing, unless
+/// `ANYTUI_UPDATE_SNAPSHOTS` is set, in which case the snapshot is written.
+pub fn assert_snapshot(name: &str, actual: &str) {
+    let path = snapshot_path(name);
+    if std::env::var_os(UPDATE_SNAPSHOTS_VAR).is_some() {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).expect("create the snapshot directory");
+        }
+        std::fs::write(&path, actual).expect("write the snapshot");
+        return;
+    }
+    let Ok(expected) = std::fs::read_to_string(&path) else {
+        panic!(
+            "missing snapshot {}, run with {UPDATE_SNAPSHOTS_VAR}=1 to create it\n{actual}",
+            path.display()
+        );
+    };
+    assert!(
+        expected == actual,
+        "screen differs from snapshot {}, run with {UPDATE_SNAPSHOTS_VAR}=1 to accept it\n{}",
+        path.display(),
+        diff(&expected, actual)
+    );
+}
+
+fn snapshot_path(name: &str) -> PathBuf {
+    let dir = std::env::var_os("CARGO_MANIFEST_DIR").map_or_else(PathBuf::new, PathBuf::from);
+    dir.join("tests")
+        .join("snapshots")
+        .join(format!("{name}.txt"))
+}
+
+/// Line by line difference: `-` expected, `+` actual.
+fn diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+    let mut out = String::new();
+    for i in 0..expected.len().max(actual.len()) {
+        match (expected.get(i), actual.get(i)) {
+            (Some(e), Some(a)) if e == a => {
+                let _ = writeln!(out, "  {e}");
+            }
+            (e, a) => {
+                if let Some(e) = e {
+                    let _ = writeln!(out, "- {e}");
+                }
+                if let Some(a) = a {
+                    let _ = writeln!(out, "+ {a}");
+                }
+            }
+        }
+    }
+    out
+}
@@ -1,9 +1,45 @@
+//! Colour palettes.
+//!
+//! The active theme is read once from `~/.config/anytui/theme.toml` and
+//! returned by [`Theme::default`], so every app picks it up automatically:
+//!
+//! ```toml
+//! preset = "gruvbox"      # dark, light, gruvbox, solarized-dark, solarized-light, nord
+//! color_depth = "auto"    # auto, truecolor, 256, 16
+//!
+//! [colors]
+//! accent = "#fabd2f"
+//! error = "red"
+//! ```
+//!
+//! `ANYTUI_THEME` overrides the preset from the file.
+
+use std::sync::LazyLock;
+
 use ratatui::{
     style::{Color, Modifier, Style},
     widgets::{BorderType, block::Title},
 };
 
+use crate::config::{self, ConfigFile, Section, Value};
+
+/// Config file name inside the anytui config directory.
+pub const THEME_FILE: &str = "theme.toml";
+
+/// Names accepted by [`Theme::preset`].
+pub const PRESETS: &[&str] = &[
+    "dark",
+    "light",
+    "gruvbox",
+    "solarized-dark",
+    "solarized-light",
+    "nord",
+];
+
+static ACTIVE: LazyLock<(Theme, Vec<String>)> = LazyLock::new(Theme::load);
+
 // TUI Suite brand colors - clean, modern palette
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Theme {
     pub bg: Color,
     pub fg: Color,
@@ -17,8 +53,75 @@ pub struct Theme {
     pub muted: Color,
 }
 
+/// The active theme from `theme.toml`, or [`Theme::dark`] if there is none.
 impl Default for Theme {
     fn default() -> Self {
+        ACTIVE.0.clone()
+    }
+}
+
+/// `TERM` names of terminals with only 8 or 16 colours.
+const BASIC_TERMS: &[&str] = &["linux", "vt100", "vt102", "vt220", "ansi", "dumb"];
+
+/// How many colours the terminal can show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorDepth {
+    TrueColor,
+    Ansi256,
+    Ansi16,
+}
+
+impl ColorDepth {
+    /// Guess the colour depth from `COLORTERM` and `TERM`.
+    #[must_use]
+    pub fn detect() -> Self {
+        let colorterm = std::env::var("COLORTERM").unwrap_or_default();
+        let term = std::env::var("TERM").unwrap_or_default();
+        Self::from_env(&colorterm, &term)
+    }
+
+    /// Truecolor when `COLORTERM` says so, 16 colours for terminals known to
+    /// have no more, such as the Linux console, and 256 otherwise: most
+    /// terminals have them, but `COLORTERM` is often unset or lost over SSH.
+    #[must_use]
+    pub fn from_env(colorterm: &str, term: &str) -> Self {
+        let name = term.split('-').next().unwrap_or_default();
+        if matches!(colorterm, "truecolor" | "24bit") || term.contains("direct") {
+            Self::TrueColor
+        } else if BASIC_TERMS.contains(&name)
+            || term.ends_with("-16color")
+            || term.ends_with("-color")
+        {
+            Self::Ansi16
+        } else {
+            Self::Ansi256
+        }
+    }
+
+    /// Parse a `color_depth` setting; `auto` detects.
+    #[must_use]
+    pub fn from_setting(value: &Value) -> Option<Self> {
+        match value {
+            Value::String(s) => match s.as_str() {
+                "auto" => Some(Self::detect()),
+                "truecolor" | "24bit" => Some(Self::TrueColor),
+                "256" => Some(Self::Ansi256),
+                "16" => Some(Self::Ansi16),
+                _ => None,
+            },
+            Value::Integer(256) => Some(Self::Ansi256),
+            Value::Integer(16) => Some(Self::Ansi16),
+            _ => None,
+        }
+    }
+}
+
+impl Theme {
+    pub const BORDER_TYPE: BorderType = BorderType::Rounded;
+
+    /// The built-in dark palette.
+    #[must_use]
+    pub fn dark() -> Self {
         Self {
             bg: Color::Reset,
             fg: Color::Rgb(220, 220, 220),          // Soft white
@@ -32,10 +135,204 @@ impl Default for Theme {
             muted: Color::Rgb(120, 120, 120),       // Medium gray
         }
     }
-}
 
-impl Theme {
-    pub const BORDER_TYPE: BorderType = BorderType::Rounded;
+    /// The built-in palette for light terminal backgrounds.
+    #[must_use]
+    pub fn light() -> Self {
+        Self {
+            bg: Color::Reset,
+            fg: Color::Rgb(40, 40, 40),
+            accent: Color::Rgb(26, 115, 232),
+            accent_dim: Color::Rgb(190, 190, 190),
+            selected_bg: Color::Rgb(26, 115, 232),
+            selected_fg: Color::Rgb(255, 255, 255),
+            error: Color::Rgb(197, 34, 31),
+            success: Color::Rgb(24, 128, 56),
+            warning: Color::Rgb(176, 96, 0),
+            muted: Color::Rgb(120, 120, 120),
+        }
+    }
+
+    /// Look up a built-in palette by name (see [`PRESETS`]).
+    #[must_use]
+    pub fn preset(name: &str) -> Option<Self> {
+        let theme = match name {
+            "dark" => Self::dark(),
+            "light" => Self::light(),
+            "gruvbox" => Self {
+                bg: Color::Reset,
+                fg: Color::Rgb(235, 219, 178),
+                accent: Color::Rgb(131, 165, 152),
+                accent_dim: Color::Rgb(80, 73, 69),
+                selected_bg: Color::Rgb(131, 165, 152),
+                selected_fg: Color::Rgb(40, 40, 40),
+                error: Color::Rgb(251, 73, 52),
+                success: Color::Rgb(184, 187, 38),
+                warning: Color::Rgb(250, 189, 47),
+                muted: Color::Rgb(146, 131, 116),
+            },
+            "solarized-dark" => Self {
+                bg: Color::Reset,
+                fg: Color::Rgb(147, 161, 161),
+                accent: Color::Rgb(38, 139, 210),
+                accent_dim: Color::Rgb(88, 110, 117),
+                selected_bg: Color::Rgb(38, 139, 210),
+                selected_fg: Color::Rgb(253, 246, 227),
+                error: Color::Rgb(220, 50, 47),
+                success: Color::Rgb(133, 153, 0),
+                warning: Color::Rgb(181, 137, 0),
+                muted: Color::Rgb(101, 123, 131),
+            },
+            "solarized-light" => Self {
+                bg: Color::Reset,
+                fg: Color::Rgb(88, 110, 117),
+                accent: Color::Rgb(38, 139, 210),
+                accent_dim: Color::Rgb(147, 161, 161),
+                selected_bg: Color::Rgb(38, 139, 210),
+                selected_fg: Color::Rgb(253, 246, 227),
+                error: Color::Rgb(220, 50, 47),
+                success: Color::Rgb(133, 153, 0),
+                warning: Color::Rgb(181, 137, 0),
+                muted: Color::Rgb(131, 148, 150),
+            },
+            "nord" => Self {
+                bg: Color::Reset,
+                fg: Color::Rgb(216, 222, 233),
+                accent: Color::Rgb(136, 192, 208),
+                accent_dim: Color::Rgb(76, 86, 106),
+                selected_bg: Color::Rgb(136, 192, 208),
+                selected_fg: Color::Rgb(46, 52, 64),
+                error: Color::Rgb(191, 97, 106),
+                success: Color::Rgb(163, 190, 140),
+                warning: Color::Rgb(235, 203, 139),
+                muted: Color::Rgb(124, 134, 154),
+            },
+            _ => return None,
+        };
+        Some(theme)
+    }
+
+    /// Read the theme from `theme.toml` and adapt it to the terminal.
+    /// Returns the theme and any problems found in the file.
+    #[must_use]
+    pub fn load() -> (Self, Vec<String>) {
+        let mut warnings = Vec::new();
+        let mut file = match config::load(THEME_FILE) {
+            None => ConfigFile::default(),
+            Some(Ok(file)) => file,
+            Some(Err(e)) => {
+                warnings.push(e);
+                ConfigFile::default()
+            }
+        };
+
+        if let Ok(name) = std::env::var("ANYTUI_THEME")
+            && !name.is_empty()
+        {
+            if Self::preset(&name).is_some() {
+                file.sections.push(Section {
+                    name: String::new(),
+                    entries: vec![("preset".to_string(), Value::String(name))],
+                });
+            } else {
+                warnings.push(format!("ANYTUI_THEME: unknown preset `{name}`"));
+            }
+        }
+
+        let (theme, file_warnings) = Self::from_config(&file);
+        warnings.extend(file_warnings);
+        (theme, warnings)
+    }
+
+    /// Problems found while loading the active theme.
+    #[must_use]
+    pub fn load_warnings() -> &'static [String] {
+        &ACTIVE.1
+    }
+
+    /// Build a theme from a parsed `theme.toml`: the preset, then `[colors]`
+    /// overrides, then the colour depth conversion.
+    #[must_use]
+    pub fn from_config(file: &ConfigFile) -> (Self, Vec<String>) {
+        let mut warnings = Vec::new();
+        let mut warn = |msg: String| warnings.push(format!("{THEME_FILE}: {msg}"));
+
+        let mut theme = match file.get("", "preset") {
+            None => Self::dark(),
+            Some(Value::String(name)) => Self::preset(name).unwrap_or_else(|| {
+                warn(format!("unknown preset `{name}`"));
+                Self::dark()
+            }),
+            Some(other) => {
+                warn(format!("`preset` must be a string, got {other}"));
+                Self::dark()
+            }
+        };
+
+        for section in file.sections("colors") {
+            for (key, value) in &section.entries {
+                let Some(slot) = theme.slot_mut(key) else {
+                    warn(format!("[colors] unknown colour `{key}`"));
+                    continue;
+                };
+                match value.as_str().map(str::parse::<Color>) {
+                    Some(Ok(color)) => *slot = color,
+                    _ => warn(format!("[colors] invalid colour {value} for `{key}`")),
+                }
+            }
+        }
+
+        let depth = match file.get("", "color_depth") {
+            None => ColorDepth::detect(),
+            Some(value) => ColorDepth::from_setting(value).unwrap_or_else(|| {
+                warn(format!("invalid color_depth {value}"));
+                ColorDepth::detect()
+            }),
+        };
+
+        (theme.with_color_depth(depth), warnings)
+    }
+
+    /// Convert every colour so it renders on a terminal with `depth` colours.
+    #[must_use]
+    pub fn with_color_depth(mut self, depth: ColorDepth) -> Self {
+        for slot in self.slots_mut() {
+            *slot = downsample(*slot, depth);
+        }
+        self
+    }
+
+    fn slot_mut(&mut self, name: &str) -> Option<&mut Color> {
+        let slot = match name {
+            "bg" => &mut self.bg,
+            "fg" => &mut self.fg,
+            "accent" => &mut self.accent,
+            "accent_dim" => &mut self.accent_dim,
+            "selected_bg" => &mut self.selected_bg,
+            "selected_fg" => &mut self.selected_fg,
+            "error" => &mut self.error,
+            "success" => &mut self.success,
+            "warning" => &mut self.warning,
+            "muted" => &mut self.muted,
+            _ => return None,
+        };
+        Some(slot)
+    }
+
+    fn slots_mut(&mut self) -> [&mut Color; 10] {
+        [
+            &mut self.bg,
+            &mut self.fg,
+            &mut self.accent,
+            &mut self.accent_dim,
+            &mut self.selected_bg,
+            &mut self.selected_fg,
+            &mut self.error,
+            &mut self.success,
+            &mut self.warning,
+            &mut self.muted,
+        ]
+    }
 
     #[must_use]
     pub fn normal(&self) -> Style {
@@ -99,3 +396,87 @@ impl Theme {
         Title::from(format!(" {name} "))
     }
 }
+
+/// The 16 ANSI colours with typical xterm values.
+const ANSI16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// Channel levels of the 6x6x6 cube in the 256-colour palette.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+fn downsample(color: Color, depth: ColorDepth) -> Color {
+    match (depth, color) {
+        (ColorDepth::TrueColor, _) => color,
+        (ColorDepth::Ansi256, Color::Rgb(r, g, b)) => Color::Indexed(nearest_256((r, g, b))),
+        (ColorDepth::Ansi16, Color::Rgb(r, g, b)) => nearest_16((r, g, b)),
+        (ColorDepth::Ansi16, Color::Indexed(i)) if i >= 16 => nearest_16(indexed_rgb(i)),
+        _ => color,
+    }
+}
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| u32::from(x.abs_diff(y)).pow(2);
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+fn nearest_16(rgb: (u8, u8, u8)) -> Color {
+    ANSI16
+        .iter()
+        .min_by_key(|(_, c)| distance(rgb, *c))
+        .map_or(Color::Reset, |(color, _)| *color)
+}
+
+fn nearest_256(rgb: (u8, u8, u8)) -> u8 {
+    // Closest colour in the 6x6x6 cube (16..=231)
+    let level = |v: u8| {
+        (0..6u8)
+            .min_by_key(|&i| CUBE_LEVELS[usize::from(i)].abs_diff(v))
+            .unwrap_or(0)
+    };
+    let (r, g, b) = (level(rgb.0), level(rgb.1), level(rgb.2));
+    let cube = 16 + 36 * r + 6 * g + b;
+
+    // Closest step of the grayscale ramp (232..=255)
+    let avg = (u16::from(rgb.0) + u16::from(rgb.1) + u16::from(rgb.2)) / 3;
+    let step = u8::try_from(avg.saturating_sub(3) / 10)
+        .unwrap_or(23)
+        .min(23);
+    let gray = 232 + step;
+
+    if distance(rgb, indexed_rgb(gray)) < distance(rgb, indexed_rgb(cube)) {
+        gray
+    } else {
+        cube
+    }
+}
+
+fn indexed_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => ANSI16[usize::from(index)].1,
+        16..=231 => {
+            let i = index - 16;
+            let level = |v: u8| CUBE_LEVELS[usize::from(v)];
+            (level(i / 36), level((i / 6) % 6), level(i % 6))
+        }
+        _ => {
+            let v = 8 + 10 * (index - 232);
+            (v, v, v)
+        }
+    }
+}
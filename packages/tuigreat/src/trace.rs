@@ -0,0 +1,360 @@
+//! A debug log of what an app does, for when a backend misbehaves.
+//!
+//! Start a tool with `--debug` to log to
+//! `$XDG_STATE_HOME/anytui/<program>.log` (`~/.local/state/anytui`), or set
+//! `ANYTUI_LOG=<path>` to pick the file. Every line is a JSON object: every
+//! [`Command`](crate::runner::Command) with its output, every dispatched
+//! action and how long each tick took.
+//!
+//! ```text
+//! {"time":0.0,"kind":"start","program":"voltui","pid":4242}
+//! {"time":0.012,"kind":"command","argv":["pactl","list","sinks"],"ms":3.1,"status":0,"stdout":"Sink #52...","stderr":""}
+//! {"time":1.204,"kind":"action","app":"vol-tui","action":"down","count":5}
+//! {"time":1.205,"kind":"tick","app":"vol-tui","ms":0.4}
+//! ```
+//!
+//! Output is cut after [`OUTPUT_LIMIT`] bytes and
+//! [redacted](crate::runner::Command::redact) arguments are masked, but the
+//! output of commands is kept as is. `D` shows the latest entries in the
+//! app, see [`LogPopup`](crate::widgets::LogPopup).
+
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::Output;
+use std::sync::{LazyLock, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use serde_json::{Value, json};
+
+use crate::{Action, CountedAction, session};
+
+/// Set to a file path to write the debug log there.
+pub const LOG_VAR: &str = "ANYTUI_LOG";
+
+/// Bytes of stdout and stderr kept for each command.
+pub const OUTPUT_LIMIT: usize = 1000;
+
+/// Entries kept in memory for [`recent`].
+pub const RECENT: usize = 500;
+
+/// Ticks quicker than this are only written to the file, so that
+/// [`recent`] is not all ticks.
+const QUICK_TICK: Duration = Duration::from_millis(1);
+
+/// What an [`Entry`] records.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// A program ran, or failed to start with `error`.
+    Command {
+        argv: Vec<String>,
+        duration: Duration,
+        /// The exit code; `None` if killed by a signal or not started.
+        status: Option<i32>,
+        stdout: String,
+        stderr: String,
+        error: Option<String>,
+    },
+    /// An action was dispatched to `app`, e.g. from a key press.
+    Action {
+        app: String,
+        action: Action,
+        count: Option<usize>,
+    },
+    /// `app` ticked.
+    Tick { app: String, duration: Duration },
+}
+
+/// One line of the log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// Counts up from 0 over the run.
+    pub seq: u64,
+    /// Time since the log was started.
+    pub time: Duration,
+    pub event: Event,
+}
+
+impl Entry {
+    #[must_use]
+    pub fn to_json(&self) -> Value {
+        let time = round(self.time.as_secs_f64(), 1000.0);
+        match &self.event {
+            Event::Command {
+                argv,
+                duration,
+                status,
+                stdout,
+                stderr,
+                error,
+            } => {
+                let mut value = json!({
+                    "time": time,
+                    "kind": "command",
+                    "argv": argv,
+                    "ms": millis(*duration),
+                    "status": status,
+                    "stdout": stdout,
+                    "stderr": stderr,
+                });
+                if let Some(error) = error {
+                    value["error"] = json!(error);
+                }
+                value
+            }
+            Event::Action { app, action, count } => {
+                let mut value = json!({
+                    "time": time,
+                    "kind": "action",
+                    "app": app,
+                    "action": action.name(),
+                });
+                if let Some(count) = count {
+                    value["count"] = json!(count);
+                }
+                value
+            }
+            Event::Tick { app, duration } => json!({
+                "time": time,
+                "kind": "tick",
+                "app": app,
+                "ms": millis(*duration),
+            }),
+        }
+    }
+
+    /// The entry in one line, e.g. `$ pactl list sinks  3.1 ms, exit 0`.
+    #[must_use]
+    pub fn summary(&self) -> String {
+        match &self.event {
+            Event::Command {
+                argv,
+                duration,
+                status,
+                error,
+                ..
+            } => {
+                let outcome = match (error, status) {
+                    (Some(error), _) => error.clone(),
+                    (None, Some(status)) => format!("exit {status}"),
+                    (None, None) => "killed".to_string(),
+                };
+                format!("$ {}  {} ms, {outcome}", argv.join(" "), millis(*duration))
+            }
+            Event::Action { app, action, count } => match count {
+                Some(count) => format!("{app}: {} x{count}", action.name()),
+                None => format!("{app}: {}", action.name()),
+            },
+            Event::Tick { app, duration } => format!("{app}: tick {} ms", millis(*duration)),
+        }
+    }
+
+    /// The summary, followed by the output of a command.
+    #[must_use]
+    pub fn detail(&self) -> String {
+        let mut detail = self.summary();
+        if let Event::Command { stdout, stderr, .. } = &self.event {
+            for (name, text) in [("stdout", stdout), ("stderr", stderr)] {
+                if !text.is_empty() {
+                    detail.push_str(&format!("\n\n{name}:\n{}", text.trim_end()));
+                }
+            }
+        }
+        detail
+    }
+
+    /// Whether the entry is a command that did not succeed.
+    #[must_use]
+    pub fn failed(&self) -> bool {
+        matches!(&self.event, Event::Command { status, .. } if *status != Some(0))
+    }
+}
+
+struct Log {
+    path: PathBuf,
+    file: File,
+    started: Instant,
+    next: u64,
+    recent: VecDeque<Entry>,
+}
+
+impl Log {
+    fn open(path: PathBuf) -> io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let program = crate::app::program();
+        let start = json!({
+            "time": 0.0,
+            "kind": "start",
+            "program": program,
+            "pid": std::process::id(),
+        });
+        writeln!(file, "{start}")?;
+        Ok(Self {
+            path,
+            file,
+            started: Instant::now(),
+            next: 0,
+            recent: VecDeque::new(),
+        })
+    }
+
+    /// The log asked for with `ANYTUI_LOG` or `--debug`.
+    fn from_env() -> Option<Self> {
+        let path = match std::env::var_os(LOG_VAR).filter(|path| !path.is_empty()) {
+            Some(path) => PathBuf::from(path),
+            None if std::env::args().any(|arg| arg == "--debug") => {
+                let program = crate::app::program()?;
+                session::state_dir()?.join(format!("{program}.log"))
+            }
+            None => return None,
+        };
+        Self::open(path.clone())
+            .inspect_err(|e| {
+                eprintln!(
+                    "warning: could not open the debug log {}: {e}",
+                    path.display()
+                );
+            })
+            .ok()
+    }
+}
+
+static LOG: LazyLock<Mutex<Option<Log>>> = LazyLock::new(|| Mutex::new(Log::from_env()));
+
+/// Open the log asked for, while a problem can still be printed.
+pub(crate) fn init() {
+    LazyLock::force(&LOG);
+}
+
+fn with_log<T>(f: impl FnOnce(&mut Option<Log>) -> T) -> T {
+    f(&mut LOG.lock().unwrap_or_else(PoisonError::into_inner))
+}
+
+/// Whether commands and actions are being logged.
+#[must_use]
+pub fn enabled() -> bool {
+    with_log(|log| log.is_some())
+}
+
+/// The file being written, if logging.
+#[must_use]
+pub fn path() -> Option<PathBuf> {
+    with_log(|log| log.as_ref().map(|log| log.path.clone()))
+}
+
+/// Log to `path` from now on, appending if it exists.
+///
+/// # Errors
+/// Returns an error if the file cannot be opened; logging is left as it was.
+pub fn start(path: impl Into<PathBuf>) -> io::Result<()> {
+    let opened = Log::open(path.into())?;
+    with_log(|log| *log = Some(opened));
+    Ok(())
+}
+
+/// Stop logging.
+pub fn stop() {
+    with_log(|log| *log = None);
+}
+
+/// The latest entries, oldest first. Ticks under a millisecond are left
+/// out.
+#[must_use]
+pub fn recent() -> Vec<Entry> {
+    with_log(|log| {
+        log.as_ref()
+            .map(|log| log.recent.iter().cloned().collect())
+            .unwrap_or_default()
+    })
+}
+
+/// Add the event made by `event` if logging, to the file and [`recent`].
+fn record(event: impl FnOnce() -> Event) {
+    with_log(|log| {
+        let Some(log) = log else { return };
+        let entry = Entry {
+            seq: log.next,
+            time: log.started.elapsed(),
+            event: event(),
+        };
+        log.next += 1;
+        // A full disk should not take the app down with it
+        let _ = writeln!(log.file, "{}", entry.to_json());
+        if matches!(entry.event, Event::Tick { duration, .. } if duration < QUICK_TICK) {
+            return;
+        }
+        if log.recent.len() == RECENT {
+            log.recent.pop_front();
+        }
+        log.recent.push_back(entry);
+    });
+}
+
+/// Log a command that ran for `duration`.
+pub(crate) fn command(argv: Vec<String>, duration: Duration, output: &io::Result<Output>) {
+    record(|| match output {
+        Ok(output) => Event::Command {
+            argv,
+            duration,
+            status: output.status.code(),
+            stdout: truncate(&output.stdout),
+            stderr: truncate(&output.stderr),
+            error: None,
+        },
+        Err(e) => Event::Command {
+            argv,
+            duration,
+            status: None,
+            stdout: String::new(),
+            stderr: String::new(),
+            error: Some(e.to_string()),
+        },
+    });
+}
+
+/// Log an action dispatched to `app`. Typed characters are logged without
+/// the character, so passwords stay out of the log.
+pub(crate) fn action(app: &str, input: CountedAction) {
+    if input.action == Action::None {
+        return;
+    }
+    record(|| Event::Action {
+        app: app.to_string(),
+        action: input.action,
+        count: input.count,
+    });
+}
+
+/// Log a tick of `app` that took `duration`.
+pub(crate) fn tick(app: &str, duration: Duration) {
+    record(|| Event::Tick {
+        app: app.to_string(),
+        duration,
+    });
+}
+
+/// `text` as a string, cut after [`OUTPUT_LIMIT`] bytes.
+fn truncate(text: &[u8]) -> String {
+    let text = String::from_utf8_lossy(text);
+    if text.len() <= OUTPUT_LIMIT {
+        return text.into_owned();
+    }
+    let mut end = OUTPUT_LIMIT;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}... ({} bytes)", &text[..end], text.len())
+}
+
+fn millis(duration: Duration) -> f64 {
+    round(duration.as_secs_f64() * 1000.0, 10.0)
+}
+
+/// `value` rounded to `1 / scale`.
+fn round(value: f64, scale: f64) -> f64 {
+    (value * scale).round() / scale
+}
@@ -1,11 +1,25 @@
+pub mod command;
+pub mod confirm;
+pub mod detail;
 pub mod gauge;
 pub mod help;
+pub mod input;
 pub mod list;
+pub mod log;
+pub mod messages;
 pub mod search;
+pub mod table;
 pub mod tabs;
 
+pub use command::{CommandEvent, CommandLine, CommandSpec, Invocation};
+pub use confirm::{Choice, ConfirmEvent, ConfirmPopup};
+pub use detail::DetailPopup;
 pub use gauge::VolumeGauge;
 pub use help::{HelpPopup, centered_rect};
-pub use list::{SearchDirection, SelectableList};
-pub use search::SearchPopup;
+pub use input::TextInput;
+pub use list::{MatchMode, SearchDirection, SelectableList};
+pub use log::LogPopup;
+pub use messages::MessagePopup;
+pub use search::{SearchPopup, SearchState};
+pub use table::{Column, SelectableTable, SortOrder};
 pub use tabs::Tabs;
@@ -0,0 +1,369 @@
+//! Ex-style command line opened with `:`.
+//!
+//! Apps register their commands up front, forward input-mode actions to
+//! [`CommandLine::handle_action`] while the line is open and run the
+//! [`Invocation`] it returns on Enter. Tab completes command names and any
+//! argument values set with [`CommandLine::set_arguments`]; Up and Down
+//! walk the history, filtered by what has been typed.
+
+use std::str::FromStr;
+
+use ratatui::{
+    Frame,
+    layout::Rect,
+    text::{Line, Span},
+    widgets::{Clear, Paragraph},
+};
+
+use super::TextInput;
+use crate::{Action, Theme};
+
+/// Entries kept in the command history.
+const HISTORY_LIMIT: usize = 100;
+
+/// A command that can be typed on the command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandSpec {
+    pub name: &'static str,
+    /// Argument hint shown while typing, e.g. `<0-100>`; empty for none.
+    pub args: &'static str,
+    pub description: &'static str,
+}
+
+impl CommandSpec {
+    #[must_use]
+    pub const fn new(name: &'static str, args: &'static str, description: &'static str) -> Self {
+        Self {
+            name,
+            args,
+            description,
+        }
+    }
+
+    fn hint(&self) -> String {
+        if self.args.is_empty() {
+            self.description.to_string()
+        } else {
+            format!("{}  {}", self.args, self.description)
+        }
+    }
+}
+
+/// A submitted command: its registered name and the rest of the line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Invocation {
+    pub name: &'static str,
+    pub args: String,
+    usage: &'static str,
+}
+
+impl Invocation {
+    /// The argument text, or an error showing the usage if it is empty.
+    ///
+    /// # Errors
+    /// Returns a status message if no argument was given.
+    pub fn arg(&self) -> Result<&str, String> {
+        match self.args.trim() {
+            "" => Err(self.usage_error()),
+            arg => Ok(arg),
+        }
+    }
+
+    /// Parse the argument, e.g. `:volume 40` as a number.
+    ///
+    /// # Errors
+    /// Returns a status message showing the usage if parsing fails.
+    pub fn parse<T: FromStr>(&self) -> Result<T, String> {
+        self.arg()?.parse().map_err(|_| self.usage_error())
+    }
+
+    /// Status message for an argument the app rejects, e.g. out of range.
+    #[must_use]
+    pub fn usage_error(&self) -> String {
+        format!("Invalid argument, usage: :{} {}", self.name, self.usage)
+    }
+}
+
+/// What a key did to the command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandEvent {
+    /// The line is still open.
+    Editing,
+    /// The line was closed without running anything.
+    Cancelled,
+    /// Enter was pressed on a known command.
+    Run(Invocation),
+    /// Enter was pressed on something that is not a command.
+    Error(String),
+}
+
+#[derive(Debug, Clone)]
+struct Completion {
+    base: String,
+    candidates: Vec<String>,
+    index: usize,
+}
+
+/// A `:` command line with completion and history.
+#[derive(Debug, Clone, Default)]
+pub struct CommandLine {
+    commands: Vec<CommandSpec>,
+    arguments: Vec<(&'static str, Vec<String>)>,
+    input: TextInput,
+    active: bool,
+    history: Vec<String>,
+    history_pos: Option<usize>,
+    draft: String,
+    completion: Option<Completion>,
+}
+
+impl CommandLine {
+    #[must_use]
+    pub fn new(commands: Vec<CommandSpec>) -> Self {
+        Self {
+            commands,
+            ..Self::default()
+        }
+    }
+
+    /// Registered commands, e.g. for the help popup.
+    #[must_use]
+    pub fn commands(&self) -> &[CommandSpec] {
+        &self.commands
+    }
+
+    /// Values offered when completing the argument of `command`, such as
+    /// network names for `:connect`.
+    pub fn set_arguments<I, S>(&mut self, command: &'static str, values: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let values = values.into_iter().map(Into::into).collect();
+        match self.arguments.iter_mut().find(|(name, _)| *name == command) {
+            Some((_, existing)) => *existing = values,
+            None => self.arguments.push((command, values)),
+        }
+    }
+
+    /// Open an empty command line.
+    pub fn open(&mut self) {
+        self.active = true;
+        self.input.clear();
+        self.history_pos = None;
+        self.completion = None;
+    }
+
+    pub fn close(&mut self) {
+        self.active = false;
+        self.input.clear();
+        self.history_pos = None;
+        self.completion = None;
+    }
+
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// The text typed after the `:`.
+    #[must_use]
+    pub fn input(&self) -> &str {
+        self.input.text()
+    }
+
+    /// Submitted lines, oldest first.
+    #[must_use]
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+
+    /// Handle an input-mode action while the line is open.
+    pub fn handle_action(&mut self, action: Action) -> CommandEvent {
+        if action != Action::Char('\t') {
+            self.completion = None;
+        }
+        match action {
+            Action::Char('\t') => self.complete(),
+            Action::Back if self.input.is_empty() => {
+                self.close();
+                return CommandEvent::Cancelled;
+            }
+            Action::Quit => {
+                self.close();
+                return CommandEvent::Cancelled;
+            }
+            Action::Up => self.history_prev(),
+            Action::Down => self.history_next(),
+            Action::Select => return self.submit(),
+            action => {
+                if self.input.handle_action(action) {
+                    self.history_pos = None;
+                }
+            }
+        }
+        CommandEvent::Editing
+    }
+
+    fn submit(&mut self) -> CommandEvent {
+        let line = self.input.text().trim().to_string();
+        self.close();
+        if line.is_empty() {
+            return CommandEvent::Cancelled;
+        }
+        self.history.retain(|h| *h != line);
+        self.history.push(line.clone());
+        if self.history.len() > HISTORY_LIMIT {
+            self.history.remove(0);
+        }
+        self.parse(&line)
+    }
+
+    /// The command in `line` without the `:`, as if it was typed and
+    /// submitted, e.g. from the [control socket](crate::ipc).
+    #[must_use]
+    pub fn parse(&self, line: &str) -> CommandEvent {
+        let line = line.trim();
+        if line.is_empty() {
+            return CommandEvent::Cancelled;
+        }
+        let (name, args) = line.split_once(' ').unwrap_or((line, ""));
+        match self.find(name) {
+            Ok(spec) => CommandEvent::Run(Invocation {
+                name: spec.name,
+                args: args.trim().to_string(),
+                usage: spec.args,
+            }),
+            Err(e) => CommandEvent::Error(e),
+        }
+    }
+
+    /// Look up a command by name or unambiguous prefix (`:vol` for `:volume`).
+    fn find(&self, name: &str) -> Result<&CommandSpec, String> {
+        if let Some(spec) = self.commands.iter().find(|c| c.name == name) {
+            return Ok(spec);
+        }
+        let mut matches = self.commands.iter().filter(|c| c.name.starts_with(name));
+        match (matches.next(), matches.next()) {
+            (Some(spec), None) => Ok(spec),
+            (Some(_), Some(_)) => Err(format!("Invalid command: {name} is ambiguous")),
+            _ => Err(format!("Invalid command: {name}")),
+        }
+    }
+
+    fn complete(&mut self) {
+        if let Some(completion) = &mut self.completion {
+            completion.index = (completion.index + 1) % completion.candidates.len();
+            self.input.set_text(format!(
+                "{}{}",
+                completion.base, completion.candidates[completion.index]
+            ));
+            return;
+        }
+
+        let input = self.input.text();
+        let (base, candidates, single_suffix) = match input.split_once(' ') {
+            None => {
+                let names: Vec<String> = self
+                    .commands
+                    .iter()
+                    .filter(|c| c.name.starts_with(input))
+                    .map(|c| c.name.to_string())
+                    .collect();
+                (String::new(), names, " ")
+            }
+            Some((name, typed)) => {
+                let Ok(spec) = self.find(name) else { return };
+                let typed = typed.trim_start().to_lowercase();
+                let values = self
+                    .arguments
+                    .iter()
+                    .find(|(command, _)| *command == spec.name)
+                    .map(|(_, values)| {
+                        values
+                            .iter()
+                            .filter(|v| v.to_lowercase().starts_with(&typed))
+                            .cloned()
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                (format!("{} ", spec.name), values, "")
+            }
+        };
+
+        match candidates.len() {
+            0 => {}
+            1 => self
+                .input
+                .set_text(format!("{base}{}{single_suffix}", candidates[0])),
+            _ => {
+                self.input.set_text(format!("{base}{}", candidates[0]));
+                self.completion = Some(Completion {
+                    base,
+                    candidates,
+                    index: 0,
+                });
+            }
+        }
+        self.history_pos = None;
+    }
+
+    fn history_prev(&mut self) {
+        if self.history_pos.is_none() {
+            self.draft = self.input.text().to_string();
+        }
+        let end = self.history_pos.unwrap_or(self.history.len());
+        if let Some(i) = self.history[..end]
+            .iter()
+            .rposition(|h| h.starts_with(&self.draft))
+        {
+            self.history_pos = Some(i);
+            self.input.set_text(self.history[i].clone());
+        }
+    }
+
+    fn history_next(&mut self) {
+        let Some(pos) = self.history_pos else { return };
+        match self.history[pos + 1..]
+            .iter()
+            .position(|h| h.starts_with(&self.draft))
+        {
+            Some(i) => {
+                self.history_pos = Some(pos + 1 + i);
+                self.input.set_text(self.history[pos + 1 + i].clone());
+            }
+            None => {
+                self.history_pos = None;
+                self.input.set_text(std::mem::take(&mut self.draft));
+            }
+        }
+    }
+
+    /// Render the line into a one-row area, usually over the status bar.
+    pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let mut spans = vec![Span::styled(" :", theme.highlight())];
+        spans.extend(self.input.spans(theme.normal(), true));
+
+        if let Some(completion) = &self.completion {
+            for (i, candidate) in completion.candidates.iter().enumerate() {
+                let style = if i == completion.index {
+                    theme.highlight()
+                } else {
+                    theme.muted()
+                };
+                spans.push(Span::raw("  "));
+                spans.push(Span::styled(candidate.clone(), style));
+            }
+        } else {
+            let name = self.input.text().split(' ').next().unwrap_or_default();
+            if !name.is_empty()
+                && let Ok(spec) = self.find(name)
+            {
+                spans.push(Span::styled(format!("  {}", spec.hint()), theme.muted()));
+            }
+        }
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(Line::from(spans)), area);
+    }
+}
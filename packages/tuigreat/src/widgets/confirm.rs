@@ -0,0 +1,159 @@
+use ratatui::{
+    Frame,
+    layout::Alignment,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Padding, Paragraph, Wrap},
+};
+
+use super::centered_rect;
+use crate::{Action, Theme};
+
+/// A button of a [`ConfirmPopup`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Choice {
+    Yes,
+    #[default]
+    No,
+}
+
+/// Result of a key press in a [`ConfirmPopup`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfirmEvent {
+    /// Still waiting for an answer.
+    Pending,
+    Confirmed,
+    Cancelled,
+}
+
+/// A yes/no question before a destructive operation.
+///
+/// `y` and `n` answer directly, Left/Right or Tab move between the buttons
+/// and Enter picks the focused one. Space ticks "don't ask again", which
+/// [`Confirmations::confirmed`](crate::Confirmations::confirmed) remembers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfirmPopup {
+    operation: String,
+    title: String,
+    message: String,
+    choice: Choice,
+    dont_ask: bool,
+}
+
+impl ConfirmPopup {
+    /// Ask before `operation` (its name in `confirm.toml`). The focus starts
+    /// on "No".
+    pub fn new(operation: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            operation: operation.into(),
+            title: " Confirm ".to_string(),
+            message: message.into(),
+            choice: Choice::default(),
+            dont_ask: false,
+        }
+    }
+
+    #[must_use]
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = format!(" {title} ");
+        self
+    }
+
+    /// Focus `choice` first, so Enter picks it.
+    #[must_use]
+    pub fn with_default(mut self, choice: Choice) -> Self {
+        self.choice = choice;
+        self
+    }
+
+    #[must_use]
+    pub fn operation(&self) -> &str {
+        &self.operation
+    }
+
+    #[must_use]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    #[must_use]
+    pub fn choice(&self) -> Choice {
+        self.choice
+    }
+
+    #[must_use]
+    pub fn dont_ask(&self) -> bool {
+        self.dont_ask
+    }
+
+    pub fn handle_action(&mut self, action: Action) -> ConfirmEvent {
+        match action {
+            // `y`, `n` and `N` arrive as yank and search by default
+            Action::Char('y' | 'Y') | Action::Yank => return ConfirmEvent::Confirmed,
+            Action::Char('n' | 'N')
+            | Action::SearchNext
+            | Action::SearchPrev
+            | Action::Back
+            | Action::Quit => return ConfirmEvent::Cancelled,
+            Action::Select => {
+                return match self.choice {
+                    Choice::Yes => ConfirmEvent::Confirmed,
+                    Choice::No => ConfirmEvent::Cancelled,
+                };
+            }
+            Action::Left => self.choice = Choice::Yes,
+            Action::Right => self.choice = Choice::No,
+            Action::Char('\t') | Action::Up | Action::Down => {
+                self.choice = match self.choice {
+                    Choice::Yes => Choice::No,
+                    Choice::No => Choice::Yes,
+                };
+            }
+            Action::Char(' ') => self.dont_ask = !self.dont_ask,
+            _ => {}
+        }
+        ConfirmEvent::Pending
+    }
+
+    pub fn render(&self, frame: &mut Frame, theme: &Theme) {
+        let width = 50;
+        let text_width = usize::from(width - 4);
+        let text_lines = self.message.chars().count().div_ceil(text_width).max(1);
+        let height = u16::try_from(text_lines).unwrap_or(1).saturating_add(6);
+        let area = centered_rect(width, height, frame.area());
+        frame.render_widget(Clear, area);
+
+        let button = |label: &'static str, choice: Choice| {
+            if self.choice == choice {
+                Span::styled(format!("[ {label} ]"), theme.selected())
+            } else {
+                Span::styled(format!("  {label}  "), theme.normal())
+            }
+        };
+        let check = if self.dont_ask { "[x]" } else { "[ ]" };
+        let mut lines = vec![Line::from(self.message.clone()), Line::from("")];
+        lines.push(
+            Line::from(vec![
+                button("Yes", Choice::Yes),
+                Span::raw("   "),
+                button("No", Choice::No),
+            ])
+            .alignment(Alignment::Center),
+        );
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("{check} Don't ask again (Space)"),
+            theme.muted(),
+        )));
+
+        let popup = Paragraph::new(lines).wrap(Wrap { trim: true }).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(Theme::BORDER_TYPE)
+                .border_style(theme.warning())
+                .title(self.title.as_str())
+                .title_style(theme.title())
+                .padding(Padding::horizontal(1)),
+        );
+        frame.render_widget(popup, area);
+    }
+}
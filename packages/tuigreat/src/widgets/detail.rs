@@ -0,0 +1,79 @@
+use ratatui::{
+    Frame,
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+};
+
+use super::help::centered_rect_percent;
+use crate::{Action, StatusMessage, Theme};
+
+/// A scrollable popup with the full text behind a status message, such as
+/// the stderr of a failed command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetailPopup {
+    title: String,
+    text: String,
+    scroll: u16,
+}
+
+impl DetailPopup {
+    pub fn new(title: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            text: text.into(),
+            scroll: 0,
+        }
+    }
+
+    /// The detail of `status`, titled with its message.
+    #[must_use]
+    pub fn for_status(status: &StatusMessage) -> Option<Self> {
+        let detail = status.detail.as_ref()?;
+        Some(Self::new(format!(" {} ", status.text), detail.clone()))
+    }
+
+    #[must_use]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Scroll with the navigation keys. Returns `false` once the popup is
+    /// closed with back, quit or the details key.
+    pub fn handle_action(&mut self, action: Action) -> bool {
+        let last = u16::try_from(self.text.lines().count().saturating_sub(1)).unwrap_or(u16::MAX);
+        match action {
+            Action::Down => self.scroll = (self.scroll + 1).min(last),
+            Action::Up => self.scroll = self.scroll.saturating_sub(1),
+            Action::PageDown | Action::FullPageDown => self.scroll = (self.scroll + 10).min(last),
+            Action::PageUp | Action::FullPageUp => self.scroll = self.scroll.saturating_sub(10),
+            Action::Top => self.scroll = 0,
+            Action::Bottom => self.scroll = last,
+            Action::Back | Action::Quit | Action::Details | Action::Select => return false,
+            _ => {}
+        }
+        true
+    }
+
+    pub fn render(&self, frame: &mut Frame, theme: &Theme) {
+        let area = centered_rect_percent(80, 60, frame.area());
+        frame.render_widget(Clear, area);
+
+        let lines: Vec<Line> = self
+            .text
+            .lines()
+            .map(|line| Line::styled(line.to_string(), theme.normal()))
+            .collect();
+        let popup = Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(Theme::BORDER_TYPE)
+                    .border_style(theme.error())
+                    .title(self.title.as_str())
+                    .title_style(theme.title()),
+            );
+        frame.render_widget(popup, area);
+    }
+}
@@ -1,6 +1,7 @@
+use crossterm::event::{MouseEvent, MouseEventKind};
 use ratatui::{
     Frame,
-    layout::Rect,
+    layout::{Position, Rect},
     widgets::{Block, Borders, Gauge},
 };
 
@@ -9,6 +10,20 @@ use crate::Theme;
 pub struct VolumeGauge;
 
 impl VolumeGauge {
+    /// Volume steps for a scroll wheel event over a gauge drawn at `area`
+    /// (`1` for up, `-1` for down).
+    #[must_use]
+    pub fn scroll_delta(area: Rect, event: &MouseEvent) -> Option<i8> {
+        if !area.contains(Position::new(event.column, event.row)) {
+            return None;
+        }
+        match event.kind {
+            MouseEventKind::ScrollUp => Some(1),
+            MouseEventKind::ScrollDown => Some(-1),
+            _ => None,
+        }
+    }
+
     pub fn render(
         frame: &mut Frame,
         area: Rect,
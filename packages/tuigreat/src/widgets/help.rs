@@ -10,7 +10,9 @@ use crate::Theme;
 pub struct HelpPopup;
 
 impl HelpPopup {
-    pub fn render(frame: &mut Frame, bindings: &[(&str, &str)], theme: &Theme) {
+    /// Render a list of `(keys, description)` rows.
+    /// Use [`Keymap::help`](crate::Keymap::help) to build rows from the active keymap.
+    pub fn render<K: AsRef<str>>(frame: &mut Frame, bindings: &[(K, &str)], theme: &Theme) {
         let area = centered_rect_percent(60, 70, frame.area());
 
         frame.render_widget(Clear, area);
@@ -19,7 +21,7 @@ impl HelpPopup {
             .iter()
             .map(|(key, desc)| {
                 Line::from(vec![
-                    Span::styled(format!("{key:>12}"), theme.highlight()),
+                    Span::styled(format!("{:>12}", key.as_ref()), theme.highlight()),
                     Span::raw("  "),
                     Span::styled(*desc, theme.normal()),
                 ])
@@ -0,0 +1,214 @@
+//! Single-line text input with a cursor.
+//!
+//! Forward input-mode actions to [`TextInput::handle_action`]; it handles
+//! typing, Backspace, paste and the [`Edit`] keys from
+//! [`KeyHandler::parse_input_mode`](crate::KeyHandler::parse_input_mode),
+//! and leaves Enter, Esc and Backspace on an empty input to the caller.
+
+use ratatui::{
+    style::{Modifier, Style},
+    text::Span,
+};
+
+use crate::{Action, Edit};
+
+/// Editable text with a cursor, optionally masked for passwords.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TextInput {
+    text: String,
+    /// Byte offset into `text`, always on a char boundary.
+    cursor: usize,
+    masked: bool,
+}
+
+impl TextInput {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// An input shown as `*` for each character.
+    #[must_use]
+    pub fn masked() -> Self {
+        Self {
+            masked: true,
+            ..Self::default()
+        }
+    }
+
+    #[must_use]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Cursor position in characters from the start.
+    #[must_use]
+    pub fn cursor(&self) -> usize {
+        self.text[..self.cursor].chars().count()
+    }
+
+    /// Text before the cursor.
+    #[must_use]
+    pub fn before_cursor(&self) -> &str {
+        &self.text[..self.cursor]
+    }
+
+    /// Text from the cursor on.
+    #[must_use]
+    pub fn after_cursor(&self) -> &str {
+        &self.text[self.cursor..]
+    }
+
+    /// Replace the text and move the cursor to the end.
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        self.text = text.into();
+        self.cursor = self.text.len();
+    }
+
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.cursor = 0;
+    }
+
+    /// Take the text out, leaving the input empty.
+    pub fn take(&mut self) -> String {
+        self.cursor = 0;
+        std::mem::take(&mut self.text)
+    }
+
+    /// Insert a character at the cursor.
+    pub fn insert(&mut self, c: char) {
+        self.text.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+    }
+
+    /// Insert text at the cursor, dropping newlines and other control
+    /// characters.
+    pub fn insert_str(&mut self, text: &str) {
+        let text: String = text.chars().filter(|c| !c.is_control()).collect();
+        self.text.insert_str(self.cursor, &text);
+        self.cursor += text.len();
+    }
+
+    /// Delete the character before the cursor.
+    pub fn backspace(&mut self) {
+        if let Some(start) = self.prev_boundary() {
+            self.text.replace_range(start..self.cursor, "");
+            self.cursor = start;
+        }
+    }
+
+    pub fn edit(&mut self, edit: Edit) {
+        match edit {
+            Edit::Left => self.cursor = self.prev_boundary().unwrap_or(0),
+            Edit::Right => self.cursor = self.next_boundary().unwrap_or(self.text.len()),
+            Edit::WordLeft => self.cursor = self.word_start(),
+            Edit::WordRight => self.cursor = self.word_end(),
+            Edit::Home => self.cursor = 0,
+            Edit::End => self.cursor = self.text.len(),
+            Edit::Delete => {
+                if let Some(end) = self.next_boundary() {
+                    self.text.replace_range(self.cursor..end, "");
+                }
+            }
+            Edit::DeleteWord => {
+                let start = self.word_start();
+                self.text.replace_range(start..self.cursor, "");
+                self.cursor = start;
+            }
+            Edit::DeleteToStart => {
+                self.text.replace_range(..self.cursor, "");
+                self.cursor = 0;
+            }
+            Edit::DeleteToEnd => self.text.truncate(self.cursor),
+        }
+    }
+
+    /// Apply an input-mode action and return whether it was handled.
+    /// Backspace on an empty input is left to the caller, which usually
+    /// closes the input.
+    pub fn handle_action(&mut self, action: Action) -> bool {
+        match action {
+            Action::Char(c) if !c.is_control() => self.insert(c),
+            Action::Back if !self.is_empty() => self.backspace(),
+            Action::Edit(edit) => self.edit(edit),
+            Action::Paste => {
+                if let Some(text) = crate::paste() {
+                    self.insert_str(&text);
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// The text as displayed, with masked characters hidden.
+    #[must_use]
+    pub fn display(&self) -> String {
+        if self.masked {
+            "*".repeat(self.text.chars().count())
+        } else {
+            self.text.clone()
+        }
+    }
+
+    /// Spans for the text in `style`, with the cursor shown as a reversed
+    /// cell when `focused`.
+    #[must_use]
+    pub fn spans(&self, style: Style, focused: bool) -> Vec<Span<'static>> {
+        let shown = self.display();
+        if !focused {
+            return vec![Span::styled(shown, style)];
+        }
+        let split = shown
+            .char_indices()
+            .nth(self.cursor())
+            .map_or(shown.len(), |(i, _)| i);
+        let (before, rest) = shown.split_at(split);
+        let mut rest = rest.chars();
+        let under = rest.next().map_or_else(|| " ".to_string(), String::from);
+        vec![
+            Span::styled(before.to_string(), style),
+            Span::styled(under, style.add_modifier(Modifier::REVERSED)),
+            Span::styled(rest.as_str().to_string(), style),
+        ]
+    }
+
+    fn prev_boundary(&self) -> Option<usize> {
+        self.text[..self.cursor]
+            .char_indices()
+            .next_back()
+            .map(|(i, _)| i)
+    }
+
+    fn next_boundary(&self) -> Option<usize> {
+        self.text[self.cursor..]
+            .chars()
+            .next()
+            .map(|c| self.cursor + c.len_utf8())
+    }
+
+    /// Start of the word before the cursor, skipping separators first.
+    fn word_start(&self) -> usize {
+        let before = &self.text[..self.cursor];
+        let word = before.trim_end_matches(|c| !is_word_char(c));
+        word.trim_end_matches(is_word_char).len()
+    }
+
+    /// End of the word after the cursor, skipping separators first.
+    fn word_end(&self) -> usize {
+        let after = &self.text[self.cursor..];
+        let word = after.trim_start_matches(|c| !is_word_char(c));
+        let rest = word.trim_start_matches(is_word_char);
+        self.text.len() - rest.len()
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
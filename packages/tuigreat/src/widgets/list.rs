@@ -1,32 +1,62 @@
+use std::collections::BTreeSet;
+
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
 use ratatui::{
     Frame,
-    layout::Rect,
-    style::Modifier,
+    layout::{Margin, Position, Rect},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, List, ListItem, ListState},
 };
+use serde_json::{Value, json};
 
-use crate::Theme;
+use super::{SearchState, TextInput};
+use crate::fuzzy::{fuzzy_match, substring_match};
+use crate::{Action, Theme};
 
 pub struct SelectableList<T> {
     items: Vec<T>,
     state: ListState,
     display_fn: fn(&T) -> String,
+    // Identifies items across set_items, to keep marks
+    key_fn: fn(&T) -> String,
+    // Marked item indices, and where visual mode started
+    marked: BTreeSet<usize>,
+    visual_anchor: Option<usize>,
     // Search state
-    search_query: String,
+    search: TextInput,
     search_matches: Vec<usize>,
     current_match: Option<usize>,
     search_direction: SearchDirection,
+    match_mode: MatchMode,
+    filter: bool,
+    // Item indices shown while filtering, best match first
+    filtered: Option<Vec<usize>>,
     // Page size for navigation (set during render)
     page_size: usize,
+    // Screen area of the last render, for mouse hit-testing
+    area: Rect,
 }
 
+/// Columns before an item's text: the `>` selection symbol and the marker.
+const ITEM_TEXT_OFFSET: u16 = 3;
+
 #[derive(Clone, Copy, PartialEq)]
 pub enum SearchDirection {
     Forward,
     Backward,
 }
 
+/// How the search query is matched against items.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MatchMode {
+    /// Case-insensitive substring.
+    #[default]
+    Substring,
+    /// Characters in order with gaps allowed, ranked fzf-style.
+    Fuzzy,
+}
+
 impl<T> SelectableList<T> {
     pub fn new(items: Vec<T>, display_fn: fn(&T) -> String) -> Self {
         let mut state = ListState::default();
@@ -37,23 +67,65 @@ impl<T> SelectableList<T> {
             items,
             state,
             display_fn,
-            search_query: String::new(),
+            key_fn: display_fn,
+            marked: BTreeSet::new(),
+            visual_anchor: None,
+            search: TextInput::new(),
             search_matches: Vec::new(),
             current_match: None,
             search_direction: SearchDirection::Forward,
+            match_mode: MatchMode::default(),
+            filter: false,
+            filtered: None,
             page_size: 10, // Default, updated during render
+            area: Rect::default(),
         }
     }
 
+    /// Use `mode` to match search queries.
+    #[must_use]
+    pub fn with_match_mode(mut self, mode: MatchMode) -> Self {
+        self.match_mode = mode;
+        self
+    }
+
+    #[must_use]
+    pub fn match_mode(&self) -> MatchMode {
+        self.match_mode
+    }
+
+    /// Identify items by `key` instead of their display text, so marks
+    /// stay on the right items when [`set_items`](Self::set_items) brings
+    /// changed values (e.g. a new volume).
+    #[must_use]
+    pub fn with_key(mut self, key: fn(&T) -> String) -> Self {
+        self.key_fn = key;
+        self
+    }
+
     #[must_use]
     pub fn items(&self) -> &[T] {
         &self.items
     }
 
+    /// Replace the items, keeping the selection, marks and any search.
     pub fn set_items(&mut self, items: Vec<T>) {
+        let marked: Vec<String> = self
+            .marked
+            .iter()
+            .map(|&i| (self.key_fn)(&self.items[i]))
+            .collect();
+        let anchor = self.visual_anchor.map(|i| (self.key_fn)(&self.items[i]));
         self.items = items;
-        self.search_matches.clear();
-        self.current_match = None;
+        let keys: Vec<String> = self.items.iter().map(self.key_fn).collect();
+        self.marked = keys
+            .iter()
+            .enumerate()
+            .filter(|(_, key)| marked.contains(key))
+            .map(|(i, _)| i)
+            .collect();
+        self.visual_anchor = anchor.and_then(|anchor| keys.iter().position(|k| *k == anchor));
+
         if self.items.is_empty() {
             self.state.select(None);
         } else if self.state.selected().is_none() {
@@ -63,6 +135,7 @@ impl<T> SelectableList<T> {
         {
             self.state.select(Some(self.items.len() - 1));
         }
+        self.refresh_matches();
     }
 
     #[must_use]
@@ -81,97 +154,233 @@ impl<T> SelectableList<T> {
         }
     }
 
+    /// The selected item's key, for the app's [session](crate::session).
+    #[must_use]
+    pub fn save_session(&self) -> Value {
+        json!({"selected": self.selected().map(self.key_fn)})
+    }
+
+    /// Select the item saved with [`save_session`](Self::save_session),
+    /// if it is still there.
+    pub fn restore_session(&mut self, saved: &Value) {
+        if let Some(key) = saved["selected"].as_str()
+            && let Some(index) = self
+                .items
+                .iter()
+                .position(|item| (self.key_fn)(item) == key)
+        {
+            self.select(index);
+        }
+    }
+
     pub fn next(&mut self) {
-        if self.items.is_empty() {
+        let len = self.view_len();
+        if len == 0 {
             return;
         }
-        let i = match self.state.selected() {
-            Some(i) => {
-                if i >= self.items.len() - 1 {
-                    0
-                } else {
-                    i + 1
-                }
-            }
-            None => 0,
+        let position = match self.view_position() {
+            Some(p) if p + 1 < len => p + 1,
+            _ => 0,
         };
-        self.state.select(Some(i));
+        self.select_position(position);
     }
 
     pub fn previous(&mut self) {
-        if self.items.is_empty() {
+        let len = self.view_len();
+        if len == 0 {
             return;
         }
-        let i = match self.state.selected() {
-            Some(i) => {
-                if i == 0 {
-                    self.items.len() - 1
-                } else {
-                    i - 1
-                }
-            }
+        let position = match self.view_position() {
+            Some(p) if p > 0 => p - 1,
+            Some(_) => len - 1,
             None => 0,
         };
-        self.state.select(Some(i));
+        self.select_position(position);
     }
 
     pub fn first(&mut self) {
-        if !self.items.is_empty() {
-            self.state.select(Some(0));
-        }
+        self.select_position(0);
     }
 
     pub fn last(&mut self) {
-        if !self.items.is_empty() {
-            self.state.select(Some(self.items.len() - 1));
+        if let Some(last) = self.view_len().checked_sub(1) {
+            self.select_position(last);
+        }
+    }
+
+    /// Select a 1-based line, clamped to the list (vim `{count}G`)
+    pub fn goto_line(&mut self, line: usize) {
+        let len = self.view_len();
+        if len > 0 {
+            self.select_position(line.clamp(1, len) - 1);
         }
     }
 
     /// Move half page down
     pub fn half_page_down(&mut self) {
-        if self.items.is_empty() {
-            return;
-        }
-        let half = self.page_size / 2;
-        let current = self.state.selected().unwrap_or(0);
-        let new_idx = (current + half).min(self.items.len() - 1);
-        self.state.select(Some(new_idx));
+        self.move_down(self.page_size / 2);
     }
 
     /// Move half page up
     pub fn half_page_up(&mut self) {
-        if self.items.is_empty() {
-            return;
-        }
-        let half = self.page_size / 2;
-        let current = self.state.selected().unwrap_or(0);
-        let new_idx = current.saturating_sub(half);
-        self.state.select(Some(new_idx));
+        self.move_up(self.page_size / 2);
     }
 
     /// Move full page down
     pub fn page_down(&mut self) {
-        if self.items.is_empty() {
-            return;
-        }
-        let current = self.state.selected().unwrap_or(0);
-        let new_idx = (current + self.page_size).min(self.items.len() - 1);
-        self.state.select(Some(new_idx));
+        self.move_down(self.page_size);
     }
 
     /// Move full page up
     pub fn page_up(&mut self) {
-        if self.items.is_empty() {
+        self.move_up(self.page_size);
+    }
+
+    fn move_down(&mut self, rows: usize) {
+        let len = self.view_len();
+        if len == 0 {
             return;
         }
-        let current = self.state.selected().unwrap_or(0);
-        let new_idx = current.saturating_sub(self.page_size);
-        self.state.select(Some(new_idx));
+        let current = self.view_position().unwrap_or(0);
+        self.select_position((current + rows).min(len - 1));
+    }
+
+    fn move_up(&mut self, rows: usize) {
+        if self.view_len() == 0 {
+            return;
+        }
+        let current = self.view_position().unwrap_or(0);
+        self.select_position(current.saturating_sub(rows));
+    }
+
+    /// Number of rows shown: every item, or the matches while filtering.
+    fn view_len(&self) -> usize {
+        self.filtered.as_ref().map_or(self.items.len(), Vec::len)
+    }
+
+    /// Item index shown at a row position.
+    fn view_item(&self, position: usize) -> Option<usize> {
+        match &self.filtered {
+            Some(shown) => shown.get(position).copied(),
+            None => (position < self.items.len()).then_some(position),
+        }
+    }
+
+    /// Row position of the selected item, if it is shown.
+    pub(crate) fn view_position(&self) -> Option<usize> {
+        let selected = self.state.selected()?;
+        match &self.filtered {
+            Some(shown) => shown.iter().position(|&i| i == selected),
+            None => Some(selected),
+        }
+    }
+
+    fn select_position(&mut self, position: usize) {
+        if let Some(index) = self.view_item(position) {
+            self.state.select(Some(index));
+        }
+    }
+
+    /// Mark or unmark the selected item.
+    pub fn toggle_mark(&mut self) {
+        if let Some(i) = self.state.selected()
+            && !self.marked.remove(&i)
+        {
+            self.marked.insert(i);
+        }
+    }
+
+    /// Start marking a range from the selected item, or mark the range
+    /// and stop if already started (vim `V`).
+    pub fn toggle_visual(&mut self) {
+        if self.visual_anchor.is_some() {
+            self.marked.extend(self.visual_range());
+            self.visual_anchor = None;
+        } else {
+            self.visual_anchor = self.state.selected();
+        }
+    }
+
+    #[must_use]
+    pub fn is_visual(&self) -> bool {
+        self.visual_anchor.is_some()
+    }
+
+    /// Leave visual mode without marking the range.
+    pub fn cancel_visual(&mut self) {
+        self.visual_anchor = None;
+    }
+
+    /// Leave visual mode, or unmark everything when not in it (Esc).
+    pub fn unmark(&mut self) {
+        if self.visual_anchor.is_some() {
+            self.visual_anchor = None;
+        } else {
+            self.marked.clear();
+        }
+    }
+
+    /// Unmark everything and leave visual mode.
+    pub fn clear_marks(&mut self) {
+        self.marked.clear();
+        self.visual_anchor = None;
+    }
+
+    /// Whether an item is marked or inside the visual range.
+    #[must_use]
+    pub fn is_marked(&self, index: usize) -> bool {
+        self.marked.contains(&index) || self.visual_range().contains(&index)
+    }
+
+    /// Indices of marked items, including the visual range, in list order.
+    #[must_use]
+    pub fn marked_indices(&self) -> Vec<usize> {
+        let mut indices = self.marked.clone();
+        indices.extend(self.visual_range());
+        indices.into_iter().collect()
+    }
+
+    /// Marked items, including the visual range, in list order.
+    #[must_use]
+    pub fn marked(&self) -> Vec<&T> {
+        self.marked_indices()
+            .into_iter()
+            .map(|i| &self.items[i])
+            .collect()
+    }
+
+    /// The items a bulk action applies to: the marked ones, or the
+    /// selected one when nothing is marked.
+    #[must_use]
+    pub fn marked_or_selected(&self) -> Vec<&T> {
+        let marked = self.marked();
+        if marked.is_empty() {
+            self.selected().into_iter().collect()
+        } else {
+            marked
+        }
+    }
+
+    /// Items between the visual anchor and the selection, as shown.
+    fn visual_range(&self) -> Vec<usize> {
+        let Some(anchor) = self.visual_anchor else {
+            return Vec::new();
+        };
+        let anchor = match &self.filtered {
+            Some(shown) => shown.iter().position(|&i| i == anchor),
+            None => Some(anchor),
+        };
+        let (Some(anchor), Some(current)) = (anchor, self.view_position()) else {
+            return Vec::new();
+        };
+        (anchor.min(current)..=anchor.max(current))
+            .filter_map(|position| self.view_item(position))
+            .collect()
     }
 
     /// Start a new search (clears previous results)
     pub fn start_search(&mut self, direction: SearchDirection) {
-        self.search_query.clear();
+        self.search.clear();
         self.search_matches.clear();
         self.current_match = None;
         self.search_direction = direction;
@@ -180,50 +389,155 @@ impl<T> SelectableList<T> {
     /// Get current search query
     #[must_use]
     pub fn search_query(&self) -> &str {
-        &self.search_query
+        self.search.text()
+    }
+
+    /// The search query being typed, for [`SearchPopup`](super::SearchPopup).
+    #[must_use]
+    pub fn search_input(&self) -> &TextInput {
+        &self.search
     }
 
     /// Check if search is active (has query)
     #[must_use]
     pub fn has_search(&self) -> bool {
-        !self.search_query.is_empty()
+        !self.search.is_empty()
+    }
+
+    /// Whether searching hides non-matching items instead of jumping
+    /// between matches.
+    #[must_use]
+    pub fn filter(&self) -> bool {
+        self.filter
+    }
+
+    pub fn set_filter(&mut self, filter: bool) {
+        self.filter = filter;
+        self.update_search_matches();
+    }
+
+    /// Whether non-matching items are currently hidden.
+    #[must_use]
+    pub fn is_filtered(&self) -> bool {
+        self.filtered.is_some()
+    }
+
+    /// What [`SearchPopup`](super::SearchPopup) shows for this list.
+    #[must_use]
+    pub fn search_state(&self) -> SearchState<'_> {
+        SearchState {
+            query: &self.search,
+            match_info: self.match_info(),
+            fuzzy: self.match_mode == MatchMode::Fuzzy,
+            filter: self.filter,
+        }
     }
 
     /// Add character to search query and update matches
     pub fn search_push(&mut self, c: char) {
-        self.search_query.push(c);
+        if c.is_control() {
+            return;
+        }
+        self.search.insert(c);
         self.update_search_matches();
     }
 
     /// Remove character from search query and update matches
     pub fn search_pop(&mut self) {
-        self.search_query.pop();
+        self.search.backspace();
         self.update_search_matches();
     }
 
+    /// Edit the search query with an input-mode action, updating matches
+    /// when the text changes. Tab toggles [`filter`](Self::filter).
+    /// Returns whether the action was handled.
+    pub fn edit_search(&mut self, action: Action) -> bool {
+        if action == Action::Char('\t') {
+            self.set_filter(!self.filter);
+            return true;
+        }
+        let before = self.search.text().to_string();
+        let handled = self.search.handle_action(action);
+        if self.search.text() != before {
+            self.update_search_matches();
+        }
+        handled
+    }
+
     /// Clear search
     pub fn clear_search(&mut self) {
-        self.search_query.clear();
+        self.search.clear();
         self.search_matches.clear();
         self.current_match = None;
+        self.filtered = None;
+        if self.state.selected().is_none() && !self.items.is_empty() {
+            self.state.select(Some(0));
+        }
     }
 
-    /// Update search matches based on current query
-    fn update_search_matches(&mut self) {
+    /// Matched character positions in `text`, or `None` if it does not match.
+    fn match_positions(&self, text: &str) -> Option<Vec<usize>> {
+        let query = self.search.text();
+        match self.match_mode {
+            MatchMode::Substring => substring_match(query, text),
+            MatchMode::Fuzzy => fuzzy_match(query, text).map(|m| m.positions),
+        }
+    }
+
+    /// Recompute matches for the current items without moving to them.
+    fn refresh_matches(&mut self) {
         self.search_matches.clear();
         self.current_match = None;
-
-        if self.search_query.is_empty() {
+        self.filtered = None;
+        if self.search.is_empty() {
             return;
         }
 
-        let query_lower = self.search_query.to_lowercase();
-        for (i, item) in self.items.iter().enumerate() {
-            let display = (self.display_fn)(item).to_lowercase();
-            if display.contains(&query_lower) {
-                self.search_matches.push(i);
+        let query = self.search.text();
+        let mut scored: Vec<(i64, usize)> = self
+            .items
+            .iter()
+            .enumerate()
+            .filter_map(|(i, item)| {
+                let display = (self.display_fn)(item);
+                let score = match self.match_mode {
+                    MatchMode::Substring => substring_match(query, &display).map(|_| 0),
+                    MatchMode::Fuzzy => fuzzy_match(query, &display).map(|m| m.score),
+                }?;
+                Some((score, i))
+            })
+            .collect();
+        // Best matches first when only the matches are shown
+        if self.filter {
+            scored.sort_by_key(|&(score, i)| (std::cmp::Reverse(score), i));
+        }
+        self.search_matches = scored.into_iter().map(|(_, i)| i).collect();
+
+        if self.filter {
+            self.filtered = Some(self.search_matches.clone());
+            if self.view_position().is_none() {
+                self.state.select(self.search_matches.first().copied());
             }
         }
+        self.current_match = self
+            .state
+            .selected()
+            .and_then(|selected| self.search_matches.iter().position(|&i| i == selected));
+    }
+
+    /// Update search matches based on current query
+    fn update_search_matches(&mut self) {
+        self.refresh_matches();
+        self.current_match = None;
+
+        // Show the best match first while filtering
+        if self.filter {
+            self.state.select(self.search_matches.first().copied());
+            if !self.search_matches.is_empty() {
+                self.current_match = Some(0);
+            }
+            return;
+        }
 
         // Jump to first match from current position
         if !self.search_matches.is_empty() {
@@ -296,24 +610,67 @@ impl<T> SelectableList<T> {
         }
     }
 
+    /// Item under a screen position, with the column inside the item's
+    /// display text (after the selection symbol and marker).
+    #[must_use]
+    pub fn item_at(&self, column: u16, row: u16) -> Option<(usize, u16)> {
+        let inner = self.area.inner(Margin::new(1, 1));
+        if column >= inner.right() {
+            return None;
+        }
+        let text_column = column.checked_sub(inner.x + ITEM_TEXT_OFFSET)?;
+        Some((self.item_on_row(row)?, text_column))
+    }
+
+    /// Click to select, scroll wheel to move. Returns whether the event was
+    /// over the list.
+    pub fn handle_mouse(&mut self, event: &MouseEvent) -> bool {
+        if !self.area.contains(Position::new(event.column, event.row)) {
+            return false;
+        }
+        match event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                if let Some(index) = self.item_on_row(event.row) {
+                    self.state.select(Some(index));
+                }
+            }
+            MouseEventKind::ScrollDown => self.move_down(1),
+            MouseEventKind::ScrollUp => self.move_up(1),
+            _ => {}
+        }
+        true
+    }
+
+    pub(crate) fn item_on_row(&self, row: u16) -> Option<usize> {
+        let inner = self.area.inner(Margin::new(1, 1));
+        if row < inner.y || row >= inner.bottom() {
+            return None;
+        }
+        self.view_item(self.state.offset() + usize::from(row - inner.y))
+    }
+
     /// Jump to next item starting with the given character (vim-style f{char})
     pub fn jump_to_char(&mut self, c: char, forward: bool) -> bool {
-        if self.items.is_empty() {
+        let len = self.view_len();
+        if len == 0 {
             return false;
         }
 
-        let current = self.state.selected().unwrap_or(0);
+        let current = self.view_position().unwrap_or(0);
         let target = c.to_lowercase().next().unwrap_or(c);
 
-        let indices: Box<dyn Iterator<Item = usize>> = if forward {
+        let positions: Box<dyn Iterator<Item = usize>> = if forward {
             // Forward: search from current+1 to end, then wrap to start
-            Box::new((current + 1..self.items.len()).chain(0..=current))
+            Box::new((current + 1..len).chain(0..=current))
         } else {
             // Backward: search from current-1 to start, then wrap from end
-            Box::new((0..current).rev().chain((current..self.items.len()).rev()))
+            Box::new((0..current).rev().chain((current..len).rev()))
         };
 
-        for i in indices {
+        for position in positions {
+            let Some(i) = self.view_item(position) else {
+                continue;
+            };
             let display = (self.display_fn)(&self.items[i]);
             // Skip prefix spaces and markers like "* " or "  "
             let text = display.trim_start();
@@ -339,7 +696,8 @@ impl<T> SelectableList<T> {
         self.render_with_marker(frame, area, title, theme, focused, None);
     }
 
-    /// Render with an optional marker index (e.g., for "now playing" indicator)
+    /// Render with an optional marker index (e.g., for "now playing" indicator).
+    /// Marked items show a `+` and the title counts them.
     pub fn render_with_marker(
         &mut self,
         frame: &mut Frame,
@@ -349,69 +707,151 @@ impl<T> SelectableList<T> {
         focused: bool,
         marker_index: Option<usize>,
     ) {
-        // Update page size based on visible area (minus borders)
-        self.page_size = area.height.saturating_sub(2) as usize;
-
-        let query_lower = self.search_query.to_lowercase();
-        let has_query = !query_lower.is_empty();
+        self.set_viewport(area);
 
+        let highlight = theme.highlight().add_modifier(Modifier::UNDERLINED);
+        let marked = self.marked_indices();
         let items: Vec<ListItem> = self
-            .items
-            .iter()
-            .enumerate()
-            .map(|(i, item)| {
-                let content = (self.display_fn)(item);
-                let prefix = if marker_index == Some(i) { "* " } else { "  " };
-                let full_text = format!("{prefix}{content}");
-
-                // Highlight search matches in the text
-                if has_query && self.search_matches.contains(&i) {
-                    let lower = full_text.to_lowercase();
-                    if let Some(pos) = lower.find(&query_lower) {
-                        // Calculate end position safely using char boundaries
-                        let match_end = pos + query_lower.len();
-                        // Verify positions are valid char boundaries
-                        if full_text.is_char_boundary(pos)
-                            && full_text.is_char_boundary(match_end)
-                            && match_end <= full_text.len()
-                        {
-                            let before = &full_text[..pos];
-                            let matched = &full_text[pos..match_end];
-                            let after = &full_text[match_end..];
-                            return ListItem::new(Line::from(vec![
-                                Span::raw(before.to_string()),
-                                Span::styled(
-                                    matched.to_string(),
-                                    theme.highlight().add_modifier(Modifier::UNDERLINED),
-                                ),
-                                Span::raw(after.to_string()),
-                            ]));
-                        }
-                    }
+            .shown()
+            .into_iter()
+            .map(|i| {
+                let content = (self.display_fn)(&self.items[i]);
+                let is_marked = marked.contains(&i);
+                let prefix = format!(
+                    "{}{}",
+                    if marker_index == Some(i) { '*' } else { ' ' },
+                    if is_marked { '+' } else { ' ' }
+                );
+
+                // Highlight the matched characters
+                let positions = self.match_positions_of(i, &content);
+                let mut spans = vec![Span::raw(prefix)];
+                spans.extend(highlight_spans(&content, &positions, highlight));
+
+                let item = ListItem::new(Line::from(spans));
+                if is_marked {
+                    item.style(theme.highlight())
+                } else {
+                    item
                 }
-
-                ListItem::new(Line::from(Span::raw(full_text)))
             })
             .collect();
 
+        let block = self.block(title, theme, focused);
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(theme.selected())
+            .highlight_symbol(">");
+
+        // The widget selects by row, which differs from the item index
+        // while filtering
+        let selected = self.state.selected();
+        *self.state.selected_mut() = self.view_position();
+        frame.render_stateful_widget(list, area, &mut self.state);
+        *self.state.selected_mut() = selected;
+    }
+
+    // Shared with SelectableTable, which renders the same view as rows
+
+    /// Remember where the items were drawn; `area` includes the borders.
+    pub(crate) fn set_viewport(&mut self, area: Rect) {
+        self.page_size = area.height.saturating_sub(2) as usize;
+        self.area = area;
+    }
+
+    /// Indices of the items on screen, in display order.
+    pub(crate) fn shown(&self) -> Vec<usize> {
+        match &self.filtered {
+            Some(shown) => shown.clone(),
+            None => (0..self.items.len()).collect(),
+        }
+    }
+
+    /// Matched character positions in `text` belonging to item `index`.
+    pub(crate) fn match_positions_of(&self, index: usize, text: &str) -> Vec<usize> {
+        if self.has_search() && self.search_matches.contains(&index) {
+            self.match_positions(text).unwrap_or_default()
+        } else {
+            Vec::new()
+        }
+    }
+
+    pub(crate) fn key_of(&self, item: &T) -> String {
+        (self.key_fn)(item)
+    }
+
+    pub(crate) fn state_mut(&mut self) -> &mut ListState {
+        &mut self.state
+    }
+
+    /// Move the item at `order[i]` to position `i`, keeping the selection,
+    /// marks and search on the same items.
+    pub(crate) fn reorder(&mut self, order: &[usize]) {
+        let mut moved_to = vec![0; order.len()];
+        for (new, &old) in order.iter().enumerate() {
+            moved_to[old] = new;
+        }
+        let mut slots: Vec<Option<T>> = std::mem::take(&mut self.items)
+            .into_iter()
+            .map(Some)
+            .collect();
+        self.items = order.iter().filter_map(|&old| slots[old].take()).collect();
+        self.marked = self.marked.iter().map(|&i| moved_to[i]).collect();
+        self.visual_anchor = self.visual_anchor.map(|i| moved_to[i]);
+        if let Some(i) = self.state.selected() {
+            self.state.select(Some(moved_to[i]));
+        }
+        self.refresh_matches();
+    }
+
+    /// Bordered block with the title and a count of marked items.
+    pub(crate) fn block<'a>(&self, title: &'a str, theme: &Theme, focused: bool) -> Block<'a> {
         let border_style = if focused {
             theme.border_focused()
         } else {
             theme.border()
         };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(Theme::BORDER_TYPE)
+            .border_style(border_style)
+            .title(title)
+            .title_style(theme.title());
+        let marked = self.marked_indices().len();
+        if self.is_visual() {
+            let count = format!(" VISUAL {marked} ");
+            block.title(Line::styled(count, theme.highlight()).right_aligned())
+        } else if marked > 0 {
+            let count = format!(" {marked} marked ");
+            block.title(Line::styled(count, theme.highlight()).right_aligned())
+        } else {
+            block
+        }
+    }
+}
 
-        let list = List::new(items)
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_type(Theme::BORDER_TYPE)
-                    .border_style(border_style)
-                    .title(title)
-                    .title_style(theme.title()),
-            )
-            .highlight_style(theme.selected())
-            .highlight_symbol(">");
-
-        frame.render_stateful_widget(list, area, &mut self.state);
+/// Split `text` into spans, styling the characters at `positions`.
+pub(crate) fn highlight_spans(text: &str, positions: &[usize], style: Style) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut run = String::new();
+    let mut run_matched = false;
+    for (n, c) in text.chars().enumerate() {
+        let matched = positions.contains(&n);
+        if matched != run_matched && !run.is_empty() {
+            let text = std::mem::take(&mut run);
+            spans.push(if run_matched {
+                Span::styled(text, style)
+            } else {
+                Span::raw(text)
+            });
+        }
+        run_matched = matched;
+        run.push(c);
     }
+    spans.push(if run_matched {
+        Span::styled(run, style)
+    } else {
+        Span::raw(run)
+    });
+    spans
 }
@@ -0,0 +1,154 @@
+use std::path::PathBuf;
+
+use ratatui::{
+    Frame,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState},
+};
+
+use super::{DetailPopup, help::centered_rect_percent};
+use crate::trace::{self, Entry, Event};
+use crate::{Action, Theme};
+
+/// A popup tailing the [debug log](crate::trace), newest last. It follows
+/// new entries while the last one is selected; Enter shows the output of a
+/// command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogPopup {
+    // The file being written, `None` when logging is off
+    path: Option<PathBuf>,
+    entries: Vec<Entry>,
+    state: ListState,
+    follow: bool,
+    details: Option<DetailPopup>,
+}
+
+impl Default for LogPopup {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LogPopup {
+    /// The latest entries of the running log.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_entries(trace::path(), trace::recent())
+    }
+
+    /// `entries` of the log written to `path`, or of no log if `None`.
+    #[must_use]
+    pub fn with_entries(path: Option<PathBuf>, entries: Vec<Entry>) -> Self {
+        let mut popup = Self {
+            path,
+            entries: Vec::new(),
+            state: ListState::default(),
+            follow: true,
+            details: None,
+        };
+        popup.update(entries);
+        popup
+    }
+
+    /// Show `entries` instead, keeping the selected one or the tail.
+    pub fn update(&mut self, entries: Vec<Entry>) {
+        let selected = self.selected().map(|entry| entry.seq);
+        self.entries = entries;
+        let index = if self.follow {
+            self.entries.len().checked_sub(1)
+        } else {
+            selected
+                .and_then(|seq| self.entries.iter().position(|entry| entry.seq == seq))
+                .or((!self.entries.is_empty()).then_some(0))
+        };
+        self.state.select(index);
+    }
+
+    #[must_use]
+    pub fn selected(&self) -> Option<&Entry> {
+        self.entries.get(self.state.selected()?)
+    }
+
+    /// Move through the log. Returns `false` once the popup is closed with
+    /// back, quit or the log key.
+    pub fn handle_action(&mut self, action: Action) -> bool {
+        if let Some(details) = &mut self.details {
+            if !details.handle_action(action) {
+                self.details = None;
+            }
+            return action != Action::Quit;
+        }
+        let last = self.entries.len().saturating_sub(1);
+        let selected = self.state.selected().unwrap_or(0);
+        let selected = match action {
+            Action::Down => (selected + 1).min(last),
+            Action::Up => selected.saturating_sub(1),
+            Action::PageDown | Action::FullPageDown => (selected + 10).min(last),
+            Action::PageUp | Action::FullPageUp => selected.saturating_sub(10),
+            Action::Top => 0,
+            Action::Bottom => last,
+            Action::Select | Action::Details => {
+                self.details = self
+                    .selected()
+                    .map(|entry| DetailPopup::new(" Debug log ", entry.detail()));
+                return true;
+            }
+            Action::Back | Action::Quit | Action::Log => return false,
+            _ => return true,
+        };
+        if !self.entries.is_empty() {
+            self.state.select(Some(selected));
+        }
+        self.follow = selected == last;
+        true
+    }
+
+    pub fn render(&self, frame: &mut Frame, theme: &Theme) {
+        if let Some(details) = &self.details {
+            details.render(frame, theme);
+            return;
+        }
+        let area = centered_rect_percent(80, 60, frame.area());
+        frame.render_widget(Clear, area);
+
+        let items: Vec<ListItem> = match &self.path {
+            Some(_) => self
+                .entries
+                .iter()
+                .map(|entry| {
+                    let style = if entry.failed() {
+                        theme.error()
+                    } else if matches!(entry.event, Event::Command { .. }) {
+                        theme.normal()
+                    } else {
+                        theme.muted()
+                    };
+                    ListItem::new(Line::from(vec![
+                        Span::styled(
+                            format!("{:>8.3}  ", entry.time.as_secs_f64()),
+                            theme.muted(),
+                        ),
+                        Span::styled(entry.summary(), style),
+                    ]))
+                })
+                .collect(),
+            None => vec![ListItem::new(Line::styled(
+                " Start with --debug or set ANYTUI_LOG=<file> to log commands and keys",
+                theme.muted(),
+            ))],
+        };
+        let title = match &self.path {
+            Some(path) => format!(" Debug log: {} ", path.display()),
+            None => " Debug log (off) ".to_string(),
+        };
+        let list = List::new(items).highlight_style(theme.selected()).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(Theme::BORDER_TYPE)
+                .border_style(theme.border_focused())
+                .title(title)
+                .title_style(theme.title()),
+        );
+        frame.render_stateful_widget(list, area, &mut self.state.clone());
+    }
+}
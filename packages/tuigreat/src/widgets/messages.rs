@@ -0,0 +1,113 @@
+use std::time::SystemTime;
+
+use ratatui::{
+    Frame,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState},
+};
+
+use super::{DetailPopup, help::centered_rect_percent};
+use crate::{Action, MessageLog, Theme, messages::Entry};
+
+/// A popup listing the message history, newest first. Enter opens the
+/// details of a failed command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessagePopup {
+    entries: Vec<Entry>,
+    state: ListState,
+    details: Option<DetailPopup>,
+}
+
+impl MessagePopup {
+    /// A snapshot of the history in `log`.
+    #[must_use]
+    pub fn new(log: &MessageLog) -> Self {
+        let entries: Vec<Entry> = log.entries().rev().cloned().collect();
+        let state = ListState::default().with_selected((!entries.is_empty()).then_some(0));
+        Self {
+            entries,
+            state,
+            details: None,
+        }
+    }
+
+    #[must_use]
+    pub fn selected(&self) -> Option<&Entry> {
+        self.entries.get(self.state.selected()?)
+    }
+
+    /// Move through the history. Returns `false` once the popup is closed
+    /// with back, quit or the messages key.
+    pub fn handle_action(&mut self, action: Action) -> bool {
+        if let Some(details) = &mut self.details {
+            if !details.handle_action(action) {
+                self.details = None;
+            }
+            return action != Action::Quit;
+        }
+        let last = self.entries.len().saturating_sub(1);
+        let selected = self.state.selected().unwrap_or(0);
+        let selected = match action {
+            Action::Down => (selected + 1).min(last),
+            Action::Up => selected.saturating_sub(1),
+            Action::PageDown | Action::FullPageDown => (selected + 10).min(last),
+            Action::PageUp | Action::FullPageUp => selected.saturating_sub(10),
+            Action::Top => 0,
+            Action::Bottom => last,
+            Action::Select | Action::Details => {
+                self.details = self
+                    .selected()
+                    .and_then(|entry| DetailPopup::for_status(&entry.message));
+                return true;
+            }
+            Action::Back | Action::Quit | Action::Messages => return false,
+            _ => return true,
+        };
+        if !self.entries.is_empty() {
+            self.state.select(Some(selected));
+        }
+        true
+    }
+
+    pub fn render(&self, frame: &mut Frame, theme: &Theme) {
+        if let Some(details) = &self.details {
+            details.render(frame, theme);
+            return;
+        }
+        let area = centered_rect_percent(80, 60, frame.area());
+        frame.render_widget(Clear, area);
+
+        let now = SystemTime::now();
+        let items: Vec<ListItem> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let mut spans = vec![
+                    Span::styled(format!("{:>4}  ", entry.age(now)), theme.muted()),
+                    Span::styled(entry.message.text.clone(), entry.message.style(theme)),
+                ];
+                if entry.count > 1 {
+                    spans.push(Span::styled(format!(" (x{})", entry.count), theme.muted()));
+                }
+                if entry.message.detail.is_some() {
+                    spans.push(Span::styled("  (Enter for details)", theme.muted()));
+                }
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+        let title = if self.entries.is_empty() {
+            " Messages (none yet) "
+        } else {
+            " Messages "
+        };
+        let list = List::new(items).highlight_style(theme.selected()).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(Theme::BORDER_TYPE)
+                .border_style(theme.border_focused())
+                .title(title)
+                .title_style(theme.title()),
+        );
+        frame.render_stateful_widget(list, area, &mut self.state.clone());
+    }
+}
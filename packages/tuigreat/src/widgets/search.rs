@@ -1,29 +1,42 @@
 use ratatui::{
     Frame,
-    style::{Modifier, Style},
+    style::Style,
     text::{Line, Span},
     widgets::{Block, Borders, Clear, Paragraph},
 };
 
+use super::TextInput;
 use super::help::centered_rect;
 use crate::Theme;
 
+/// A list's search, as shown by [`SearchPopup`].
+#[derive(Debug, Clone, Copy)]
+pub struct SearchState<'a> {
+    pub query: &'a TextInput,
+    /// Current and total matches
+    pub match_info: Option<(usize, usize)>,
+    pub fuzzy: bool,
+    /// Non-matching items are hidden instead of jumped over
+    pub filter: bool,
+}
+
+impl SearchState<'_> {
+    /// Whether the list is showing only the matches.
+    #[must_use]
+    pub fn is_filtered(&self) -> bool {
+        self.filter && !self.query.is_empty()
+    }
+}
+
 /// A popup for search input with match info display.
 pub struct SearchPopup;
 
 impl SearchPopup {
-    /// Render a search popup with the given title, query, and match info.
+    /// Render a search popup with the given title and search state.
     ///
     /// - `title`: The popup title (e.g., " Search Artists ")
-    /// - `query`: The current search query string
-    /// - `match_info`: Optional (current, total) match count
-    pub fn render(
-        frame: &mut Frame,
-        title: &str,
-        query: &str,
-        match_info: Option<(usize, usize)>,
-        theme: &Theme,
-    ) {
+    /// - `search`: The query being typed, match count and matching mode
+    pub fn render(frame: &mut Frame, title: &str, search: SearchState, theme: &Theme) {
         let area = centered_rect(50, 5, frame.area());
         frame.render_widget(Clear, area);
 
@@ -34,18 +47,26 @@ impl SearchPopup {
             .border_type(Theme::BORDER_TYPE)
             .border_style(theme.border_focused());
 
-        let match_str = match_info
+        let match_str = search
+            .match_info
             .map(|(c, t)| format!(" ({c}/{t})"))
             .unwrap_or_default();
 
+        let mut line = vec![Span::raw("  /")];
+        line.extend(search.query.spans(Style::default(), true));
+        line.push(Span::styled(match_str, theme.muted()));
+
+        let matcher = if search.fuzzy { "fuzzy" } else { "exact" };
+        let (mode, toggle) = if search.filter {
+            ("filter", "Jump")
+        } else {
+            ("jump", "Filter")
+        };
+        let hint = format!("  {matcher}, {mode}  [Tab] {toggle}");
         let content = vec![
             Line::from(""),
-            Line::from(vec![
-                Span::raw("  /"),
-                Span::raw(query),
-                Span::styled("_", Style::default().add_modifier(Modifier::SLOW_BLINK)),
-                Span::styled(match_str, theme.muted()),
-            ]),
+            Line::from(line),
+            Line::from(Span::styled(hint, theme.muted())),
         ];
 
         let popup = Paragraph::new(content).block(block);
@@ -0,0 +1,371 @@
+use std::cmp::Ordering;
+use std::ops::{Deref, DerefMut};
+
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Flex, Layout, Margin, Rect},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Cell, HighlightSpacing, Row, Table, TableState},
+};
+use serde_json::{Value, json};
+
+use super::SelectableList;
+use super::list::highlight_spans;
+use crate::Theme;
+
+const COLUMN_SPACING: u16 = 1;
+
+/// One column of a [`SelectableTable`].
+pub struct Column<T> {
+    title: String,
+    width: Constraint,
+    value: fn(&T) -> String,
+    style: Option<fn(&T, &Theme) -> Style>,
+    compare: Option<fn(&T, &T) -> Ordering>,
+    alignment: Alignment,
+}
+
+impl<T> Column<T> {
+    pub fn new(title: &str, width: Constraint, value: fn(&T) -> String) -> Self {
+        Self {
+            title: title.to_string(),
+            width,
+            value,
+            style: None,
+            compare: None,
+            alignment: Alignment::Left,
+        }
+    }
+
+    /// Style each cell by its item, e.g. dim muted rows.
+    #[must_use]
+    pub fn with_style(mut self, style: fn(&T, &Theme) -> Style) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    /// Sort with `compare` instead of by the cell text, e.g. numerically.
+    #[must_use]
+    pub fn with_sort(mut self, compare: fn(&T, &T) -> Ordering) -> Self {
+        self.compare = Some(compare);
+        self
+    }
+
+    #[must_use]
+    pub fn with_alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        match self.compare {
+            Some(compare) => compare(a, b),
+            None => (self.value)(a)
+                .to_lowercase()
+                .cmp(&(self.value)(b).to_lowercase()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+/// A [`SelectableList`] drawn as a table with a header row, sortable by
+/// any column.
+///
+/// Navigation, search, marks and mouse selection come from the list, which
+/// the table dereferences to; the list's display function is the text that
+/// search and `f{char}` match against.
+pub struct SelectableTable<T> {
+    list: SelectableList<T>,
+    columns: Vec<Column<T>>,
+    sort: Option<(usize, SortOrder)>,
+    // Position of each item in the order it was given, to undo sorting
+    source: Vec<usize>,
+    // Header row and the x range of each column from the last render,
+    // for the mouse
+    header_row: Option<u16>,
+    column_areas: Vec<(u16, u16)>,
+}
+
+impl<T> SelectableTable<T> {
+    pub fn new(items: Vec<T>, display_fn: fn(&T) -> String, columns: Vec<Column<T>>) -> Self {
+        let source = (0..items.len()).collect();
+        Self {
+            list: SelectableList::new(items, display_fn),
+            columns,
+            sort: None,
+            source,
+            header_row: None,
+            column_areas: Vec::new(),
+        }
+    }
+
+    /// Wrap a configured list, e.g. one with a match mode or key.
+    #[must_use]
+    pub fn from_list(list: SelectableList<T>, columns: Vec<Column<T>>) -> Self {
+        let source = (0..list.items().len()).collect();
+        Self {
+            list,
+            columns,
+            sort: None,
+            source,
+            header_row: None,
+            column_areas: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_sort(mut self, column: usize, order: SortOrder) -> Self {
+        self.set_sort(Some((column, order)));
+        self
+    }
+
+    /// Sorted column and direction, `None` for the order items were given in.
+    #[must_use]
+    pub fn sort(&self) -> Option<(usize, SortOrder)> {
+        self.sort
+    }
+
+    /// Sort by `column`, or flip the direction if it is already sorted by it.
+    pub fn sort_by(&mut self, column: usize) {
+        if column >= self.columns.len() {
+            return;
+        }
+        let order = match self.sort {
+            Some((sorted, SortOrder::Ascending)) if sorted == column => SortOrder::Descending,
+            _ => SortOrder::Ascending,
+        };
+        self.set_sort(Some((column, order)));
+    }
+
+    /// Step through each column ascending then descending, and back to the
+    /// original order.
+    pub fn cycle_sort(&mut self) {
+        let sort = match self.sort {
+            None => Some((0, SortOrder::Ascending)),
+            Some((column, SortOrder::Ascending)) => Some((column, SortOrder::Descending)),
+            Some((column, SortOrder::Descending)) if column + 1 < self.columns.len() => {
+                Some((column + 1, SortOrder::Ascending))
+            }
+            Some(_) => None,
+        };
+        self.set_sort(sort);
+    }
+
+    pub fn set_sort(&mut self, sort: Option<(usize, SortOrder)>) {
+        self.sort = sort.filter(|&(column, _)| column < self.columns.len());
+        self.apply_sort();
+    }
+
+    /// The selected item and the sort, for the app's
+    /// [session](crate::session).
+    #[must_use]
+    pub fn save_session(&self) -> Value {
+        let mut saved = self.list.save_session();
+        saved["sort"] = match self.sort {
+            Some((column, SortOrder::Ascending)) => json!([column, "ascending"]),
+            Some((column, SortOrder::Descending)) => json!([column, "descending"]),
+            None => Value::Null,
+        };
+        saved
+    }
+
+    /// Sort and select as saved with [`save_session`](Self::save_session).
+    pub fn restore_session(&mut self, saved: &Value) {
+        let column = saved["sort"][0]
+            .as_u64()
+            .and_then(|c| usize::try_from(c).ok());
+        let order = match saved["sort"][1].as_str() {
+            Some("ascending") => Some(SortOrder::Ascending),
+            Some("descending") => Some(SortOrder::Descending),
+            _ => None,
+        };
+        self.set_sort(column.zip(order));
+        self.list.restore_session(saved);
+    }
+
+    /// Replace the items, keeping the sort, the selected item, marks and
+    /// any search.
+    pub fn set_items(&mut self, items: Vec<T>) {
+        let selected = self.list.selected().map(|item| self.list.key_of(item));
+        self.source = (0..items.len()).collect();
+        self.list.set_items(items);
+        self.apply_sort();
+        if let Some(key) = selected
+            && let Some(index) = self
+                .list
+                .items()
+                .iter()
+                .position(|item| self.list.key_of(item) == key)
+        {
+            self.list.select(index);
+        }
+    }
+
+    fn apply_sort(&mut self) {
+        let items = self.list.items();
+        let mut order: Vec<usize> = (0..items.len()).collect();
+        match self.sort {
+            Some((column, direction)) => {
+                let column = &self.columns[column];
+                order.sort_by(|&a, &b| {
+                    let ordering = column.compare(&items[a], &items[b]);
+                    match direction {
+                        SortOrder::Ascending => ordering,
+                        SortOrder::Descending => ordering.reverse(),
+                    }
+                });
+            }
+            None => order.sort_by_key(|&i| self.source[i]),
+        }
+        if order.iter().enumerate().all(|(new, &old)| new == old) {
+            return;
+        }
+        self.source = order.iter().map(|&old| self.source[old]).collect();
+        self.list.reorder(&order);
+    }
+
+    /// Item and column under a screen position.
+    #[must_use]
+    pub fn cell_at(&self, column: u16, row: u16) -> Option<(usize, usize)> {
+        let index = self.list.item_on_row(row)?;
+        Some((index, self.column_at(column)?))
+    }
+
+    fn column_at(&self, x: u16) -> Option<usize> {
+        self.column_areas
+            .iter()
+            .position(|&(start, width)| x >= start && x < start + width)
+    }
+
+    /// Click a column title to sort by it, otherwise like
+    /// [`SelectableList::handle_mouse`].
+    pub fn handle_mouse(&mut self, event: &MouseEvent) -> bool {
+        if event.kind == MouseEventKind::Down(MouseButton::Left)
+            && Some(event.row) == self.header_row
+            && let Some(column) = self.column_at(event.column)
+        {
+            self.sort_by(column);
+            return true;
+        }
+        self.list.handle_mouse(event)
+    }
+
+    pub fn render(
+        &mut self,
+        frame: &mut Frame,
+        area: Rect,
+        title: &str,
+        theme: &Theme,
+        focused: bool,
+    ) {
+        // Rows start one line lower than in a list, below the header
+        self.list.set_viewport(Rect {
+            y: area.y + 1,
+            height: area.height.saturating_sub(1),
+            ..area
+        });
+
+        let highlight = theme.highlight().add_modifier(Modifier::UNDERLINED);
+        let marked = self.list.marked_indices();
+        let items = self.list.items();
+
+        let header = Row::new(std::iter::once(Cell::from("")).chain(
+            self.columns.iter().enumerate().map(|(n, column)| {
+                let title = match self.sort {
+                    Some((sorted, SortOrder::Ascending)) if sorted == n => {
+                        format!("{} ▲", column.title)
+                    }
+                    Some((sorted, SortOrder::Descending)) if sorted == n => {
+                        format!("{} ▼", column.title)
+                    }
+                    _ => column.title.clone(),
+                };
+                Cell::from(Line::from(title).alignment(column.alignment))
+            }),
+        ))
+        .style(theme.title());
+
+        let rows: Vec<Row> = self
+            .list
+            .shown()
+            .into_iter()
+            .map(|i| {
+                let item = &items[i];
+                let is_marked = marked.contains(&i);
+                let mark = Cell::from(if is_marked { "+" } else { " " });
+                let cells = self.columns.iter().map(|column| {
+                    let text = (column.value)(item);
+                    let positions = self.list.match_positions_of(i, &text);
+                    let line = Line::from(highlight_spans(&text, &positions, highlight))
+                        .alignment(column.alignment);
+                    match column.style {
+                        Some(style) => Cell::from(line).style(style(item, theme)),
+                        None => Cell::from(line),
+                    }
+                });
+                let row = Row::new(std::iter::once(mark).chain(cells));
+                if is_marked {
+                    row.style(theme.highlight())
+                } else {
+                    row
+                }
+            })
+            .collect();
+
+        // Same layout the table widget uses: the `>` symbol, then the mark
+        // column and the declared columns
+        let widths: Vec<Constraint> = std::iter::once(Constraint::Length(1))
+            .chain(self.columns.iter().map(|c| c.width))
+            .collect();
+        let inner = area.inner(Margin::new(1, 1));
+        let cells_area = Rect {
+            x: inner.x + 1,
+            width: inner.width.saturating_sub(1),
+            ..inner
+        };
+        self.header_row = (inner.height > 0).then_some(inner.y);
+        self.column_areas = Layout::horizontal(widths.iter().copied())
+            .flex(Flex::Start)
+            .spacing(COLUMN_SPACING)
+            .split(cells_area)
+            .iter()
+            .skip(1)
+            .map(|r| (r.x, r.width))
+            .collect();
+
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(self.list.block(title, theme, focused))
+            .column_spacing(COLUMN_SPACING)
+            .row_highlight_style(theme.selected())
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+
+        let mut state = TableState::default()
+            .with_offset(self.list.state_mut().offset())
+            .with_selected(self.list.view_position());
+        frame.render_stateful_widget(table, area, &mut state);
+        *self.list.state_mut().offset_mut() = state.offset();
+    }
+}
+
+impl<T> Deref for SelectableTable<T> {
+    type Target = SelectableList<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.list
+    }
+}
+
+impl<T> DerefMut for SelectableTable<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.list
+    }
+}
@@ -1,6 +1,7 @@
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
 use ratatui::{
     Frame,
-    layout::Rect,
+    layout::{Position, Rect},
     text::{Line, Span},
     widgets::Paragraph,
 };
@@ -11,6 +12,8 @@ pub struct Tabs {
     app_title: Option<String>,
     titles: Vec<String>,
     selected: usize,
+    // Screen area of the last render, for mouse hit-testing
+    area: Rect,
 }
 
 impl Tabs {
@@ -20,6 +23,7 @@ impl Tabs {
             app_title: None,
             titles,
             selected: 0,
+            area: Rect::default(),
         }
     }
 
@@ -57,9 +61,43 @@ impl Tabs {
         }
     }
 
+    /// Tab whose title is at a screen position.
+    #[must_use]
+    pub fn tab_at(&self, column: u16, row: u16) -> Option<usize> {
+        if !self.area.contains(Position::new(column, row)) {
+            return None;
+        }
+        // Layout matches render: " ┌─ Tab1 ─┬─ Tab2 ─┐"
+        let mut start = self.area.x + 1;
+        for (i, title) in self.titles.iter().enumerate() {
+            let width = u16::try_from(title.chars().count()).unwrap_or(u16::MAX);
+            let end = start.saturating_add(width + 5);
+            if column > start && column < end {
+                return Some(i);
+            }
+            start = end;
+        }
+        None
+    }
+
+    /// Switch to a tab when its title is clicked. Returns whether the
+    /// event was over the tab bar.
+    pub fn handle_mouse(&mut self, event: &MouseEvent) -> bool {
+        if !self.area.contains(Position::new(event.column, event.row)) {
+            return false;
+        }
+        if event.kind == MouseEventKind::Down(MouseButton::Left)
+            && let Some(index) = self.tab_at(event.column, event.row)
+        {
+            self.selected = index;
+        }
+        true
+    }
+
     /// Render tabs as part of a top border line
     /// Output:  ┌─ Tab1 ─┬─ Tab2 ─┐        App Title v0.1 ─┐
-    pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        self.area = area;
         let mut spans: Vec<Span> = vec![Span::raw(" "), Span::styled("┌", theme.border())];
 
         // Tabs on the left
@@ -0,0 +1,22 @@
+use tuigreat::Screen;
+
+fn screen(args: &[&str]) -> Result<Screen, String> {
+    let args: Vec<String> = args.iter().map(ToString::to_string).collect();
+    Screen::from_args(&args)
+}
+
+#[test]
+fn test_screen_from_args() {
+    assert_eq!(screen(&["voltui"]), Ok(Screen::Full));
+    assert_eq!(
+        screen(&["voltui", "--inline"]),
+        Ok(Screen::Inline(Screen::INLINE_HEIGHT))
+    );
+    assert_eq!(screen(&["caltui", "--inline=12"]), Ok(Screen::Inline(12)));
+    assert!(screen(&["caltui", "--inline=12"]).unwrap().is_inline());
+    assert_eq!(
+        screen(&["caltui", "--inline=0"]),
+        Err("invalid --inline height `0`".to_string())
+    );
+    assert!(screen(&["caltui", "--inline=tall"]).is_err());
+}
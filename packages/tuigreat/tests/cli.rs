@@ -0,0 +1,75 @@
+use serde_json::json;
+use tuigreat::Cli;
+use tuigreat::cli::format_value;
+use tuigreat::widgets::CommandSpec;
+
+const COMMANDS: &[CommandSpec] = &[
+    CommandSpec::new("sink list", "", "List outputs"),
+    CommandSpec::new("sink volume", "<0-100> [name]", "Set the volume"),
+    CommandSpec::new("connect", "<ssid>", "Connect"),
+];
+const CLI: Cli = Cli::new("voltui", COMMANDS);
+
+fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(ToString::to_string).collect()
+}
+
+#[test]
+fn test_parse() {
+    // No command starts the UI, as does an argument that is not one
+    assert!(CLI.parse(&args(&["voltui"])).is_none());
+    assert!(CLI.parse(&args(&["voltui", "--inline"])).is_none());
+    assert!(CLI.parse(&args(&["mustui", "~/Music"])).is_none());
+
+    let call = CLI
+        .parse(&args(&["voltui", "--json", "sink", "volume", "40", "hdmi"]))
+        .unwrap()
+        .unwrap();
+    assert_eq!(call.name, "sink volume");
+    assert_eq!(call.args, ["40", "hdmi"]);
+    assert!(call.json);
+    assert_eq!(call.parse::<u8>(0), Ok(40));
+    assert_eq!(call.arg(1), Ok("hdmi"));
+
+    let error = CLI.parse(&args(&["voltui", "sink", "lst"])).unwrap();
+    assert_eq!(
+        error,
+        Err("unknown command `sink lst`, see `voltui help`".to_string())
+    );
+}
+
+#[test]
+fn test_call_arguments() {
+    let call = CLI
+        .parse(&args(&["nettui", "connect", "Home", "Wi-Fi"]))
+        .unwrap()
+        .unwrap();
+    assert!(!call.json);
+    assert_eq!(call.rest(0), Ok("Home Wi-Fi".to_string()));
+    assert_eq!(call.arg(2), Err("usage: connect <ssid>".to_string()));
+    assert!(call.parse::<u8>(0).is_err());
+    assert!(
+        CLI.usage()
+            .contains("  sink volume <0-100> [name]  Set the volume")
+    );
+}
+
+#[test]
+fn test_format_value() {
+    let sinks = json!([
+        {"name": "hdmi", "volume": 40, "muted": false},
+        {"name": "usb", "volume": 100, "muted": true},
+    ]);
+    assert_eq!(
+        format_value(&sinks, false),
+        "hdmi\t40\tfalse\nusb\t100\ttrue"
+    );
+    assert_eq!(
+        format_value(&sinks, true),
+        r#"[{"name":"hdmi","volume":40,"muted":false},{"name":"usb","volume":100,"muted":true}]"#
+    );
+    let sink = json!({"name": "hdmi", "address": null});
+    assert_eq!(format_value(&sink, false), "name: hdmi\naddress: ");
+    assert_eq!(format_value(&json!("Connected"), false), "Connected");
+    assert_eq!(format_value(&json!(null), false), "");
+}
@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use tuigreat::Clipboard;
+use tuigreat::clipboard::{self, Backend, Selection};
+use tuigreat::osc::Multiplexer;
+use tuigreat::runner::{self, FakeRunner, Reply};
+
+#[test]
+fn test_backend_order() {
+    assert_eq!(
+        Backend::parse_list("osc52, XSEL,pbcopy,wl-copy"),
+        vec![Backend::Osc52, Backend::Xsel, Backend::WlCopy]
+    );
+    assert_eq!(Backend::for_session(false, false), vec![Backend::Osc52]);
+    assert_eq!(
+        Backend::for_session(true, true),
+        vec![
+            Backend::WlCopy,
+            Backend::Xclip,
+            Backend::Xsel,
+            Backend::Osc52
+        ]
+    );
+    assert_eq!(Selection::from_name("Primary"), Some(Selection::Primary));
+    assert_eq!(Selection::from_name("secondary"), None);
+}
+
+#[test]
+fn test_osc52() {
+    assert_eq!(
+        clipboard::osc52("10.0.0.1", Selection::Clipboard),
+        "\x1b]52;c;MTAuMC4wLjE=\x07"
+    );
+    assert_eq!(
+        clipboard::osc52("ab", Selection::Primary),
+        "\x1b]52;p;YWI=\x07"
+    );
+    assert_eq!(
+        clipboard::osc52("abc", Selection::Both),
+        "\x1b]52;cp;YWJj\x07"
+    );
+
+    let osc = clipboard::osc52("a", Selection::Clipboard);
+    assert_eq!(Multiplexer::None.wrap(&osc), osc);
+    assert_eq!(
+        Multiplexer::Tmux.wrap(&osc),
+        "\x1bPtmux;\x1b\x1b]52;c;YQ==\x07\x1b\\"
+    );
+    assert_eq!(
+        Multiplexer::Screen.wrap(&osc),
+        "\x1bP\x1b]52;c;YQ==\x07\x1b\\"
+    );
+}
+
+/// The multiplexer detected from the variables in `vars`.
+fn multiplexer(vars: &[(&str, &str)]) -> Multiplexer {
+    Multiplexer::from_vars(|name| {
+        vars.iter()
+            .find(|(var, _)| *var == name)
+            .map(|(_, value)| (*value).to_string())
+    })
+}
+
+#[test]
+fn test_detect_multiplexer() {
+    assert_eq!(
+        multiplexer(&[("TMUX", "/tmp/tmux-1000/default,1,0")]),
+        Multiplexer::Tmux
+    );
+    assert_eq!(multiplexer(&[("STY", "1234.pts-0")]), Multiplexer::Screen);
+    // SSH from inside tmux passes TERM on but not TMUX
+    assert_eq!(
+        multiplexer(&[("TERM", "screen-256color")]),
+        Multiplexer::None
+    );
+    assert_eq!(multiplexer(&[("TMUX", "")]), Multiplexer::None);
+}
+
+#[test]
+fn test_copy_errors() {
+    let clipboard = Clipboard::detect().with_backends(vec![]);
+    assert!(clipboard.copy("").is_err());
+    let error = clipboard.copy("10.0.0.1").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "No clipboard backend (see ANYTUI_CLIPBOARD)"
+    );
+}
+
+#[test]
+fn test_paste() {
+    let fake = Arc::new(
+        FakeRunner::new()
+            .with(Reply::new(&["xclip", "-selection", "primary", "-o"]).stdout("middle"))
+            .with(Reply::new(&["xsel", "--clipboard", "--output"]).stdout("ctrl-v")),
+    );
+    runner::set(fake.clone());
+    let clipboard = Clipboard::detect().with_backends(vec![Backend::Osc52, Backend::Xclip]);
+    assert_eq!(
+        clipboard
+            .clone()
+            .with_selection(Selection::Primary)
+            .paste()
+            .as_deref(),
+        Some("middle")
+    );
+
+    // Backends without text are skipped
+    let clipboard = clipboard
+        .with_backends(vec![Backend::Xclip, Backend::Xsel])
+        .with_selection(Selection::Both);
+    assert_eq!(clipboard.paste().as_deref(), Some("ctrl-v"));
+    assert!(fake.ran(&["xclip", "-selection", "clipboard", "-o"]));
+}
@@ -0,0 +1,139 @@
+use tuigreat::Action;
+use tuigreat::widgets::{CommandEvent, CommandLine, CommandSpec};
+
+fn command_line() -> CommandLine {
+    CommandLine::new(vec![
+        CommandSpec::new("volume", "<0-100>", "Set volume"),
+        CommandSpec::new("set-default", "", "Make the selection the default"),
+        CommandSpec::new("connect", "<ssid>", "Connect to a network"),
+    ])
+}
+
+/// Type `text` into an open command line.
+fn type_text(line: &mut CommandLine, text: &str) {
+    for c in text.chars() {
+        line.handle_action(Action::Char(c));
+    }
+}
+
+fn run(line: &mut CommandLine, text: &str) -> CommandEvent {
+    line.open();
+    type_text(line, text);
+    line.handle_action(Action::Select)
+}
+
+#[test]
+fn test_run_command_with_argument() {
+    let mut line = command_line();
+    let CommandEvent::Run(invocation) = run(&mut line, "volume 40") else {
+        panic!("expected a command");
+    };
+    assert_eq!(invocation.name, "volume");
+    assert_eq!(invocation.parse::<u8>(), Ok(40));
+    assert!(!line.is_active());
+}
+
+#[test]
+fn test_prefix_resolves_unique_command() {
+    let mut line = command_line();
+    let CommandEvent::Run(invocation) = run(&mut line, "set") else {
+        panic!("expected a command");
+    };
+    assert_eq!(invocation.name, "set-default");
+}
+
+#[test]
+fn test_unknown_and_invalid_input() {
+    let mut line = command_line();
+    assert_eq!(
+        run(&mut line, "frobnicate"),
+        CommandEvent::Error("Invalid command: frobnicate".to_string())
+    );
+    let CommandEvent::Run(invocation) = run(&mut line, "volume loud") else {
+        panic!("expected a command");
+    };
+    assert!(invocation.parse::<u8>().unwrap_err().contains("<0-100>"));
+    let CommandEvent::Run(invocation) = run(&mut line, "connect") else {
+        panic!("expected a command");
+    };
+    assert!(invocation.arg().is_err());
+}
+
+#[test]
+fn test_parse_without_typing() {
+    let line = command_line();
+    let CommandEvent::Run(invocation) = line.parse(" vol 40 ") else {
+        panic!("expected a command");
+    };
+    assert_eq!(invocation.name, "volume");
+    assert_eq!(invocation.arg(), Ok("40"));
+    assert_eq!(line.parse(""), CommandEvent::Cancelled);
+    assert!(matches!(line.parse("mute"), CommandEvent::Error(_)));
+    assert!(line.history().is_empty());
+}
+
+#[test]
+fn test_cancel() {
+    let mut line = command_line();
+    line.open();
+    type_text(&mut line, "vo");
+    assert_eq!(line.handle_action(Action::Quit), CommandEvent::Cancelled);
+    assert!(!line.is_active());
+
+    // Backspace on an empty line closes it
+    line.open();
+    type_text(&mut line, "v");
+    assert_eq!(line.handle_action(Action::Back), CommandEvent::Editing);
+    assert_eq!(line.handle_action(Action::Back), CommandEvent::Cancelled);
+    assert!(line.history().is_empty());
+}
+
+#[test]
+fn test_complete_command_name() {
+    let mut line = command_line();
+    line.open();
+    type_text(&mut line, "vo");
+    line.handle_action(Action::Char('\t'));
+    assert_eq!(line.input(), "volume ");
+}
+
+#[test]
+fn test_complete_cycles_candidates() {
+    let mut line = command_line();
+    line.set_arguments("connect", ["Home", "HomeGuest", "Office"]);
+    line.open();
+    type_text(&mut line, "connect ho");
+    line.handle_action(Action::Char('\t'));
+    assert_eq!(line.input(), "connect Home");
+    line.handle_action(Action::Char('\t'));
+    assert_eq!(line.input(), "connect HomeGuest");
+    line.handle_action(Action::Char('\t'));
+    assert_eq!(line.input(), "connect Home");
+    // Typing ends the cycle
+    type_text(&mut line, "!");
+    line.handle_action(Action::Char('\t'));
+    assert_eq!(line.input(), "connect Home!");
+}
+
+#[test]
+fn test_history_filters_by_prefix() {
+    let mut line = command_line();
+    run(&mut line, "volume 40");
+    run(&mut line, "set-default");
+    run(&mut line, "volume 60");
+    run(&mut line, "volume 40");
+    assert_eq!(line.history(), ["set-default", "volume 60", "volume 40"]);
+
+    line.open();
+    type_text(&mut line, "vol");
+    line.handle_action(Action::Up);
+    assert_eq!(line.input(), "volume 40");
+    line.handle_action(Action::Up);
+    assert_eq!(line.input(), "volume 60");
+    line.handle_action(Action::Up);
+    assert_eq!(line.input(), "volume 60");
+    line.handle_action(Action::Down);
+    assert_eq!(line.input(), "volume 40");
+    line.handle_action(Action::Down);
+    assert_eq!(line.input(), "vol");
+}
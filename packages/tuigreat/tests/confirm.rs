@@ -0,0 +1,108 @@
+use ratatui::{Terminal, backend::TestBackend};
+use tuigreat::config::ConfigFile;
+use tuigreat::testing::screen_text;
+use tuigreat::widgets::{Choice, ConfirmEvent, ConfirmPopup};
+use tuigreat::{Action, Confirmations, Theme};
+
+fn from_config(text: &str, app: &str) -> Confirmations {
+    Confirmations::from_config(&ConfigFile::parse(text).unwrap(), app, &["remove"])
+}
+
+#[test]
+fn test_config_replaces_defaults() {
+    assert!(Confirmations::new(&["remove"]).required("remove"));
+
+    let text = "[global]\nconfirm = [\"remove\", \"down\"]\n\n[blutui]\nconfirm = []\n";
+    let nettui = from_config(text, "nettui");
+    assert!(nettui.required("down"));
+    assert!(nettui.required("remove"));
+    let blutui = from_config(text, "blutui");
+    assert!(!blutui.required("remove"));
+    assert!(blutui.ask("remove", "Remove Buds?").is_none());
+
+    let bad = from_config("[global]\nconfirm = 1\nask = true\n", "voltui");
+    assert!(bad.required("remove"));
+    assert_eq!(
+        bad.warnings(),
+        [
+            "confirm.toml: [global] `confirm` must be a list of operations",
+            "confirm.toml: [global] unknown setting `ask`",
+        ]
+    );
+}
+
+#[test]
+fn test_answers() {
+    let confirmations = Confirmations::new(&["remove"]);
+    let popup = confirmations.ask("remove", "Remove Buds?").unwrap();
+    assert_eq!(popup.operation(), "remove");
+    assert_eq!(popup.choice(), Choice::No);
+
+    // Enter picks the focused button, which starts on "No"
+    assert_eq!(
+        popup.clone().handle_action(Action::Select),
+        ConfirmEvent::Cancelled
+    );
+    let mut yes = popup.clone().with_default(Choice::Yes);
+    assert_eq!(yes.handle_action(Action::Select), ConfirmEvent::Confirmed);
+
+    let mut moved = popup.clone();
+    assert_eq!(moved.handle_action(Action::Left), ConfirmEvent::Pending);
+    assert_eq!(moved.choice(), Choice::Yes);
+    assert_eq!(
+        moved.handle_action(Action::Char('\t')),
+        ConfirmEvent::Pending
+    );
+    assert_eq!(moved.choice(), Choice::No);
+
+    // `y` is bound to yank and `n` to search by default
+    assert_eq!(
+        popup.clone().handle_action(Action::Yank),
+        ConfirmEvent::Confirmed
+    );
+    assert_eq!(
+        popup.clone().handle_action(Action::SearchNext),
+        ConfirmEvent::Cancelled
+    );
+    assert_eq!(
+        popup.clone().handle_action(Action::Back),
+        ConfirmEvent::Cancelled
+    );
+    assert_eq!(
+        popup.clone().handle_action(Action::Down),
+        ConfirmEvent::Pending
+    );
+}
+
+#[test]
+fn test_dont_ask_again() {
+    let mut confirmations = Confirmations::new(&["remove", "restart"]);
+    let mut popup = confirmations.ask("remove", "Remove Buds?").unwrap();
+    confirmations.confirmed(&popup);
+    assert!(confirmations.required("remove"));
+
+    assert_eq!(
+        popup.handle_action(Action::Char(' ')),
+        ConfirmEvent::Pending
+    );
+    assert!(popup.dont_ask());
+    confirmations.confirmed(&popup);
+    assert!(!confirmations.required("remove"));
+    assert!(confirmations.required("restart"));
+}
+
+#[test]
+fn test_render() {
+    let mut popup = ConfirmPopup::new("remove", "Remove Buds? It has to be paired again.")
+        .with_title("Remove device");
+    popup.handle_action(Action::Char(' '));
+    let mut terminal = Terminal::new(TestBackend::new(60, 12)).unwrap();
+    terminal
+        .draw(|frame| popup.render(frame, &Theme::dark()))
+        .unwrap();
+    let screen = screen_text(terminal.backend().buffer());
+    assert!(screen.contains(" Remove device "));
+    assert!(screen.contains("Remove Buds? It has to be paired again."));
+    assert!(screen.contains("Yes     [ No ]"));
+    assert!(screen.contains("[x] Don't ask again"));
+}
@@ -0,0 +1,216 @@
+use std::io;
+use std::os::unix::process::ExitStatusExt;
+use std::process::{ExitStatus, Output};
+use std::sync::Arc;
+use std::time::Duration;
+
+use ratatui::{Terminal, backend::TestBackend};
+use tuigreat::runner::{self, Command, CommandRunner, FakeRunner, Reply};
+use tuigreat::testing::screen_text;
+use tuigreat::widgets::DetailPopup;
+use tuigreat::{Action, Error, StatusLevel, StatusMessage, Theme};
+
+fn output(status: i32, stdout: &str, stderr: &str) -> Output {
+    Output {
+        status: ExitStatus::from_raw(status << 8),
+        stdout: stdout.as_bytes().to_vec(),
+        stderr: stderr.as_bytes().to_vec(),
+    }
+}
+
+#[test]
+fn test_from_output() {
+    assert_eq!(Error::from_output("true", &output(0, "", "noise")), None);
+
+    let error = Error::from_output(
+        "nmcli con up Home",
+        &output(
+            4,
+            "",
+            "\nError: Connection activation failed.\nHint: see journal\n",
+        ),
+    )
+    .unwrap();
+    assert_eq!(error.to_string(), "Error: Connection activation failed.");
+    assert_eq!(error.level(), StatusLevel::Error);
+    assert_eq!(
+        error.detail().unwrap(),
+        "$ nmcli con up Home\n\nError: Connection activation failed.\nHint: see journal"
+    );
+
+    // Tools that report errors on stdout
+    let error = Error::from_output(
+        "bluetoothctl connect X",
+        &output(1, "Failed to connect\n", ""),
+    );
+    assert_eq!(error.unwrap().to_string(), "Failed to connect");
+
+    let error = Error::from_output("false", &output(1, "", "")).unwrap();
+    assert_eq!(error.to_string(), "`false` exited with status 1");
+    assert_eq!(error.detail(), None);
+}
+
+#[test]
+fn test_permission_denied() {
+    let error = Error::from_output(
+        "networkctl up wlan0",
+        &output(
+            1,
+            "",
+            "Failed to bring up: Access denied (Not authorized)\n",
+        ),
+    )
+    .unwrap();
+    assert!(matches!(error, Error::PermissionDenied { .. }));
+    assert_eq!(error.to_string(), "Permission denied: networkctl up wlan0");
+    assert!(error.detail().unwrap().contains("Not authorized"));
+}
+
+#[test]
+fn test_from_io() {
+    let missing = io::Error::from(io::ErrorKind::NotFound);
+    let error = Error::from_io("iwctl station list", &missing);
+    assert_eq!(error, Error::MissingBinary("iwctl".to_string()));
+    assert_eq!(error.level(), StatusLevel::Warning);
+    assert_eq!(error.to_string(), "`iwctl` not found, is it installed?");
+}
+
+#[test]
+fn test_status_message_from_error() {
+    let status = StatusMessage::from(Error::parse("pactl sinks", "expected value"));
+    assert_eq!(status.text, "Could not parse pactl sinks: expected value");
+    assert_eq!(status.level, StatusLevel::Error);
+    assert_eq!(status.detail, None);
+
+    let mut status = StatusMessage::info("Ready");
+    status
+        .report(Box::new(Error::Timeout {
+            command: "bluetoothctl connect X".to_string(),
+            after: Duration::from_secs(20),
+        }))
+        .unwrap();
+    assert_eq!(status.text, "`bluetoothctl connect X` timed out after 20s");
+    assert_eq!(status.level, StatusLevel::Warning);
+
+    // Other errors are handed back
+    let other = status.report("terminal gone".into()).unwrap_err();
+    assert_eq!(other.to_string(), "terminal gone");
+    assert_eq!(status.level, StatusLevel::Warning);
+}
+
+#[test]
+fn test_run() {
+    let fake = FakeRunner::new()
+        .with(Reply::new(&["pactl", "info"]).stdout("Server Name: PipeWire\n"))
+        .with(
+            Reply::new(&["pactl", "set-default-sink", "*"])
+                .stderr("Failure: No such entity\n")
+                .status(1),
+        );
+    runner::set(Arc::new(fake));
+    assert_eq!(
+        Command::new("pactl").arg("info").run().unwrap(),
+        "Server Name: PipeWire\n"
+    );
+    let error = Command::new("pactl")
+        .args(["set-default-sink", "nope"])
+        .run()
+        .unwrap_err();
+    assert_eq!(
+        error,
+        Error::Backend {
+            command: "pactl set-default-sink nope".to_string(),
+            message: "Failure: No such entity".to_string(),
+            stderr: "Failure: No such entity".to_string(),
+        }
+    );
+    assert!(matches!(
+        Command::new("amixer").run(),
+        Err(Error::MissingBinary(program)) if program == "amixer"
+    ));
+}
+
+#[test]
+fn test_redact() {
+    let fake = FakeRunner::new().with(
+        Reply::new(&["nmcli", "dev", "wifi", "connect", "Home", "password", "*"])
+            .stderr("Error: bad password\n")
+            .status(4),
+    );
+    runner::set(Arc::new(fake));
+    let mut command = Command::new("nmcli");
+    command
+        .args(["dev", "wifi", "connect", "Home", "password", "hunter22"])
+        .redact("hunter22");
+    assert_eq!(command.line(), "nmcli dev wifi connect Home password ***");
+    let detail = command.run().unwrap_err().detail().unwrap();
+    assert!(!detail.contains("hunter22"));
+}
+
+/// Never answers within a test's patience.
+struct Hang;
+
+impl CommandRunner for Hang {
+    fn output(&self, _: &str, _: &[String], _: Option<&str>) -> io::Result<Output> {
+        std::thread::sleep(Duration::from_secs(2));
+        Ok(output(0, "", ""))
+    }
+}
+
+#[test]
+fn test_timeout() {
+    runner::set(Arc::new(Hang));
+    let error = Command::new("bluetoothctl")
+        .args(["connect", "AA:BB"])
+        .timeout(Duration::from_millis(20))
+        .run()
+        .unwrap_err();
+    assert_eq!(
+        error,
+        Error::Timeout {
+            command: "bluetoothctl connect AA:BB".to_string(),
+            after: Duration::from_millis(20),
+        }
+    );
+    assert_eq!(error.level(), StatusLevel::Warning);
+}
+
+#[test]
+fn test_detail_popup() {
+    let status = StatusMessage::from(Error::Backend {
+        command: "pactl load-module x".to_string(),
+        message: "Failure: Module initialization failed".to_string(),
+        stderr: "Failure: Module initialization failed".to_string(),
+    });
+    assert!(DetailPopup::for_status(&StatusMessage::info("Ready")).is_none());
+    let mut popup = DetailPopup::for_status(&status).unwrap();
+    assert_eq!(
+        popup.text(),
+        "$ pactl load-module x\nFailure: Module initialization failed"
+    );
+
+    let mut terminal = Terminal::new(TestBackend::new(50, 10)).unwrap();
+    terminal
+        .draw(|frame| popup.render(frame, &Theme::dark()))
+        .unwrap();
+    let screen = screen_text(terminal.backend().buffer());
+    assert!(screen.contains("Failure: Module initialization"));
+    assert!(screen.contains("$ pactl load-module x"));
+
+    assert!(popup.handle_action(Action::Down));
+    assert!(popup.handle_action(Action::Bottom));
+    assert!(!popup.handle_action(Action::Back));
+}
+
+#[test]
+fn test_status_line_hints_at_details() {
+    let theme = Theme::dark();
+    let plain = StatusMessage::error("Yank failed").to_line(&theme);
+    assert_eq!(plain.to_string(), " Yank failed");
+    let status =
+        StatusMessage::from(Error::from_output("pactl x", &output(1, "", "Failure\n")).unwrap());
+    assert_eq!(
+        status.to_line(&theme).to_string(),
+        " Failure  (! for details)"
+    );
+}
@@ -0,0 +1,76 @@
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use tuigreat::{Events, Interval};
+
+// ============================================================================
+// Interval
+// ============================================================================
+
+#[test]
+fn test_interval_fires_once_per_period() {
+    let mut timer = Interval::new(Duration::from_secs(2));
+    let start = Instant::now();
+    assert!(!timer.due(start));
+    let later = start + Duration::from_secs(3);
+    assert!(timer.due(later));
+    assert!(!timer.due(later));
+    assert_eq!(timer.deadline(), later + Duration::from_secs(2));
+}
+
+#[test]
+fn test_interval_reset() {
+    let mut timer = Interval::new(Duration::from_secs(1));
+    let later = Instant::now() + Duration::from_secs(5);
+    timer.reset(later);
+    assert!(!timer.due(later));
+    assert_eq!(timer.deadline(), later + Duration::from_secs(1));
+}
+
+// ============================================================================
+// Event sources
+// ============================================================================
+
+#[test]
+fn test_forward_relays_until_disconnect() {
+    let events = Events::default();
+    let (tx, rx) = mpsc::channel();
+    let relayed = events.forward(rx);
+    tx.send(1).unwrap();
+    tx.send(2).unwrap();
+    drop(tx);
+    assert_eq!(relayed.iter().collect::<Vec<_>>(), vec![1, 2]);
+}
+
+#[test]
+fn test_lines_from_command() {
+    let events = Events::default();
+    let output = events
+        .lines(Command::new("printf").arg("new sink\\nremove sink\\n"))
+        .unwrap();
+    let lines: Vec<String> = output.receiver().iter().collect();
+    assert_eq!(lines, vec!["new sink", "remove sink"]);
+}
+
+#[test]
+fn test_lines_stop_the_command_when_dropped() {
+    let events = Events::default();
+    let output = events.lines(Command::new("sleep").arg("30")).unwrap();
+    let proc = PathBuf::from(format!("/proc/{}", output.id()));
+    assert!(proc.exists());
+    drop(output);
+    // Killed and reaped, so not even a zombie is left
+    assert!(!proc.exists());
+}
+
+#[test]
+fn test_lines_missing_command() {
+    let events = Events::default();
+    assert!(
+        events
+            .lines(&mut Command::new("no-such-command-xyz"))
+            .is_err()
+    );
+}
@@ -0,0 +1,39 @@
+use tuigreat::fuzzy::substring_match;
+use tuigreat::fuzzy_match;
+
+#[test]
+fn test_fuzzy_matches_in_order() {
+    let m = fuzzy_match("pnk", "Pink Floyd").unwrap();
+    assert_eq!(m.positions, vec![0, 2, 3]);
+    assert!(fuzzy_match("knp", "Pink Floyd").is_none());
+}
+
+#[test]
+fn test_fuzzy_prefers_tight_window() {
+    // The match ending first is narrowed to the closest start
+    let m = fuzzy_match("ab", "a xab").unwrap();
+    assert_eq!(m.positions, vec![3, 4]);
+}
+
+#[test]
+fn test_fuzzy_scores_boundaries_and_runs() {
+    let boundary = fuzzy_match("dm", "Dark Matter").unwrap();
+    let inside = fuzzy_match("dm", "addmore").unwrap();
+    let scattered = fuzzy_match("dm", "adxxxm").unwrap();
+    assert!(boundary.score > inside.score);
+    assert!(inside.score > scattered.score);
+}
+
+#[test]
+fn test_fuzzy_empty_pattern() {
+    let m = fuzzy_match("", "anything").unwrap();
+    assert_eq!(m.score, 0);
+    assert!(m.positions.is_empty());
+}
+
+#[test]
+fn test_substring_match() {
+    assert_eq!(substring_match("LOY", "Pink Floyd"), Some(vec![6, 7, 8]));
+    assert_eq!(substring_match("öy", "Mötley Crüe"), None);
+    assert_eq!(substring_match("crü", "Mötley Crüe"), Some(vec![7, 8, 9]));
+}
@@ -0,0 +1,251 @@
+use std::time::{Duration, Instant};
+
+use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{Frame, Terminal, backend::TestBackend, layout::Position, widgets::Paragraph};
+use serde_json::{Value, json};
+use tuigreat::ipc::{Op, Request};
+use tuigreat::testing::TestHarness;
+use tuigreat::{Action, App, AppResult, Hub, Theme};
+
+/// Counts key presses, ticks and clicks.
+struct Counter {
+    theme: Theme,
+    name: &'static str,
+    presses: usize,
+    ticks: usize,
+    clicked: Option<(u16, u16)>,
+    // `None` ticks on every wake-up, like an app waiting on events
+    tick_every: Option<Duration>,
+}
+
+impl Counter {
+    fn new(name: &'static str, tick_every: Option<Duration>) -> Self {
+        Self {
+            theme: Theme::dark(),
+            name,
+            presses: 0,
+            ticks: 0,
+            clicked: None,
+            tick_every,
+        }
+    }
+}
+
+impl App for Counter {
+    fn title(&self) -> &str {
+        self.name
+    }
+
+    fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    fn handle_action(&mut self, action: Action) -> AppResult<bool> {
+        match action {
+            Action::Quit => return Ok(false),
+            Action::Up => self.presses += 1,
+            _ => {}
+        }
+        Ok(true)
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let mut text = format!(
+            "{} pressed {} ticked {}",
+            self.name, self.presses, self.ticks
+        );
+        if let Some((column, row)) = self.clicked {
+            text.push_str(&format!(" clicked {column},{row}"));
+            frame.set_cursor_position((column, row));
+        }
+        frame.render_widget(Paragraph::new(text), frame.area());
+    }
+
+    fn tick(&mut self) -> AppResult<()> {
+        self.ticks += 1;
+        Ok(())
+    }
+
+    fn next_tick(&self) -> Option<Instant> {
+        self.tick_every.map(|every| Instant::now() + every)
+    }
+
+    fn mouse_capture(&self) -> bool {
+        true
+    }
+
+    fn handle_mouse(&mut self, event: MouseEvent) -> AppResult<bool> {
+        self.clicked = Some((event.column, event.row));
+        Ok(true)
+    }
+
+    fn summary(&self) -> Option<String> {
+        Some(self.presses.to_string())
+    }
+
+    fn output(&self) -> Option<String> {
+        (self.presses > 0).then(|| format!("{} {}", self.name, self.presses))
+    }
+
+    fn session_version(&self) -> u32 {
+        2
+    }
+
+    fn save_session(&self) -> Option<Value> {
+        Some(json!({"presses": self.presses}))
+    }
+
+    fn restore_session(&mut self, saved: &Value) {
+        if let Some(presses) = saved["presses"].as_u64() {
+            self.presses = usize::try_from(presses).unwrap_or_default();
+        }
+    }
+}
+
+fn hub() -> Hub {
+    Hub::new()
+        .with_app("vol", Counter::new("vol", None))
+        .with_app("net", Counter::new("net", Some(Duration::from_secs(60))))
+}
+
+/// The text the app drew on the second row.
+fn app_line(harness: &TestHarness<Hub>) -> String {
+    harness
+        .screen()
+        .lines()
+        .nth(1)
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[test]
+fn test_switching() {
+    let mut harness = TestHarness::new(hub(), 40, 4);
+    harness.press("k k");
+    let screen = harness.screen();
+    let lines: Vec<&str> = screen.lines().collect();
+    assert!(lines[0].starts_with(" ┌─ vol ─┬─ net ─┐"), "{screen}");
+    assert!(lines[0].ends_with("anytui ─┐"), "{screen}");
+    assert!(lines[1].starts_with("vol pressed 2"), "{screen}");
+    assert_eq!(lines[3], " vol 2 │ net 0");
+
+    harness.press("A-2").press("k");
+    assert_eq!(harness.app().selected(), 1);
+    assert!(app_line(&harness).starts_with("net pressed 1"));
+    assert_eq!(harness.screen().lines().last(), Some(" vol 2 │ net 1"));
+
+    // There is no ninth app to switch to
+    harness.press("A-9");
+    assert_eq!(harness.app().selected(), 1);
+
+    harness.click(4, 0);
+    assert_eq!(harness.app().selected(), 0);
+
+    harness.press("q");
+    assert!(!harness.is_running());
+}
+
+#[test]
+fn test_background_ticks() {
+    let mut harness = TestHarness::new(hub(), 60, 4);
+    harness.tick().tick().tick();
+    assert_eq!(app_line(&harness), "vol pressed 0 ticked 3");
+
+    // `net` waits for its deadline while in the background, and ticks
+    // with every pass of the loop once selected
+    harness.press("A-2");
+    assert_eq!(app_line(&harness), "net pressed 0 ticked 1");
+    harness.press("A-1");
+    assert_eq!(app_line(&harness), "vol pressed 0 ticked 5");
+    harness.tick();
+    harness.press("A-2");
+    assert_eq!(app_line(&harness), "net pressed 0 ticked 2");
+}
+
+#[test]
+fn test_mouse_is_relative_to_the_app() {
+    let mut harness = TestHarness::new(hub(), 60, 6);
+    harness.mouse(MouseEvent {
+        kind: MouseEventKind::Down(MouseButton::Left),
+        column: 5,
+        row: 3,
+        modifiers: KeyModifiers::NONE,
+    });
+    assert_eq!(app_line(&harness), "vol pressed 0 ticked 1 clicked 5,2");
+
+    // The status line is not part of the app
+    harness.click(5, 5);
+    assert!(app_line(&harness).ends_with("clicked 5,2"));
+}
+
+#[test]
+fn test_cursor_is_relative_to_the_app() {
+    let mut hub = hub();
+    let mut terminal = Terminal::new(TestBackend::new(60, 6)).unwrap();
+    terminal.draw(|frame| hub.render(frame)).unwrap();
+    hub.handle_mouse(MouseEvent {
+        kind: MouseEventKind::Down(MouseButton::Left),
+        column: 5,
+        row: 3,
+        modifiers: KeyModifiers::NONE,
+    })
+    .unwrap();
+    terminal.draw(|frame| hub.render(frame)).unwrap();
+    // Below the tabs, where the app put it
+    assert_eq!(terminal.get_cursor_position().unwrap(), Position::new(5, 3));
+}
+
+#[test]
+fn test_output_of_the_selected_app() {
+    let mut harness = TestHarness::new(hub(), 60, 6);
+    assert_eq!(harness.app().output(), None);
+    harness.press("k").press("A-2").press("k k");
+    assert_eq!(harness.app().output(), Some("net 2".to_string()));
+}
+
+#[test]
+fn test_requests_go_to_the_named_app() {
+    let mut harness = TestHarness::new(hub(), 40, 4);
+    let up = Request::new(Op::Action(Action::Up, Some(2)));
+    harness.request(&up.clone().with_app("net"));
+    harness.request(&up);
+    assert_eq!(harness.screen().lines().last(), Some(" vol 2 │ net 2"));
+    assert_eq!(harness.app().selected(), 0);
+
+    let state = harness.request(&Request::new(Op::State));
+    assert_eq!(state["result"]["selected"], "vol");
+    assert_eq!(state["result"]["apps"]["net"]["summary"], "2");
+
+    let response = harness.request(&Request::new(Op::State).with_app("bt"));
+    assert_eq!(
+        response,
+        json!({"ok": false, "error": "bt is not open in the hub"})
+    );
+}
+
+#[test]
+fn test_session() {
+    let mut hub = hub();
+    hub.handle_action(Action::Up).unwrap();
+    hub.select(1);
+    let saved = hub.save_session().unwrap();
+    assert_eq!(saved["selected"], "net");
+    assert_eq!(
+        saved["apps"]["vol"],
+        json!({"version": 2, "state": {"presses": 1}})
+    );
+
+    let mut restored = self::hub();
+    restored.restore_session(&saved);
+    assert_eq!(restored.selected(), 1);
+    let harness = TestHarness::new(restored, 40, 4);
+    assert_eq!(harness.screen().lines().last(), Some(" vol 1 │ net 0"));
+
+    // Sessions of an older version are dropped
+    let mut saved = saved;
+    saved["apps"]["vol"]["version"] = json!(1);
+    let mut restored = self::hub();
+    restored.restore_session(&saved);
+    let harness = TestHarness::new(restored, 40, 4);
+    assert_eq!(harness.screen().lines().last(), Some(" vol 0 │ net 0"));
+}
@@ -0,0 +1,97 @@
+use tuigreat::widgets::TextInput;
+use tuigreat::{Action, Edit};
+
+fn input(text: &str) -> TextInput {
+    let mut input = TextInput::new();
+    input.set_text(text);
+    input
+}
+
+#[test]
+fn test_insert_at_cursor() {
+    let mut input = input("helo");
+    input.edit(Edit::Left);
+    input.insert('l');
+    assert_eq!(input.text(), "hello");
+    assert_eq!(input.cursor(), 4);
+
+    input.edit(Edit::Home);
+    input.insert_str("oh\n ");
+    assert_eq!(input.text(), "oh hello");
+    assert_eq!(input.cursor(), 3);
+}
+
+#[test]
+fn test_backspace_and_delete() {
+    let mut input = input("abc");
+    input.edit(Edit::Left);
+    input.backspace();
+    assert_eq!(input.text(), "ac");
+    input.edit(Edit::Delete);
+    assert_eq!(input.text(), "a");
+    input.edit(Edit::Delete);
+    assert_eq!(input.text(), "a");
+}
+
+#[test]
+fn test_word_motions() {
+    let mut input = input("ls /usr/local_bin");
+    input.edit(Edit::WordLeft);
+    assert_eq!(input.before_cursor(), "ls /usr/");
+    input.edit(Edit::WordLeft);
+    assert_eq!(input.before_cursor(), "ls /");
+    input.edit(Edit::WordRight);
+    assert_eq!(input.before_cursor(), "ls /usr");
+    input.edit(Edit::End);
+    assert_eq!(input.after_cursor(), "");
+}
+
+#[test]
+fn test_delete_word_and_line() {
+    let mut input = input("connect home wifi");
+    input.edit(Edit::DeleteWord);
+    assert_eq!(input.text(), "connect home ");
+    input.edit(Edit::WordLeft);
+    input.edit(Edit::DeleteToEnd);
+    assert_eq!(input.text(), "connect ");
+    input.edit(Edit::Left);
+    input.edit(Edit::DeleteToStart);
+    assert_eq!(input.text(), " ");
+    assert_eq!(input.cursor(), 0);
+}
+
+#[test]
+fn test_unicode_cursor() {
+    let mut input = input("häää");
+    input.edit(Edit::Left);
+    input.backspace();
+    assert_eq!(input.text(), "hää");
+    assert_eq!(input.cursor(), 2);
+    input.edit(Edit::DeleteWord);
+    assert_eq!(input.text(), "ä");
+}
+
+#[test]
+fn test_masked_display() {
+    let mut input = TextInput::masked();
+    input.insert_str("hunter2");
+    assert_eq!(input.text(), "hunter2");
+    assert_eq!(input.display(), "*******");
+}
+
+#[test]
+fn test_handle_action() {
+    let mut input = TextInput::new();
+    assert!(input.handle_action(Action::Char('a')));
+    assert!(input.handle_action(Action::Edit(Edit::Home)));
+    assert!(input.handle_action(Action::Char('b')));
+    assert_eq!(input.text(), "ba");
+
+    // Control characters, Enter and Esc are left to the caller
+    assert!(!input.handle_action(Action::Char('\t')));
+    assert!(!input.handle_action(Action::Select));
+    assert!(!input.handle_action(Action::Quit));
+
+    input.clear();
+    assert!(!input.handle_action(Action::Back));
+}
@@ -0,0 +1,163 @@
+use std::fs::{self, Permissions};
+use std::io::ErrorKind;
+use std::os::unix::fs::{PermissionsExt, symlink};
+
+use ratatui::Frame;
+use serde_json::json;
+use tuigreat::ipc::{self, Op, Request};
+use tuigreat::testing::TestHarness;
+use tuigreat::{Action, App, AppResult, Theme};
+
+fn args(args: &[&str]) -> Result<Request, String> {
+    let args: Vec<String> = args.iter().map(ToString::to_string).collect();
+    Request::from_args(&args)
+}
+
+#[test]
+fn test_request_from_args() {
+    let volume_up = Request::new(Op::Action(Action::VolumeUp, None));
+    assert_eq!(args(&["volume-up"]), Ok(volume_up.clone()));
+    assert_eq!(args(&["volume_up", "--json"]), Ok(volume_up.clone()));
+    assert_eq!(
+        args(&["--app", "voltui", "volume-up", "5"]),
+        Ok(Request::new(Op::Action(Action::VolumeUp, Some(5))).with_app("voltui"))
+    );
+    assert_eq!(
+        args(&["state", "--app=blutui"]),
+        Ok(Request::new(Op::State).with_app("blutui"))
+    );
+
+    let volume = Request::new(Op::Command("volume 40".to_string()));
+    assert_eq!(args(&[":volume", "40"]), Ok(volume.clone()));
+    assert_eq!(args(&["run", "volume", "40"]), Ok(volume));
+
+    assert_eq!(
+        args(&[]),
+        Err("missing action, command or `state`".to_string())
+    );
+    assert_eq!(
+        args(&["louder"]),
+        Err("unknown action `louder`".to_string())
+    );
+    assert_eq!(args(&["up", "0"]), Err("invalid count `0`".to_string()));
+    assert!(args(&[":"]).is_err());
+    assert!(args(&["up", "2", "3"]).is_err());
+}
+
+#[test]
+fn test_request_json() {
+    let request = Request::new(Op::Action(Action::PageDown, Some(3))).with_app("mustui");
+    let line = request.to_json().to_string();
+    assert_eq!(Request::from_json(&line), Ok(request));
+    assert_eq!(
+        Request::from_json(r#"{"command":"play Artist"}"#),
+        Ok(Request::new(Op::Command("play Artist".to_string())))
+    );
+    assert_eq!(
+        Request::from_json(r#"{"state":true}"#),
+        Ok(Request::new(Op::State))
+    );
+    assert!(Request::from_json(r#"{"action":"up","count":-1}"#).is_err());
+    assert!(Request::from_json(r#"{"query":"state"}"#).is_err());
+    assert!(Request::from_json("volume-up").is_err());
+}
+
+/// A volume knob without commands.
+struct Knob {
+    theme: Theme,
+    volume: u8,
+}
+
+impl App for Knob {
+    fn title(&self) -> &'static str {
+        "knob"
+    }
+
+    fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    fn handle_action(&mut self, action: Action) -> AppResult<bool> {
+        match action {
+            Action::Quit => return Ok(false),
+            Action::VolumeUp => self.volume += 5,
+            _ => {}
+        }
+        Ok(true)
+    }
+
+    fn render(&mut self, _frame: &mut Frame) {}
+
+    fn summary(&self) -> Option<String> {
+        Some(format!("{}%", self.volume))
+    }
+}
+
+#[test]
+fn test_requests_reach_the_app() {
+    let knob = Knob {
+        theme: Theme::dark(),
+        volume: 40,
+    };
+    let mut harness = TestHarness::new(knob, 20, 2);
+    let response = harness.request(&Request::new(Op::Action(Action::VolumeUp, Some(2))));
+    assert_eq!(response, json!({"ok": true, "result": null}));
+    assert_eq!(harness.app().volume, 50);
+
+    let state = harness.request(&Request::new(Op::State));
+    assert_eq!(state["result"], json!({"title": "knob", "summary": "50%"}));
+
+    // Errors go back to the client, and the app carries on
+    let response = harness.request(&Request::new(Op::Command("reset".to_string())));
+    assert_eq!(
+        response,
+        json!({"ok": false, "error": "knob has no commands"})
+    );
+    assert!(harness.is_running());
+
+    harness.request(&Request::new(Op::Action(Action::Quit, None)));
+    assert!(!harness.is_running());
+}
+
+#[test]
+fn test_socket_dir_must_be_private() {
+    let root = std::env::temp_dir().join(format!("anytui-ipc-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let dir = root.join("anytui");
+    fs::create_dir_all(&dir).unwrap();
+    let link = root.join("link");
+    symlink(&dir, &link).unwrap();
+
+    fs::set_permissions(&dir, Permissions::from_mode(0o755)).unwrap();
+    let open = ipc::check_private(&dir).unwrap_err();
+    fs::set_permissions(&dir, Permissions::from_mode(0o700)).unwrap();
+    let private = ipc::check_private(&dir);
+    let linked = ipc::check_private(&link).unwrap_err();
+    let missing = ipc::check_private(&root.join("missing")).unwrap_err();
+    let _ = fs::remove_dir_all(&root);
+
+    assert_eq!(open.kind(), ErrorKind::PermissionDenied);
+    assert!(private.is_ok());
+    assert_eq!(linked.kind(), ErrorKind::PermissionDenied);
+    assert_eq!(missing.kind(), ErrorKind::NotFound);
+}
+
+#[test]
+fn test_requests_pass_the_log_popup() {
+    let knob = Knob {
+        theme: Theme::dark(),
+        volume: 40,
+    };
+    let mut harness = TestHarness::new(knob, 60, 12);
+    harness.press("D");
+    assert!(harness.screen().contains("Debug log"));
+
+    // Keys go to the open popup, requests still reach the app
+    let response = harness.request(&Request::new(Op::Action(Action::VolumeUp, None)));
+    assert_eq!(response, json!({"ok": true, "result": null}));
+    assert_eq!(harness.app().volume, 45);
+    assert!(harness.screen().contains("Debug log"));
+
+    harness.request(&Request::new(Op::Action(Action::Quit, None)));
+    assert!(!harness.is_running());
+}
@@ -1,5 +1,5 @@
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-use tuigreat::{Action, KeyHandler};
+use tuigreat::{Action, Edit, KeyHandler};
 
 fn key(code: KeyCode) -> KeyEvent {
     KeyEvent::new(code, KeyModifiers::NONE)
@@ -35,7 +35,11 @@ fn test_arrow_navigation() {
 
 #[test]
 fn test_jump_to_top() {
-    assert_eq!(KeyHandler::parse(key(KeyCode::Char('g'))), Action::Top);
+    // `g` alone only starts the `gg` sequence (see tests/sequence.rs)
+    assert_eq!(
+        KeyHandler::parse(key(KeyCode::Char('g'))),
+        Action::Char('g')
+    );
     assert_eq!(KeyHandler::parse(key(KeyCode::Char('0'))), Action::Top);
     assert_eq!(KeyHandler::parse(key(KeyCode::Home)), Action::Top);
 }
@@ -165,6 +169,44 @@ fn test_input_mode_control() {
         KeyHandler::parse_input_mode(key(KeyCode::Enter)),
         Action::Select
     );
+    assert_eq!(
+        KeyHandler::parse_input_mode(key(KeyCode::Tab)),
+        Action::Char('\t')
+    );
+    assert_eq!(KeyHandler::parse_input_mode(key(KeyCode::Up)), Action::Up);
+}
+
+#[test]
+fn test_input_mode_editing() {
+    assert_eq!(
+        KeyHandler::parse_input_mode(key(KeyCode::Left)),
+        Action::Edit(Edit::Left)
+    );
+    assert_eq!(
+        KeyHandler::parse_input_mode(key_ctrl(KeyCode::Right)),
+        Action::Edit(Edit::WordRight)
+    );
+    assert_eq!(
+        KeyHandler::parse_input_mode(key(KeyCode::Home)),
+        Action::Edit(Edit::Home)
+    );
+    assert_eq!(
+        KeyHandler::parse_input_mode(key_ctrl(KeyCode::Char('w'))),
+        Action::Edit(Edit::DeleteWord)
+    );
+    assert_eq!(
+        KeyHandler::parse_input_mode(key_ctrl(KeyCode::Char('u'))),
+        Action::Edit(Edit::DeleteToStart)
+    );
+    assert_eq!(
+        KeyHandler::parse_input_mode(key_ctrl(KeyCode::Char('v'))),
+        Action::Paste
+    );
+    // Unbound control keys are not typed
+    assert_eq!(
+        KeyHandler::parse_input_mode(key_ctrl(KeyCode::Char('x'))),
+        Action::None
+    );
 }
 
 // ============================================================================
@@ -0,0 +1,207 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use tuigreat::config::{ConfigFile, Value};
+use tuigreat::{Action, KeyChord, Keymap};
+
+fn key(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::NONE)
+}
+
+fn keymap(text: &str, app: &str) -> Keymap {
+    Keymap::from_config(&ConfigFile::parse(text).unwrap(), app)
+}
+
+// ============================================================================
+// Config parsing
+// ============================================================================
+
+#[test]
+fn test_config_sections_and_values() {
+    let file = ConfigFile::parse(
+        r#"
+        # comment
+        top = 1
+
+        [global]
+        quit = "q" # trailing comment
+        down = ["j", "Down"]
+        "search_next" = 'n'
+        enabled = true
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(file.get("", "top"), Some(&Value::Integer(1)));
+    assert_eq!(
+        file.get("global", "quit").and_then(Value::as_str),
+        Some("q")
+    );
+    assert_eq!(
+        file.get("global", "down").and_then(Value::as_str_list),
+        Some(vec!["j", "Down"])
+    );
+    assert_eq!(
+        file.get("global", "search_next").and_then(Value::as_str),
+        Some("n")
+    );
+    assert_eq!(file.get("global", "enabled"), Some(&Value::Bool(true)));
+    assert_eq!(file.get("global", "missing"), None);
+}
+
+#[test]
+fn test_config_multiline_array() {
+    let file = ConfigFile::parse("[a]\nkeys = [\n  \"#\",  # hash\n  \"x\",\n]\n").unwrap();
+    assert_eq!(
+        file.get("a", "keys").and_then(Value::as_str_list),
+        Some(vec!["#", "x"])
+    );
+}
+
+#[test]
+fn test_config_errors_report_line() {
+    let err = ConfigFile::parse("[global]\nquit\n").unwrap_err();
+    assert!(err.starts_with("line 2:"), "{err}");
+    assert!(ConfigFile::parse("[global\n").is_err());
+    assert!(ConfigFile::parse("a = \"open\n").is_err());
+    assert!(ConfigFile::parse("a = [1, 2\n").is_err());
+}
+
+// ============================================================================
+// Key chords
+// ============================================================================
+
+#[test]
+fn test_chord_parse() {
+    assert_eq!(
+        KeyChord::parse("j").unwrap(),
+        KeyChord::plain(KeyCode::Char('j'))
+    );
+    assert_eq!(
+        KeyChord::parse("C-u").unwrap(),
+        KeyChord::new(KeyCode::Char('u'), KeyModifiers::CONTROL)
+    );
+    assert_eq!(
+        KeyChord::parse("A-Enter").unwrap(),
+        KeyChord::new(KeyCode::Enter, KeyModifiers::ALT)
+    );
+    assert_eq!(
+        KeyChord::parse("Space").unwrap(),
+        KeyChord::plain(KeyCode::Char(' '))
+    );
+    assert_eq!(
+        KeyChord::parse("PageDown").unwrap(),
+        KeyChord::plain(KeyCode::PageDown)
+    );
+    assert_eq!(
+        KeyChord::parse("F5").unwrap(),
+        KeyChord::plain(KeyCode::F(5))
+    );
+    assert_eq!(
+        KeyChord::parse("-").unwrap(),
+        KeyChord::plain(KeyCode::Char('-'))
+    );
+    assert!(KeyChord::parse("C-").is_err());
+    assert!(KeyChord::parse("Nope").is_err());
+}
+
+#[test]
+fn test_chord_display_roundtrip() {
+    for s in ["j", "G", "C-u", "A-Enter", "Space", "PageUp", "Esc", "F12"] {
+        assert_eq!(KeyChord::parse(s).unwrap().to_string(), s);
+    }
+}
+
+// ============================================================================
+// Overrides
+// ============================================================================
+
+#[test]
+fn test_defaults_without_config() {
+    let keymap = keymap("", "voltui");
+    assert!(keymap.warnings().is_empty());
+    assert_eq!(keymap.parse(key(KeyCode::Char('j'))), Action::Down);
+    assert_eq!(keymap.parse(key(KeyCode::Char('z'))), Action::Char('z'));
+}
+
+#[test]
+fn test_global_override_replaces_defaults() {
+    let keymap = keymap("[global]\ndown = [\"n\", \"Down\"]\n", "voltui");
+    assert_eq!(keymap.parse(key(KeyCode::Char('n'))), Action::Down);
+    assert_eq!(keymap.parse(key(KeyCode::Down)), Action::Down);
+    // The old default key falls through as a plain character
+    assert_eq!(keymap.parse(key(KeyCode::Char('j'))), Action::Char('j'));
+}
+
+#[test]
+fn test_app_section_overrides_global() {
+    let text = "[global]\nquit = \"x\"\n\n[mustui]\nquit = \"Q\"\n";
+    let music = keymap(text, "mustui");
+    assert_eq!(music.parse(key(KeyCode::Char('Q'))), Action::Quit);
+    assert_eq!(music.parse(key(KeyCode::Char('x'))), Action::Char('x'));
+
+    let vol = keymap(text, "voltui");
+    assert_eq!(vol.parse(key(KeyCode::Char('x'))), Action::Quit);
+    assert_eq!(vol.parse(key(KeyCode::Char('Q'))), Action::Char('Q'));
+}
+
+#[test]
+fn test_swapped_keys_have_no_warnings() {
+    let keymap = keymap("[global]\ndown = \"k\"\nup = \"j\"\n", "voltui");
+    assert!(keymap.warnings().is_empty(), "{:?}", keymap.warnings());
+    assert_eq!(keymap.parse(key(KeyCode::Char('k'))), Action::Down);
+    assert_eq!(keymap.parse(key(KeyCode::Char('j'))), Action::Up);
+}
+
+// ============================================================================
+// Warnings
+// ============================================================================
+
+#[test]
+fn test_unknown_action_and_key_warn() {
+    let keymap = keymap("[global]\nfly = \"x\"\ndown = \"Nope\"\n", "voltui");
+    let warnings = keymap.warnings();
+    assert_eq!(warnings.len(), 2, "{warnings:?}");
+    assert!(warnings[0].contains("unknown action `fly`"));
+    assert!(warnings[1].contains("Nope"));
+}
+
+#[test]
+fn test_conflict_in_section_warns() {
+    let keymap = keymap("[global]\ndown = \"x\"\nup = \"x\"\n", "voltui");
+    assert!(
+        keymap
+            .warnings()
+            .iter()
+            .any(|w| w.contains("`x` is bound to both `down` and `up`"))
+    );
+    // First binding wins
+    assert_eq!(keymap.parse(key(KeyCode::Char('x'))), Action::Down);
+}
+
+#[test]
+fn test_stealing_last_key_warns() {
+    let keymap = keymap("[global]\nsearch = \"y\"\n", "voltui");
+    assert!(keymap.warnings().iter().any(|w| w.contains("`yank`")));
+    assert_eq!(keymap.parse(key(KeyCode::Char('y'))), Action::Search);
+}
+
+// ============================================================================
+// Help labels
+// ============================================================================
+
+#[test]
+fn test_help_follows_bindings() {
+    let keymap = keymap("[global]\ndown = \"n\"\nup = \"e\"\n", "voltui");
+    let help = keymap.help(&[
+        (&[Action::Down, Action::Up], "Navigate"),
+        (&[Action::PageUp, Action::PageDown], "Half page"),
+        (&[Action::Char('R')], "Restart"),
+    ]);
+    assert_eq!(
+        help,
+        vec![
+            ("n/e".to_string(), "Navigate"),
+            ("C-u/C-d".to_string(), "Half page"),
+            ("R".to_string(), "Restart"),
+        ]
+    );
+}
@@ -0,0 +1,171 @@
+use tuigreat::Action;
+use tuigreat::widgets::{MatchMode, SearchDirection, SelectableList};
+
+fn list(n: usize) -> SelectableList<usize> {
+    SelectableList::new((1..=n).collect(), ToString::to_string)
+}
+
+#[test]
+fn test_goto_line() {
+    let mut items = list(10);
+    items.goto_line(4);
+    assert_eq!(items.selected(), Some(&4));
+    // Clamped to the list
+    items.goto_line(50);
+    assert_eq!(items.selected(), Some(&10));
+    items.goto_line(0);
+    assert_eq!(items.selected(), Some(&1));
+}
+
+#[test]
+fn test_goto_line_empty() {
+    let mut items = list(0);
+    items.goto_line(3);
+    assert_eq!(items.selected_index(), None);
+}
+
+fn names() -> SelectableList<&'static str> {
+    SelectableList::new(
+        vec!["Pink Floyd", "Pantera", "Kink", "Phoenix", "Nirvana"],
+        ToString::to_string,
+    )
+}
+
+fn search(list: &mut SelectableList<&'static str>, query: &str) {
+    list.start_search(SearchDirection::Forward);
+    for c in query.chars() {
+        list.edit_search(Action::Char(c));
+    }
+}
+
+#[test]
+fn test_substring_search_jumps() {
+    let mut items = names();
+    search(&mut items, "ink");
+    assert_eq!(items.selected(), Some(&"Pink Floyd"));
+    assert_eq!(items.match_info(), Some((1, 2)));
+    items.next_match();
+    assert_eq!(items.selected(), Some(&"Kink"));
+    // Jumping keeps every item
+    items.last();
+    assert_eq!(items.selected(), Some(&"Nirvana"));
+}
+
+#[test]
+fn test_fuzzy_search() {
+    let mut items = names().with_match_mode(MatchMode::Fuzzy);
+    search(&mut items, "pnx");
+    assert_eq!(items.selected(), Some(&"Phoenix"));
+    assert_eq!(items.match_info(), Some((1, 1)));
+}
+
+#[test]
+fn test_filter_hides_non_matches() {
+    let mut items = names().with_match_mode(MatchMode::Fuzzy);
+    items.set_filter(true);
+    search(&mut items, "pi");
+    assert!(items.is_filtered());
+    // Best match first: "Pink Floyd" has the run, "Phoenix" a gap
+    assert_eq!(items.selected(), Some(&"Pink Floyd"));
+    items.next();
+    assert_eq!(items.selected(), Some(&"Phoenix"));
+    items.next();
+    assert_eq!(items.selected(), Some(&"Pink Floyd"));
+    items.goto_line(9);
+    assert_eq!(items.selected(), Some(&"Phoenix"));
+
+    items.clear_search();
+    assert!(!items.is_filtered());
+    items.last();
+    assert_eq!(items.selected(), Some(&"Nirvana"));
+}
+
+#[test]
+fn test_filter_without_matches() {
+    let mut items = names();
+    items.set_filter(true);
+    search(&mut items, "zz");
+    assert_eq!(items.selected(), None);
+    items.next();
+    assert_eq!(items.selected(), None);
+    items.clear_search();
+    assert_eq!(items.selected(), Some(&"Pink Floyd"));
+}
+
+#[test]
+fn test_tab_toggles_filter() {
+    let mut items = names();
+    search(&mut items, "ink");
+    assert!(!items.is_filtered());
+    items.edit_search(Action::Char('\t'));
+    assert!(items.filter());
+    assert!(items.is_filtered());
+    assert_eq!(items.search_query(), "ink");
+}
+
+#[test]
+fn test_set_items_keeps_filter() {
+    let mut items = names();
+    items.set_filter(true);
+    search(&mut items, "ink");
+    items.next();
+    assert_eq!(items.selected(), Some(&"Kink"));
+    items.set_items(vec!["Kink", "Blink-182", "Abba"]);
+    assert_eq!(items.selected(), Some(&"Kink"));
+    items.next();
+    assert_eq!(items.selected(), Some(&"Blink-182"));
+    items.next();
+    assert_eq!(items.selected(), Some(&"Kink"));
+}
+
+#[test]
+fn test_marks() {
+    let mut items = list(5);
+    assert_eq!(items.marked_or_selected(), vec![&1]);
+    items.toggle_mark();
+    items.goto_line(4);
+    items.toggle_mark();
+    assert_eq!(items.marked(), vec![&1, &4]);
+    assert_eq!(items.marked_or_selected(), vec![&1, &4]);
+    items.toggle_mark();
+    assert_eq!(items.marked_indices(), vec![0]);
+    items.clear_marks();
+    assert_eq!(items.marked_or_selected(), vec![&4]);
+}
+
+#[test]
+fn test_visual_range() {
+    let mut items = list(6);
+    items.goto_line(2);
+    items.toggle_mark();
+    items.goto_line(5);
+    items.toggle_visual();
+    assert!(items.is_visual());
+    items.previous();
+    items.previous();
+    // The live range counts as marked
+    assert_eq!(items.marked(), vec![&2, &3, &4, &5]);
+    items.toggle_visual();
+    assert!(!items.is_visual());
+    items.last();
+    assert_eq!(items.marked(), vec![&2, &3, &4, &5]);
+
+    // Esc leaves visual mode first, then unmarks
+    items.toggle_visual();
+    items.unmark();
+    assert_eq!(items.marked().len(), 4);
+    items.unmark();
+    assert!(items.marked().is_empty());
+}
+
+#[test]
+fn test_marks_follow_items() {
+    let mut items = SelectableList::new(vec![(1, "a"), (2, "b"), (3, "c")], |item| {
+        format!("{} {}", item.1, item.0)
+    })
+    .with_key(|item| item.1.to_string());
+    items.goto_line(2);
+    items.toggle_mark();
+    items.set_items(vec![(0, "new"), (5, "a"), (6, "b")]);
+    assert_eq!(items.marked(), vec![&(6, "b")]);
+}
@@ -0,0 +1,129 @@
+use std::time::{Duration, Instant, SystemTime};
+
+use ratatui::{Terminal, backend::TestBackend};
+use tuigreat::messages::{CAPACITY, Entry};
+use tuigreat::testing::screen_text;
+use tuigreat::widgets::MessagePopup;
+use tuigreat::{Action, Error, MessageLog, StatusLevel, StatusMessage, Theme};
+
+fn texts(log: &MessageLog) -> Vec<&str> {
+    log.entries()
+        .map(|entry| entry.message.text.as_str())
+        .collect()
+}
+
+#[test]
+fn test_push_keeps_history() {
+    let mut log = MessageLog::new();
+    log.push(StatusMessage::info("Scanning"));
+    log.push(StatusMessage::error("Connect failed"));
+    assert_eq!(log.current().unwrap().text, "Connect failed");
+    assert_eq!(texts(&log), ["Scanning", "Connect failed"]);
+
+    // Repeats are counted, not listed twice
+    log.push(StatusMessage::error("Connect failed"));
+    assert_eq!(log.len(), 2);
+    assert_eq!(log.entries().last().unwrap().count, 2);
+
+    // Clearing the status line keeps the history
+    log.clear();
+    assert!(log.current().is_none());
+    log.push("");
+    assert_eq!(log.len(), 2);
+}
+
+#[test]
+fn test_messages_expire_by_level() {
+    let mut log = MessageLog::new();
+    log.push(StatusMessage::success("Connected"));
+    let later = Instant::now() + Duration::from_secs(10);
+    assert!(log.current_at(later).is_none());
+    assert!(log.next_expiry().is_some());
+
+    log.push(StatusMessage::error("Connect failed"));
+    assert_eq!(log.current_at(later).unwrap().text, "Connect failed");
+    assert!(StatusLevel::Error.lifetime() > StatusLevel::Info.lifetime());
+
+    // Hints stay until replaced and are not logged
+    log.hint(StatusMessage::info("Jump to: "));
+    assert_eq!(log.current_at(later).unwrap().text, "Jump to: ");
+    assert!(log.next_expiry().is_none());
+    assert_eq!(log.len(), 2);
+}
+
+#[test]
+fn test_capacity() {
+    let mut log = MessageLog::new();
+    for i in 0..=CAPACITY {
+        log.push(format!("Message {i}"));
+    }
+    assert_eq!(log.len(), CAPACITY);
+    assert_eq!(log.entries().next().unwrap().message.text, "Message 1");
+}
+
+#[test]
+fn test_report_and_last_detail() {
+    let mut log = MessageLog::new();
+    let error = Error::Backend {
+        command: "nmcli con up Home".to_string(),
+        message: "Error: secrets were required".to_string(),
+        stderr: "Error: secrets were required".to_string(),
+    };
+    log.report(Box::new(error)).unwrap();
+    assert_eq!(log.current().unwrap().level, StatusLevel::Error);
+    assert!(log.report("not a backend error".into()).is_err());
+
+    // Still reachable after a newer message replaced it
+    log.push(StatusMessage::info("Cancelled"));
+    let detail = log.last_detail().unwrap();
+    assert_eq!(detail.text, "Error: secrets were required");
+}
+
+#[test]
+fn test_entry_age() {
+    let entry = Entry {
+        message: StatusMessage::info("Ready"),
+        at: SystemTime::UNIX_EPOCH,
+        count: 1,
+    };
+    let at = |secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+    assert_eq!(entry.age(at(42)), "42s");
+    assert_eq!(entry.age(at(180)), "3m");
+    assert_eq!(entry.age(at(7200)), "2h");
+}
+
+#[test]
+fn test_message_popup() {
+    let mut log = MessageLog::new();
+    log.push(StatusMessage::info("Scanning"));
+    log.push(StatusMessage::from(Error::Backend {
+        command: "pactl load-module x".to_string(),
+        message: "Failure: Module initialization failed".to_string(),
+        stderr: "Failure: Module initialization failed".to_string(),
+    }));
+    let mut popup = MessagePopup::new(&log);
+    assert_eq!(
+        popup.selected().unwrap().message.text,
+        "Failure: Module initialization failed"
+    );
+
+    let mut terminal = Terminal::new(TestBackend::new(70, 12)).unwrap();
+    terminal
+        .draw(|frame| popup.render(frame, &Theme::dark()))
+        .unwrap();
+    let screen = screen_text(terminal.backend().buffer());
+    assert!(screen.contains("Messages"));
+    // Newest first
+    assert!(screen.find("Failure").unwrap() < screen.find("Scanning").unwrap());
+
+    // Enter shows the details, Esc goes back to the list
+    assert!(popup.handle_action(Action::Select));
+    terminal
+        .draw(|frame| popup.render(frame, &Theme::dark()))
+        .unwrap();
+    assert!(screen_text(terminal.backend().buffer()).contains("$ pactl load-module x"));
+    assert!(popup.handle_action(Action::Back));
+    assert!(popup.handle_action(Action::Down));
+    assert_eq!(popup.selected().unwrap().message.text, "Scanning");
+    assert!(!popup.handle_action(Action::Messages));
+}
@@ -0,0 +1,111 @@
+use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{Terminal, backend::TestBackend, layout::Rect};
+use tuigreat::Theme;
+use tuigreat::widgets::{SelectableList, Tabs, VolumeGauge};
+
+fn mouse(kind: MouseEventKind, column: u16, row: u16) -> MouseEvent {
+    MouseEvent {
+        kind,
+        column,
+        row,
+        modifiers: KeyModifiers::NONE,
+    }
+}
+
+fn click(column: u16, row: u16) -> MouseEvent {
+    mouse(MouseEventKind::Down(MouseButton::Left), column, row)
+}
+
+/// Render a list of `n` items into a 20x6 area at the top-left corner.
+fn rendered_list(n: usize) -> SelectableList<usize> {
+    let mut list = SelectableList::new((1..=n).collect(), ToString::to_string);
+    let mut terminal = Terminal::new(TestBackend::new(20, 6)).unwrap();
+    terminal
+        .draw(|f| list.render(f, f.area(), "", &Theme::dark(), true))
+        .unwrap();
+    list
+}
+
+// ============================================================================
+// SelectableList
+// ============================================================================
+
+#[test]
+fn test_list_click_selects_row() {
+    let mut list = rendered_list(10);
+    // Row 0 is the border, so row 3 is the third item
+    assert!(list.handle_mouse(&click(5, 3)));
+    assert_eq!(list.selected(), Some(&3));
+    // Clicking the border keeps the selection
+    assert!(list.handle_mouse(&click(5, 0)));
+    assert_eq!(list.selected(), Some(&3));
+    // Outside the list
+    assert!(!list.handle_mouse(&click(5, 10)));
+}
+
+#[test]
+fn test_list_click_below_items() {
+    let mut list = rendered_list(2);
+    list.handle_mouse(&click(5, 4));
+    assert_eq!(list.selected(), Some(&1));
+}
+
+#[test]
+fn test_list_scroll_moves_selection() {
+    let mut list = rendered_list(3);
+    list.handle_mouse(&mouse(MouseEventKind::ScrollDown, 5, 2));
+    list.handle_mouse(&mouse(MouseEventKind::ScrollDown, 5, 2));
+    list.handle_mouse(&mouse(MouseEventKind::ScrollDown, 5, 2));
+    assert_eq!(list.selected(), Some(&3));
+    list.handle_mouse(&mouse(MouseEventKind::ScrollUp, 5, 2));
+    assert_eq!(list.selected(), Some(&2));
+}
+
+#[test]
+fn test_list_item_at_reports_text_column() {
+    let list = rendered_list(10);
+    // Border, `>` symbol and marker come before the text
+    assert_eq!(list.item_at(4, 1), Some((0, 0)));
+    assert_eq!(list.item_at(10, 2), Some((1, 6)));
+    assert_eq!(list.item_at(2, 1), None);
+    assert_eq!(list.item_at(19, 1), None);
+}
+
+// ============================================================================
+// Tabs
+// ============================================================================
+
+#[test]
+fn test_tab_click_switches_tab() {
+    let mut tabs = Tabs::new(vec!["One".to_string(), "Two".to_string()]);
+    let mut terminal = Terminal::new(TestBackend::new(40, 1)).unwrap();
+    terminal
+        .draw(|f| tabs.render(f, f.area(), &Theme::dark()))
+        .unwrap();
+
+    // " ┌─ One ─┬─ Two ─┐"
+    assert_eq!(tabs.tab_at(4, 0), Some(0));
+    assert_eq!(tabs.tab_at(12, 0), Some(1));
+    assert_eq!(tabs.tab_at(9, 0), None);
+    assert_eq!(tabs.tab_at(30, 0), None);
+
+    assert!(tabs.handle_mouse(&click(12, 0)));
+    assert_eq!(tabs.selected(), 1);
+    assert!(!tabs.handle_mouse(&click(12, 3)));
+}
+
+// ============================================================================
+// VolumeGauge
+// ============================================================================
+
+#[test]
+fn test_gauge_scroll_delta() {
+    let area = Rect::new(0, 5, 20, 3);
+    let up = mouse(MouseEventKind::ScrollUp, 3, 6);
+    let down = mouse(MouseEventKind::ScrollDown, 3, 6);
+    assert_eq!(VolumeGauge::scroll_delta(area, &up), Some(1));
+    assert_eq!(VolumeGauge::scroll_delta(area, &down), Some(-1));
+    assert_eq!(VolumeGauge::scroll_delta(area, &click(3, 6)), None);
+    let outside = mouse(MouseEventKind::ScrollUp, 3, 1);
+    assert_eq!(VolumeGauge::scroll_delta(area, &outside), None);
+}
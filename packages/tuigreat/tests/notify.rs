@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use tuigreat::Notifier;
+use tuigreat::notify::{self, Backend, Notification, Urgency};
+use tuigreat::runner::{self, FakeRunner, Reply};
+
+#[test]
+fn test_osc_sequences() {
+    let notification = Notification::new("Buds connected", "Bluetooth");
+    assert_eq!(
+        notification.osc(Backend::Osc9).unwrap(),
+        "\x1b]9;Buds connected: Bluetooth\x07"
+    );
+    assert_eq!(
+        Notification::new("Buds connected", "")
+            .osc(Backend::Osc9)
+            .unwrap(),
+        "\x1b]9;Buds connected\x07"
+    );
+    assert_eq!(
+        notification.osc(Backend::Osc777).unwrap(),
+        "\x1b]777;notify;Buds connected;Bluetooth\x07"
+    );
+    assert!(notification.osc(Backend::DBus).is_none());
+
+    // A BEL or `;` in the text must not end the sequence early
+    let tricky = Notification::new("a;b\x07c", "d\x1be");
+    assert_eq!(
+        tricky.osc(Backend::Osc777).unwrap(),
+        "\x1b]777;notify;a,b c;d e\x07"
+    );
+}
+
+#[test]
+fn test_backends() {
+    assert_eq!(Backend::from_name("DBus"), Some(Backend::DBus));
+    assert_eq!(Backend::from_name("off"), Some(Backend::Off));
+    assert_eq!(Backend::from_name("growl"), None);
+    assert_eq!(Backend::osc_for_terminal("foot", ""), Backend::Osc777);
+    assert_eq!(
+        Backend::osc_for_terminal("xterm-256color", "ghostty"),
+        Backend::Osc777
+    );
+    assert_eq!(Backend::osc_for_terminal("xterm-kitty", ""), Backend::Osc9);
+}
+
+#[test]
+fn test_send_over_dbus() {
+    let mut call = vec!["gdbus"];
+    call.extend(["*"; 15]);
+    let fake = Arc::new(FakeRunner::new().with(Reply::new(&call).stdout("(uint32 7,)\n")));
+    runner::set(fake.clone());
+    let notifier = Notifier::new("blutui").with_backend(Backend::DBus);
+    let notification =
+        Notification::new("Buds \"Pro\"", "Bluetooth").with_urgency(Urgency::Critical);
+    notifier.send(&notification).unwrap();
+
+    assert!(fake.ran(&["gdbus", "call", "--session"]));
+    let args = &fake.calls()[0];
+    assert!(args.contains(&"\"blutui\"".to_string()));
+    assert!(args.contains(&"\"Buds \\\"Pro\\\"\"".to_string()));
+    assert!(args.contains(&"{'urgency': <byte 2>}".to_string()));
+
+    // Nothing runs with notifications off
+    Notifier::new("blutui")
+        .with_backend(Backend::Off)
+        .send(&notification)
+        .unwrap();
+    assert_eq!(fake.calls().len(), 1);
+}
+
+#[test]
+fn test_focus() {
+    notify::set_focused(true);
+    assert!(notify::is_focused());
+    notify::set_focused(false);
+    assert!(!notify::is_focused());
+}
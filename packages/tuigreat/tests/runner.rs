@@ -0,0 +1,141 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tuigreat::Tasks;
+use tuigreat::runner::{
+    self, Command, CommandRunner, FakeRunner, RecordingRunner, Reply, parse_fixture,
+};
+
+fn stdout(command: &mut Command) -> String {
+    String::from_utf8(command.output().unwrap().stdout).unwrap()
+}
+
+#[test]
+fn test_fake_matches_argv() {
+    let fake = FakeRunner::new()
+        .with(Reply::new(&["iwctl", "station", "*", "scan"]))
+        .with(Reply::new(&["iwctl", "device", "list"]).stdout("wlan0\n"));
+    runner::set(Arc::new(fake));
+
+    assert_eq!(
+        stdout(Command::new("iwctl").args(["device", "list"])),
+        "wlan0\n"
+    );
+    assert!(
+        Command::new("iwctl")
+            .args(["station", "wlan0", "scan"])
+            .output()
+            .is_ok()
+    );
+    // Argument count must match too
+    let missing = Command::new("iwctl").args(["device"]).output().unwrap_err();
+    assert_eq!(missing.to_string(), "no recorded reply for `iwctl device`");
+}
+
+#[test]
+fn test_fake_status_and_stderr() {
+    let fake = FakeRunner::new().with(
+        Reply::new(&["nmcli", "con", "up", "Home"])
+            .stderr("Error: secrets were required\n")
+            .status(4),
+    );
+    runner::set(Arc::new(fake));
+    let output = Command::new("nmcli")
+        .args(["con", "up", "Home"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(4));
+    assert!(!output.status.success());
+    assert_eq!(output.stderr, b"Error: secrets were required\n");
+}
+
+#[test]
+fn test_latest_reply_wins_and_calls_are_recorded() {
+    let fake =
+        Arc::new(FakeRunner::new().with(Reply::new(&["pactl", "get-default-sink"]).stdout("a")));
+    runner::set(fake.clone());
+    assert_eq!(stdout(Command::new("pactl").arg("get-default-sink")), "a");
+    fake.add(Reply::new(&["pactl", "get-default-sink"]).stdout("b"));
+    assert_eq!(stdout(Command::new("pactl").arg("get-default-sink")), "b");
+
+    assert_eq!(fake.calls().len(), 2);
+    assert!(fake.ran(&["pactl"]));
+    assert!(!fake.ran(&["pactl", "set-default-sink"]));
+}
+
+#[test]
+fn test_parse_fixture() {
+    let replies = parse_fixture(
+        "# comment\n\
+         $ bluetoothctl info 'AA:BB' 'it''s here'\n\
+         > Name: Buds\n\
+         >\n\
+         ! warning\n\
+         ? 2\n\
+         $ true\n",
+    )
+    .unwrap();
+    assert_eq!(
+        replies,
+        [
+            Reply::new(&["bluetoothctl", "info", "AA:BB", "it's here"])
+                .stdout("Name: Buds\n\n")
+                .stderr("warning\n")
+                .status(2),
+            Reply::new(&["true"]),
+        ]
+    );
+    assert!(parse_fixture("> stray output\n").is_err());
+    assert!(parse_fixture("$ a\n? x\n").is_err());
+    assert!(parse_fixture("$ 'open\n").is_err());
+}
+
+#[test]
+fn test_recording_replays() {
+    let path = std::env::temp_dir().join(format!("anytui-record-{}.txt", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let inner = FakeRunner::new()
+        .with(Reply::new(&["echo", "two words"]).stdout("two words\n"))
+        .with(Reply::new(&["false"]).stderr("nope\n").status(1));
+    let recorder = RecordingRunner::new(inner, &path);
+    recorder
+        .output("echo", &["two words".to_string()], None)
+        .unwrap();
+    recorder.output("false", &[], None).unwrap();
+
+    let replay = FakeRunner::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let output = replay
+        .output("echo", &["two words".to_string()], None)
+        .unwrap();
+    assert_eq!(output.stdout, b"two words\n");
+    let output = replay.output("false", &[], None).unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(output.stderr, b"nope\n");
+}
+
+#[test]
+fn test_tasks_inherit_runner() {
+    let fake = Arc::new(FakeRunner::new().with(Reply::new(&["pactl", "info"]).stdout("ok")));
+    runner::set(fake.clone());
+    let mut tasks: Tasks<String> = Tasks::new();
+    tasks.spawn("Checking", |_| stdout(Command::new("pactl").arg("info")));
+    let start = std::time::Instant::now();
+    let result = loop {
+        if let Some(result) = tasks.finished().pop() {
+            break result;
+        }
+        assert!(start.elapsed() < Duration::from_secs(5));
+        std::thread::sleep(Duration::from_millis(5));
+    };
+    assert_eq!(result, "ok");
+    assert!(fake.ran(&["pactl", "info"]));
+}
+
+#[test]
+fn test_reset_runs_for_real() {
+    runner::set(Arc::new(FakeRunner::new()));
+    assert!(Command::new("true").output().is_err());
+    runner::reset();
+    assert!(Command::new("true").output().unwrap().status.success());
+}
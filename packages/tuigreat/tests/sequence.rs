@@ -0,0 +1,210 @@
+use std::time::{Duration, Instant};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use tuigreat::config::ConfigFile;
+use tuigreat::{Action, CountedAction, KeyChord, KeySequencer, Keymap, SequenceMatch};
+
+fn key(c: char) -> KeyEvent {
+    KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE)
+}
+
+/// Feed a string of plain characters, collecting every action produced.
+fn feed(keys: &mut KeySequencer, keymap: &Keymap, input: &str) -> Vec<CountedAction> {
+    let now = Instant::now();
+    input
+        .chars()
+        .flat_map(|c| keys.feed(keymap, key(c), now, true))
+        .collect()
+}
+
+fn plain(action: Action) -> CountedAction {
+    CountedAction::new(action)
+}
+
+fn counted(action: Action, count: usize) -> CountedAction {
+    CountedAction {
+        action,
+        count: Some(count),
+    }
+}
+
+// ============================================================================
+// Sequences
+// ============================================================================
+
+#[test]
+fn test_gg_goes_to_top() {
+    let mut keys = KeySequencer::new();
+    let keymap = Keymap::default();
+    assert!(feed(&mut keys, &keymap, "g").is_empty());
+    assert_eq!(keys.pending(), "g");
+    assert_eq!(feed(&mut keys, &keymap, "g"), vec![plain(Action::Top)]);
+    assert!(!keys.is_pending());
+}
+
+#[test]
+fn test_broken_sequence_restarts_with_new_key() {
+    let mut keys = KeySequencer::new();
+    let keymap = Keymap::default();
+    assert_eq!(feed(&mut keys, &keymap, "gj"), vec![plain(Action::Down)]);
+}
+
+#[test]
+fn test_escape_cancels_pending() {
+    let mut keys = KeySequencer::new();
+    let keymap = Keymap::default();
+    let now = Instant::now();
+    feed(&mut keys, &keymap, "5g");
+    let esc = KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE);
+    assert!(keys.feed(&keymap, esc, now, true).is_empty());
+    assert!(!keys.is_pending());
+    // With nothing pending Esc is Back again
+    assert_eq!(
+        keys.feed(&keymap, esc, now, true),
+        vec![plain(Action::Back)]
+    );
+}
+
+#[test]
+fn test_lookup_reports_prefixes() {
+    let keymap = Keymap::default();
+    let g = KeyChord::plain(KeyCode::Char('g'));
+    assert_eq!(keymap.lookup(&[g]), SequenceMatch::Pending);
+    assert_eq!(keymap.lookup(&[g, g]), SequenceMatch::Complete(Action::Top));
+    assert_eq!(keymap.lookup(&[g, g, g]), SequenceMatch::None);
+}
+
+// ============================================================================
+// Timeouts
+// ============================================================================
+
+#[test]
+fn test_ambiguous_sequence_fires_on_timeout() {
+    let file = ConfigFile::parse("timeout = 200\n[global]\nrefresh = \"g\"\n").unwrap();
+    let keymap = Keymap::from_config(&file, "voltui");
+    assert_eq!(keymap.timeout(), Duration::from_millis(200));
+
+    let mut keys = KeySequencer::new();
+    let start = Instant::now();
+    assert!(keys.feed(&keymap, key('g'), start, true).is_empty());
+    assert_eq!(
+        keys.poll_timeout(&keymap, start + Duration::from_millis(100)),
+        None
+    );
+    assert_eq!(
+        keys.poll_timeout(&keymap, start + Duration::from_millis(250)),
+        Some(plain(Action::Refresh))
+    );
+    assert!(!keys.is_pending());
+
+    // A second g before the timeout still completes gg
+    keys.feed(&keymap, key('g'), start, true);
+    assert_eq!(
+        keys.feed(&keymap, key('g'), start, true),
+        vec![plain(Action::Top)]
+    );
+}
+
+#[test]
+fn test_ambiguous_sequence_fires_before_other_key() {
+    let file = ConfigFile::parse("[global]\nrefresh = \"g\"\n").unwrap();
+    let keymap = Keymap::from_config(&file, "voltui");
+    let mut keys = KeySequencer::new();
+    assert_eq!(
+        feed(&mut keys, &keymap, "gj"),
+        vec![plain(Action::Refresh), plain(Action::Down)]
+    );
+}
+
+#[test]
+fn test_deadline_only_while_sequence_pending() {
+    let mut keys = KeySequencer::new();
+    let keymap = Keymap::default();
+    let start = Instant::now();
+    assert_eq!(keys.deadline(&keymap), None);
+    keys.feed(&keymap, key('5'), start, true);
+    // A count alone waits indefinitely
+    assert_eq!(keys.deadline(&keymap), None);
+    keys.feed(&keymap, key('g'), start, true);
+    assert_eq!(keys.deadline(&keymap), Some(start + keymap.timeout()));
+}
+
+#[test]
+fn test_unbound_prefix_is_dropped_on_timeout() {
+    let mut keys = KeySequencer::new();
+    let keymap = Keymap::default();
+    let start = Instant::now();
+    keys.feed(&keymap, key('g'), start, true);
+    assert_eq!(
+        keys.poll_timeout(&keymap, start + Duration::from_secs(5)),
+        None
+    );
+    assert!(!keys.is_pending());
+}
+
+// ============================================================================
+// Counts
+// ============================================================================
+
+#[test]
+fn test_count_prefix() {
+    let mut keys = KeySequencer::new();
+    let keymap = Keymap::default();
+    assert_eq!(
+        feed(&mut keys, &keymap, "5j"),
+        vec![counted(Action::Down, 5)]
+    );
+    assert_eq!(
+        feed(&mut keys, &keymap, "3+"),
+        vec![counted(Action::VolumeUp, 3)]
+    );
+    assert_eq!(
+        feed(&mut keys, &keymap, "10G"),
+        vec![counted(Action::Bottom, 10)]
+    );
+    assert_eq!(
+        feed(&mut keys, &keymap, "12gg"),
+        vec![counted(Action::Top, 12)]
+    );
+}
+
+#[test]
+fn test_zero_is_top_unless_counting() {
+    let mut keys = KeySequencer::new();
+    let keymap = Keymap::default();
+    assert_eq!(feed(&mut keys, &keymap, "0"), vec![plain(Action::Top)]);
+    feed(&mut keys, &keymap, "20");
+    assert_eq!(keys.pending(), "20");
+}
+
+#[test]
+fn test_counts_disabled_pass_digits() {
+    let mut keys = KeySequencer::new();
+    let keymap = Keymap::default();
+    let actions = keys.feed(&keymap, key('5'), Instant::now(), false);
+    assert_eq!(actions, vec![plain(Action::Char('5'))]);
+}
+
+// ============================================================================
+// Sequence syntax
+// ============================================================================
+
+#[test]
+fn test_parse_sequence() {
+    let g = KeyChord::plain(KeyCode::Char('g'));
+    assert_eq!(KeyChord::parse_sequence("gg").unwrap(), vec![g, g]);
+    assert_eq!(
+        KeyChord::parse_sequence("C-w j").unwrap(),
+        vec![
+            KeyChord::new(KeyCode::Char('w'), KeyModifiers::CONTROL),
+            KeyChord::plain(KeyCode::Char('j')),
+        ]
+    );
+    assert_eq!(
+        KeyChord::parse_sequence("Enter").unwrap(),
+        vec![KeyChord::plain(KeyCode::Enter)]
+    );
+    assert!(KeyChord::parse_sequence("Entr").is_err());
+    assert!(KeyChord::parse_sequence("").is_err());
+    assert_eq!(KeyChord::format_sequence(&[g, g]), "gg");
+}
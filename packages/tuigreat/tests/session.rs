@@ -0,0 +1,16 @@
+use serde_json::json;
+use tuigreat::session;
+
+#[test]
+fn test_versions() {
+    let state = json!({"tab": 2});
+    let saved = session::wrap(&state, 3);
+    assert_eq!(saved, json!({"version": 3, "state": {"tab": 2}}));
+    assert_eq!(session::unwrap(&saved, 3), Some(&state));
+
+    // A session from another version, or none at all, is not restored
+    assert_eq!(session::unwrap(&saved, 4), None);
+    assert_eq!(session::unwrap(&json!({"tab": 2}), 1), None);
+    assert_eq!(session::unwrap(&json!({"version": 1}), 1), None);
+    assert_eq!(session::unwrap(&json!("garbage"), 1), None);
+}
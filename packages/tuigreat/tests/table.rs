@@ -0,0 +1,184 @@
+use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{Terminal, backend::TestBackend, layout::Constraint};
+use tuigreat::widgets::{Column, SearchDirection, SelectableTable, SortOrder};
+use tuigreat::{Action, Theme};
+
+#[derive(Debug, PartialEq)]
+struct Row {
+    name: &'static str,
+    volume: u8,
+}
+
+const fn row(name: &'static str, volume: u8) -> Row {
+    Row { name, volume }
+}
+
+fn table(rows: Vec<Row>) -> SelectableTable<Row> {
+    SelectableTable::new(
+        rows,
+        |r| r.name.to_string(),
+        vec![
+            Column::new("Name", Constraint::Length(8), |r: &Row| r.name.to_string()),
+            Column::new("Vol", Constraint::Length(5), |r: &Row| {
+                format!("{}%", r.volume)
+            })
+            .with_sort(|a, b| a.volume.cmp(&b.volume)),
+        ],
+    )
+}
+
+fn rows() -> Vec<Row> {
+    vec![row("bass", 80), row("Alto", 5), row("cello", 40)]
+}
+
+fn names(table: &SelectableTable<Row>) -> Vec<&str> {
+    table.items().iter().map(|r| r.name).collect()
+}
+
+fn render(table: &mut SelectableTable<Row>) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(20, 6)).unwrap();
+    terminal
+        .draw(|f| table.render(f, f.area(), "", &Theme::dark(), true))
+        .unwrap();
+    let buffer = terminal.backend().buffer();
+    (0..buffer.area.height)
+        .map(|y| {
+            (0..buffer.area.width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn click(column: u16, row: u16) -> MouseEvent {
+    MouseEvent {
+        kind: MouseEventKind::Down(MouseButton::Left),
+        column,
+        row,
+        modifiers: KeyModifiers::NONE,
+    }
+}
+
+#[test]
+fn test_sort_by_column() {
+    let mut table = table(rows());
+    table.sort_by(0);
+    assert_eq!(table.sort(), Some((0, SortOrder::Ascending)));
+    // Text sorts ignore case
+    assert_eq!(names(&table), ["Alto", "bass", "cello"]);
+    table.sort_by(0);
+    assert_eq!(names(&table), ["cello", "bass", "Alto"]);
+    // Numeric comparison, not "40%" < "5%"
+    table.sort_by(1);
+    assert_eq!(names(&table), ["Alto", "cello", "bass"]);
+    // Out of range leaves the sort alone
+    table.sort_by(5);
+    assert_eq!(table.sort(), Some((1, SortOrder::Ascending)));
+}
+
+#[test]
+fn test_cycle_sort_restores_order() {
+    let mut table = table(rows());
+    let mut seen = Vec::new();
+    for _ in 0..5 {
+        table.cycle_sort();
+        seen.push(table.sort());
+    }
+    assert_eq!(
+        seen,
+        [
+            Some((0, SortOrder::Ascending)),
+            Some((0, SortOrder::Descending)),
+            Some((1, SortOrder::Ascending)),
+            Some((1, SortOrder::Descending)),
+            None,
+        ]
+    );
+    assert_eq!(names(&table), ["bass", "Alto", "cello"]);
+}
+
+#[test]
+fn test_sort_keeps_selection_and_marks() {
+    let mut table = table(rows());
+    table.select(2);
+    table.toggle_mark();
+    table.sort_by(0);
+    assert_eq!(table.selected().unwrap().name, "cello");
+    assert_eq!(table.marked(), [&row("cello", 40)]);
+}
+
+#[test]
+fn test_set_items_keeps_sort() {
+    let mut table = table(rows()).with_sort(1, SortOrder::Descending);
+    assert_eq!(names(&table), ["bass", "cello", "Alto"]);
+    table.select(1);
+    // cello gets louder and moves up, the selection goes with it
+    table.set_items(vec![row("bass", 80), row("Alto", 5), row("cello", 90)]);
+    assert_eq!(names(&table), ["cello", "bass", "Alto"]);
+    assert_eq!(table.selected().unwrap().name, "cello");
+}
+
+#[test]
+fn test_search_matches_display_text() {
+    let mut table = table(rows()).with_sort(0, SortOrder::Ascending);
+    table.start_search(SearchDirection::Forward);
+    table.edit_search(Action::Char('c'));
+    assert_eq!(table.selected().unwrap().name, "cello");
+    assert_eq!(table.match_info(), Some((1, 1)));
+    // Volume text is not searched
+    table.clear_search();
+    table.start_search(SearchDirection::Forward);
+    table.edit_search(Action::Char('%'));
+    assert_eq!(table.match_info(), None);
+}
+
+#[test]
+fn test_render_header_and_columns() {
+    let mut table =
+        table(vec![row("Ääkkönen", 100), row("b", 7)]).with_sort(1, SortOrder::Ascending);
+    let screen = render(&mut table);
+    let lines: Vec<&str> = screen.lines().collect();
+    assert_eq!(lines[1], "│   Name     Vol ▲ │");
+    assert_eq!(lines[2], "│   b        7%    │");
+    // The selection stays on the first item as it moves down
+    assert_eq!(lines[3], "│>  Ääkkönen 100%  │");
+}
+
+#[test]
+fn test_mouse_cells_and_header() {
+    let mut table = table(rows());
+    render(&mut table);
+    // Row 1 is the header, items start on row 2
+    assert_eq!(table.cell_at(4, 2), Some((0, 0)));
+    assert_eq!(table.cell_at(13, 3), Some((1, 1)));
+    assert_eq!(table.cell_at(2, 3), None);
+    assert!(table.handle_mouse(&click(5, 3)));
+    assert_eq!(table.selected().unwrap().name, "Alto");
+    // Clicking a title sorts by that column
+    table.handle_mouse(&click(13, 1));
+    assert_eq!(table.sort(), Some((1, SortOrder::Ascending)));
+    assert_eq!(names(&table), ["Alto", "cello", "bass"]);
+}
+
+#[test]
+fn test_session_keeps_sort_and_selection() {
+    let mut t = table(rows());
+    t.sort_by(1);
+    t.sort_by(1);
+    t.last();
+    let saved = t.save_session();
+    assert_eq!(saved["sort"], serde_json::json!([1, "descending"]));
+
+    let mut restored = table(rows());
+    restored.restore_session(&saved);
+    assert_eq!(restored.sort(), Some((1, SortOrder::Descending)));
+    assert_eq!(names(&restored), ["bass", "cello", "Alto"]);
+    assert_eq!(restored.selected().map(|r| r.name), Some("Alto"));
+
+    // Rows that are gone and unknown sorts are skipped
+    let mut other = table(vec![row("tuba", 10)]);
+    other.restore_session(&serde_json::json!({"selected": "Alto", "sort": [7, "sideways"]}));
+    assert_eq!(other.sort(), None);
+    assert_eq!(other.selected().map(|r| r.name), Some("tuba"));
+}
@@ -0,0 +1,86 @@
+use std::sync::{Arc, mpsc};
+use std::time::{Duration, Instant};
+
+use tuigreat::Tasks;
+use tuigreat::runner::{self, Command, FakeRunner, Reply};
+
+/// Collect results until nothing is running, failing after a few seconds.
+fn wait<T: Send + 'static>(tasks: &mut Tasks<T>) -> Vec<T> {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut results = Vec::new();
+    while tasks.is_busy() {
+        assert!(Instant::now() < deadline, "task did not finish");
+        results.extend(tasks.finished());
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    results
+}
+
+#[test]
+fn test_result_is_returned() {
+    let mut tasks = Tasks::new();
+    assert!(!tasks.is_busy());
+    assert_eq!(tasks.indicator(), None);
+    tasks.spawn(" Adding...", |_| 1 + 2);
+    assert!(tasks.is_busy());
+    assert_eq!(wait(&mut tasks), vec![3]);
+    assert_eq!(tasks.next_tick(), None);
+}
+
+#[test]
+fn test_indicator_shows_progress() {
+    let mut tasks = Tasks::new();
+    let (step_tx, step_rx) = mpsc::channel();
+    let (done_tx, done_rx) = mpsc::channel::<()>();
+    tasks.spawn(" Restarting...", move |progress| {
+        progress.set(" Starting services...");
+        step_tx.send(()).unwrap();
+        done_rx.recv().unwrap();
+    });
+
+    step_rx.recv().unwrap();
+    let indicator = tasks.indicator().unwrap();
+    assert!(indicator.ends_with(" Starting services..."), "{indicator}");
+    assert!(tasks.next_tick().is_some());
+
+    done_tx.send(()).unwrap();
+    wait(&mut tasks);
+}
+
+#[test]
+fn test_indicator_counts_other_tasks() {
+    let mut tasks = Tasks::new();
+    let (done_tx, done_rx) = mpsc::channel::<()>();
+    tasks.spawn(" First", |_| ());
+    tasks.spawn(" Second", move |_| {
+        done_rx.recv().unwrap();
+    });
+    let indicator = tasks.indicator().unwrap();
+    assert!(indicator.contains("Second"), "{indicator}");
+    drop(done_tx);
+    wait(&mut tasks);
+}
+
+#[test]
+fn test_panicking_task_finishes_without_result() {
+    let mut tasks: Tasks<u32> = Tasks::new();
+    tasks.spawn(" Failing", |_| panic!("backend exploded"));
+    assert!(wait(&mut tasks).is_empty());
+}
+
+#[test]
+fn test_spawn_command() {
+    // The command goes to the runner of the thread that spawned it
+    let fake = Arc::new(FakeRunner::new().with(Reply::new(&["echo", "*"]).stdout("connected\n")));
+    runner::set(fake.clone());
+    let mut tasks = Tasks::new();
+    let mut command = Command::new("echo");
+    command.arg("connected");
+    tasks.spawn_command(" Running", command, |output| {
+        String::from_utf8_lossy(&output.unwrap().stdout)
+            .trim()
+            .to_string()
+    });
+    assert_eq!(wait(&mut tasks), vec!["connected".to_string()]);
+    assert!(fake.ran(&["echo", "connected"]));
+}
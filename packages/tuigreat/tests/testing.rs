@@ -0,0 +1,165 @@
+use ratatui::{
+    Frame,
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    widgets::Paragraph,
+};
+use tuigreat::testing::{TestHarness, screen_text};
+use tuigreat::widgets::{SearchDirection, SearchPopup, SelectableList};
+use tuigreat::{Action, App, AppResult, Theme};
+
+/// A list with a status line, searchable with `/`.
+struct Fruits {
+    theme: Theme,
+    list: SelectableList<&'static str>,
+    searching: bool,
+    status: String,
+}
+
+impl Fruits {
+    fn new() -> Self {
+        Self {
+            theme: Theme::dark(),
+            list: SelectableList::new(
+                vec!["apple", "banana", "cherry", "date", "elderberry", "fig"],
+                ToString::to_string,
+            ),
+            searching: false,
+            status: String::new(),
+        }
+    }
+}
+
+impl App for Fruits {
+    fn title(&self) -> &str {
+        "fruits"
+    }
+
+    fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    fn input_mode(&self) -> bool {
+        self.searching
+    }
+
+    fn mouse_capture(&self) -> bool {
+        true
+    }
+
+    fn handle_mouse(&mut self, event: crossterm::event::MouseEvent) -> AppResult<bool> {
+        self.list.handle_mouse(&event);
+        Ok(true)
+    }
+
+    fn handle_action(&mut self, action: Action) -> AppResult<bool> {
+        if self.searching {
+            match action {
+                Action::Select | Action::Back => self.searching = false,
+                action => {
+                    self.list.edit_search(action);
+                }
+            }
+            return Ok(true);
+        }
+        match action {
+            Action::Quit => return Ok(false),
+            Action::Down => self.list.next(),
+            Action::Up => self.list.previous(),
+            Action::Top => self.list.first(),
+            Action::Bottom => self.list.last(),
+            Action::Search => {
+                self.list.start_search(SearchDirection::Forward);
+                self.searching = true;
+            }
+            Action::Select => {
+                self.status = format!(" Picked {}", self.list.selected().unwrap_or(&"nothing"));
+            }
+            _ => {}
+        }
+        Ok(true)
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let [list, status] =
+            Layout::vertical([Constraint::Min(3), Constraint::Length(1)]).areas(frame.area());
+        self.list.render(frame, list, " Fruits ", &self.theme, true);
+        frame.render_widget(Paragraph::new(self.status.as_str()), status);
+        if self.searching {
+            SearchPopup::render(frame, "Search", self.list.search_state(), &self.theme);
+        }
+    }
+}
+
+fn harness() -> TestHarness<Fruits> {
+    TestHarness::new(Fruits::new(), 36, 9)
+}
+
+#[test]
+fn test_first_frame() {
+    harness().assert_snapshot("fruits_start");
+}
+
+#[test]
+fn test_keys_and_counts() {
+    let mut harness = harness();
+    harness.press("j j");
+    assert_eq!(harness.app().list.selected(), Some(&"cherry"));
+    harness.press("G").press("gg").press("3j");
+    assert_eq!(harness.app().list.selected(), Some(&"date"));
+    harness.press("Enter");
+    assert!(harness.screen().contains("Picked date"));
+}
+
+#[test]
+fn test_actions() {
+    let mut harness = harness();
+    harness
+        .actions(&[Action::Down, Action::Down])
+        .action_count(Action::Down, 2);
+    assert_eq!(harness.app().list.selected(), Some(&"elderberry"));
+    harness.action(Action::Select);
+    harness.assert_snapshot("fruits_picked");
+}
+
+#[test]
+fn test_input_mode_types_text() {
+    let mut harness = harness();
+    harness.press("/").type_text("rr");
+    harness.assert_snapshot("fruits_search");
+    harness.press("Enter");
+    assert!(!harness.app().searching);
+    assert_eq!(harness.app().list.selected(), Some(&"cherry"));
+}
+
+#[test]
+fn test_click() {
+    let mut harness = harness();
+    // Row 0 is the border
+    harness.click(4, 2);
+    assert_eq!(harness.app().list.selected(), Some(&"banana"));
+}
+
+#[test]
+fn test_quit_stops_input() {
+    let mut harness = harness();
+    harness.press("q");
+    assert!(!harness.is_running());
+    harness.press("j");
+    assert_eq!(harness.app().list.selected(), Some(&"apple"));
+}
+
+#[test]
+fn test_resize() {
+    let mut harness = harness();
+    harness.resize(16, 5);
+    assert_eq!(harness.screen().lines().count(), 5);
+    assert!(harness.screen().lines().all(|l| l.chars().count() <= 16));
+}
+
+#[test]
+fn test_screen_text_wide_chars() {
+    let mut buffer = Buffer::empty(Rect::new(0, 0, 6, 2));
+    buffer.set_string(0, 0, "日本 a", ratatui::style::Style::default());
+    assert_eq!(screen_text(&buffer), "日本 a\n\n");
+}
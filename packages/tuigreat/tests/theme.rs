@@ -0,0 +1,134 @@
+use ratatui::style::Color;
+use tuigreat::config::ConfigFile;
+use tuigreat::theme::PRESETS;
+use tuigreat::{ColorDepth, Theme};
+
+fn theme(text: &str) -> (Theme, Vec<String>) {
+    Theme::from_config(&ConfigFile::parse(text).unwrap())
+}
+
+// ============================================================================
+// Presets
+// ============================================================================
+
+#[test]
+fn test_all_presets_exist() {
+    for name in PRESETS {
+        assert!(Theme::preset(name).is_some(), "{name}");
+    }
+    assert!(Theme::preset("nope").is_none());
+    assert_ne!(Theme::dark(), Theme::light());
+}
+
+#[test]
+fn test_preset_from_file() {
+    let (theme, warnings) = theme("preset = \"gruvbox\"\ncolor_depth = \"truecolor\"\n");
+    assert!(warnings.is_empty());
+    assert_eq!(theme, Theme::preset("gruvbox").unwrap());
+}
+
+#[test]
+fn test_empty_file_is_dark() {
+    let (theme, _) = theme("color_depth = \"truecolor\"\n");
+    assert_eq!(theme, Theme::dark());
+}
+
+// ============================================================================
+// Colour overrides
+// ============================================================================
+
+#[test]
+fn test_color_overrides() {
+    let (theme, warnings) = theme(
+        "preset = \"light\"\ncolor_depth = \"truecolor\"\n\n[colors]\naccent = \"#fabd2f\"\nerror = \"red\"\nmuted = \"8\"\n",
+    );
+    assert!(warnings.is_empty(), "{warnings:?}");
+    assert_eq!(theme.accent, Color::Rgb(250, 189, 47));
+    assert_eq!(theme.error, Color::Red);
+    assert_eq!(theme.muted, Color::Indexed(8));
+    assert_eq!(theme.fg, Theme::light().fg);
+}
+
+#[test]
+fn test_invalid_entries_warn() {
+    let (theme, warnings) = theme(
+        "preset = \"dracula\"\ncolor_depth = \"truecolor\"\n\n[colors]\nsparkle = \"red\"\nfg = \"not-a-colour\"\n",
+    );
+    assert_eq!(theme, Theme::dark());
+    assert_eq!(warnings.len(), 3, "{warnings:?}");
+    assert!(warnings[0].contains("unknown preset `dracula`"));
+    assert!(warnings[1].contains("unknown colour `sparkle`"));
+    assert!(warnings[2].contains("`fg`"));
+}
+
+// ============================================================================
+// Colour depth
+// ============================================================================
+
+#[test]
+fn test_detect_color_depth() {
+    assert_eq!(
+        ColorDepth::from_env("truecolor", "xterm"),
+        ColorDepth::TrueColor
+    );
+    assert_eq!(ColorDepth::from_env("24bit", ""), ColorDepth::TrueColor);
+    assert_eq!(
+        ColorDepth::from_env("", "xterm-256color"),
+        ColorDepth::Ansi256
+    );
+    assert_eq!(ColorDepth::from_env("", "linux"), ColorDepth::Ansi16);
+    assert_eq!(ColorDepth::from_env("", "vt100"), ColorDepth::Ansi16);
+    assert_eq!(
+        ColorDepth::from_env("", "xterm-16color"),
+        ColorDepth::Ansi16
+    );
+    // Without COLORTERM, e.g. over SSH, other terminals keep 256 colours
+    assert_eq!(ColorDepth::from_env("", "alacritty"), ColorDepth::Ansi256);
+    assert_eq!(ColorDepth::from_env("", "xterm-kitty"), ColorDepth::Ansi256);
+    assert_eq!(ColorDepth::from_env("", "foot"), ColorDepth::Ansi256);
+}
+
+#[test]
+fn test_256_color_fallback() {
+    let theme = Theme::dark().with_color_depth(ColorDepth::Ansi256);
+    assert_eq!(theme.bg, Color::Reset);
+    // Soft white lands on the grayscale ramp
+    assert_eq!(theme.fg, Color::Indexed(253));
+    // Soft blue lands in the colour cube
+    assert_eq!(theme.accent, Color::Indexed(111));
+}
+
+#[test]
+fn test_16_color_fallback() {
+    let theme = Theme::dark().with_color_depth(ColorDepth::Ansi16);
+    let all = [
+        theme.fg,
+        theme.accent,
+        theme.accent_dim,
+        theme.selected_bg,
+        theme.selected_fg,
+        theme.error,
+        theme.success,
+        theme.warning,
+        theme.muted,
+    ];
+    assert!(
+        all.iter()
+            .all(|c| !matches!(c, Color::Rgb(..) | Color::Indexed(16..)))
+    );
+    assert_eq!(theme.selected_fg, Color::Black);
+    assert_eq!(
+        Theme::dark().with_color_depth(ColorDepth::TrueColor),
+        Theme::dark()
+    );
+}
+
+#[test]
+fn test_color_depth_setting() {
+    let (themed, warnings) = theme("color_depth = 16\n");
+    assert!(warnings.is_empty());
+    assert_eq!(themed, Theme::dark().with_color_depth(ColorDepth::Ansi16));
+
+    let (_, warnings) = theme("color_depth = \"lots\"\n");
+    assert_eq!(warnings.len(), 1);
+}
@@ -0,0 +1,185 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use ratatui::{Frame, Terminal, backend::TestBackend, widgets::Paragraph};
+use serde_json::{Value, json};
+use tuigreat::ipc::{Op, Request};
+use tuigreat::runner::{self, Command, FakeRunner, Reply};
+use tuigreat::testing::{TestHarness, screen_text};
+use tuigreat::trace::{self, Entry, Event, OUTPUT_LIMIT};
+use tuigreat::widgets::LogPopup;
+use tuigreat::{Action, App, AppResult, Theme};
+
+struct Empty {
+    theme: Theme,
+}
+
+impl App for Empty {
+    fn title(&self) -> &str {
+        "empty"
+    }
+
+    fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    fn handle_action(&mut self, action: Action) -> AppResult<bool> {
+        Ok(action != Action::Quit)
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        frame.render_widget(Paragraph::new("empty app"), frame.area());
+    }
+}
+
+fn command(seq: u64, argv: &[&str], status: i32) -> Entry {
+    Entry {
+        seq,
+        time: Duration::from_millis(seq * 100),
+        event: Event::Command {
+            argv: argv.iter().map(ToString::to_string).collect(),
+            duration: Duration::from_micros(3140),
+            status: Some(status),
+            stdout: "wlan0\n".to_string(),
+            stderr: String::new(),
+            error: None,
+        },
+    }
+}
+
+// The log is global, so everything that writes to it is in this one test
+#[test]
+fn test_log() {
+    let path: PathBuf =
+        std::env::temp_dir().join(format!("anytui-trace-{}.log", std::process::id()));
+    let _ = fs::remove_file(&path);
+    trace::start(&path).unwrap();
+    assert!(trace::enabled());
+    assert_eq!(trace::path(), Some(path.clone()));
+
+    let long = "x".repeat(OUTPUT_LIMIT + 500);
+    runner::set(Arc::new(
+        FakeRunner::new()
+            .with(Reply::new(&["iwctl", "device", "list"]).stdout(&long))
+            .with(
+                Reply::new(&["nmcli", "con", "up", "*"])
+                    .stderr("Error\n")
+                    .status(4),
+            ),
+    ));
+    Command::new("iwctl")
+        .args(["device", "list"])
+        .run()
+        .unwrap();
+    Command::new("nmcli")
+        .args(["con", "up", "hunter2"])
+        .redact("hunter2")
+        .run()
+        .unwrap_err();
+
+    let mut harness = TestHarness::new(
+        Empty {
+            theme: Theme::dark(),
+        },
+        90,
+        20,
+    );
+    harness.press("5j").type_text("x");
+    // Actions from `ctl` are logged like keys
+    harness.request(&Request::new(Op::Action(Action::Up, Some(2))));
+    harness.press("D");
+    assert!(harness.screen().contains(" Debug log: "));
+    assert!(harness.screen().contains("$ nmcli con up ***"));
+    // Keys go to the popup while it is open
+    harness.press("q");
+    assert!(harness.is_running());
+    assert!(!harness.screen().contains("Debug log"));
+    trace::stop();
+    assert!(!trace::enabled());
+
+    let lines: Vec<Value> = fs::read_to_string(&path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let _ = fs::remove_file(&path);
+    assert_eq!(lines[0]["kind"], "start");
+    assert_eq!(lines[1]["argv"], json!(["iwctl", "device", "list"]));
+    assert_eq!(lines[1]["status"], 0);
+    assert!(
+        lines[1]["stdout"]
+            .as_str()
+            .unwrap()
+            .ends_with(&format!("... ({} bytes)", OUTPUT_LIMIT + 500))
+    );
+    assert_eq!(lines[2]["argv"], json!(["nmcli", "con", "up", "***"]));
+    assert_eq!(lines[2]["status"], 4);
+    assert_eq!(lines[2]["stderr"], "Error\n");
+
+    let actions: Vec<&Value> = lines.iter().filter(|l| l["kind"] == "action").collect();
+    assert_eq!(actions[0]["app"], "empty");
+    assert_eq!(actions[0]["action"], "down");
+    assert_eq!(actions[0]["count"], 5);
+    // Typed characters are not logged
+    assert_eq!(actions[1]["action"], "char");
+    assert_eq!(actions[1].get("char"), None);
+    assert_eq!(actions[2]["action"], "up");
+    assert_eq!(actions[2]["count"], 2);
+    assert_eq!(actions[3]["action"], "log");
+    assert!(
+        lines
+            .iter()
+            .any(|l| l["kind"] == "tick" && l["app"] == "empty")
+    );
+}
+
+#[test]
+fn test_popup_follows_the_tail() {
+    let path = Some(PathBuf::from("/tmp/voltui.log"));
+    let mut popup = LogPopup::with_entries(path, vec![command(0, &["iwctl"], 0)]);
+    popup.update(vec![command(0, &["iwctl"], 0), command(1, &["nmcli"], 4)]);
+    assert_eq!(popup.selected().unwrap().seq, 1);
+
+    // Once moved away from the tail, the selection stays put
+    assert!(popup.handle_action(Action::Up));
+    popup.update(vec![
+        command(0, &["iwctl"], 0),
+        command(1, &["nmcli"], 4),
+        command(2, &["ip"], 0),
+    ]);
+    assert_eq!(popup.selected().unwrap().seq, 0);
+
+    let mut terminal = Terminal::new(TestBackend::new(70, 12)).unwrap();
+    terminal
+        .draw(|frame| popup.render(frame, &Theme::dark()))
+        .unwrap();
+    let screen = screen_text(terminal.backend().buffer());
+    assert!(screen.contains("Debug log: /tmp/voltui.log"));
+    assert!(
+        screen.contains("0.100  $ nmcli  3.1 ms, exit 4"),
+        "{screen}"
+    );
+
+    // Enter shows the output of the command
+    assert!(popup.handle_action(Action::Select));
+    terminal
+        .draw(|frame| popup.render(frame, &Theme::dark()))
+        .unwrap();
+    assert!(screen_text(terminal.backend().buffer()).contains("stdout:"));
+    assert!(popup.handle_action(Action::Back));
+    assert!(!popup.handle_action(Action::Log));
+}
+
+#[test]
+fn test_popup_without_a_log() {
+    let popup = LogPopup::with_entries(None, Vec::new());
+    let mut terminal = Terminal::new(TestBackend::new(90, 12)).unwrap();
+    terminal
+        .draw(|frame| popup.render(frame, &Theme::dark()))
+        .unwrap();
+    let screen = screen_text(terminal.backend().buffer());
+    assert!(screen.contains("Debug log (off)"));
+    assert!(screen.contains("--debug"));
+}
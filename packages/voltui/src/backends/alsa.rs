@@ -1,7 +1,7 @@
-use std::process::Command;
+use tuigreat::Error;
+use tuigreat::runner::Command;
 
 use crate::audio::{Sink, Source};
-use tuigreat::AppResult;
 
 struct ControlInfo {
     name: String,
@@ -67,9 +67,8 @@ fn parse_alsa_controls(output: &str, playback: bool) -> Vec<ControlInfo> {
     controls
 }
 
-pub fn get_sinks() -> AppResult<Vec<Sink>> {
-    let output = Command::new("amixer").args(["scontents"]).output()?;
-    let text = String::from_utf8_lossy(&output.stdout);
+pub fn get_sinks() -> Result<Vec<Sink>, Error> {
+    let text = Command::new("amixer").args(["scontents"]).run()?;
     let controls = parse_alsa_controls(&text, true);
 
     Ok(controls
@@ -84,9 +83,8 @@ pub fn get_sinks() -> AppResult<Vec<Sink>> {
         .collect())
 }
 
-pub fn get_sources() -> AppResult<Vec<Source>> {
-    let output = Command::new("amixer").args(["scontents"]).output()?;
-    let text = String::from_utf8_lossy(&output.stdout);
+pub fn get_sources() -> Result<Vec<Source>, Error> {
+    let text = Command::new("amixer").args(["scontents"]).run()?;
     let controls = parse_alsa_controls(&text, false);
 
     Ok(controls
@@ -101,32 +99,41 @@ pub fn get_sources() -> AppResult<Vec<Source>> {
         .collect())
 }
 
-pub fn adjust_sink_volume(name: &str, delta: i8) -> AppResult<()> {
-    let change = if delta > 0 { "5%+" } else { "5%-" };
-    Command::new("amixer")
-        .args(["sset", name, change])
-        .output()?;
+pub fn adjust_sink_volume(name: &str, delta: i8) -> Result<(), Error> {
+    let change = volume_change(delta);
+    Command::new("amixer").args(["sset", name, &change]).run()?;
+    Ok(())
+}
+
+pub fn adjust_source_volume(name: &str, delta: i8) -> Result<(), Error> {
+    let change = volume_change(delta);
+    Command::new("amixer").args(["sset", name, &change]).run()?;
     Ok(())
 }
 
-pub fn adjust_source_volume(name: &str, delta: i8) -> AppResult<()> {
-    let change = if delta > 0 { "5%+" } else { "5%-" };
+pub fn set_volume(name: &str, percent: u8) -> Result<(), Error> {
     Command::new("amixer")
-        .args(["sset", name, change])
-        .output()?;
+        .args(["sset", name, &format!("{percent}%")])
+        .run()?;
     Ok(())
 }
 
-pub fn toggle_sink_mute(name: &str) -> AppResult<()> {
+pub fn toggle_sink_mute(name: &str) -> Result<(), Error> {
     Command::new("amixer")
         .args(["sset", name, "toggle"])
-        .output()?;
+        .run()?;
     Ok(())
 }
 
-pub fn toggle_source_mute(name: &str) -> AppResult<()> {
+pub fn toggle_source_mute(name: &str) -> Result<(), Error> {
     Command::new("amixer")
         .args(["sset", name, "toggle"])
-        .output()?;
+        .run()?;
     Ok(())
 }
+
+/// amixer syntax for `delta` steps of 5%, e.g. `15%+`.
+fn volume_change(delta: i8) -> String {
+    let sign = if delta > 0 { '+' } else { '-' };
+    format!("{}%{sign}", u16::from(delta.unsigned_abs()) * 5)
+}
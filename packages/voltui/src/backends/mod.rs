@@ -2,79 +2,102 @@ pub mod alsa;
 pub mod pactl;
 
 use crate::audio::{AppStream, AudioBackend, Sink, Source};
-use tuigreat::AppResult;
+use tuigreat::Error;
 
-pub fn get_sinks(backend: AudioBackend) -> AppResult<Vec<Sink>> {
+pub fn get_sinks(backend: AudioBackend) -> Result<Vec<Sink>, Error> {
     match backend {
         AudioBackend::PulseAudio => pactl::get_sinks(),
         AudioBackend::Alsa => alsa::get_sinks(),
     }
 }
 
-pub fn get_sources(backend: AudioBackend) -> AppResult<Vec<Source>> {
+pub fn get_sources(backend: AudioBackend) -> Result<Vec<Source>, Error> {
     match backend {
         AudioBackend::PulseAudio => pactl::get_sources(),
         AudioBackend::Alsa => alsa::get_sources(),
     }
 }
 
-pub fn adjust_sink_volume(backend: AudioBackend, name: &str, delta: i8) -> AppResult<()> {
+pub fn adjust_sink_volume(backend: AudioBackend, name: &str, delta: i8) -> Result<(), Error> {
     match backend {
         AudioBackend::PulseAudio => pactl::adjust_sink_volume(name, delta),
         AudioBackend::Alsa => alsa::adjust_sink_volume(name, delta),
     }
 }
 
-pub fn adjust_source_volume(backend: AudioBackend, name: &str, delta: i8) -> AppResult<()> {
+pub fn adjust_source_volume(backend: AudioBackend, name: &str, delta: i8) -> Result<(), Error> {
     match backend {
         AudioBackend::PulseAudio => pactl::adjust_source_volume(name, delta),
         AudioBackend::Alsa => alsa::adjust_source_volume(name, delta),
     }
 }
 
-pub fn toggle_sink_mute(backend: AudioBackend, name: &str) -> AppResult<()> {
+pub fn set_sink_volume(backend: AudioBackend, name: &str, percent: u8) -> Result<(), Error> {
+    match backend {
+        AudioBackend::PulseAudio => pactl::set_sink_volume(name, percent),
+        AudioBackend::Alsa => alsa::set_volume(name, percent),
+    }
+}
+
+pub fn set_source_volume(backend: AudioBackend, name: &str, percent: u8) -> Result<(), Error> {
+    match backend {
+        AudioBackend::PulseAudio => pactl::set_source_volume(name, percent),
+        AudioBackend::Alsa => alsa::set_volume(name, percent),
+    }
+}
+
+pub fn toggle_sink_mute(backend: AudioBackend, name: &str) -> Result<(), Error> {
     match backend {
         AudioBackend::PulseAudio => pactl::toggle_sink_mute(name),
         AudioBackend::Alsa => alsa::toggle_sink_mute(name),
     }
 }
 
-pub fn toggle_source_mute(backend: AudioBackend, name: &str) -> AppResult<()> {
+pub fn toggle_source_mute(backend: AudioBackend, name: &str) -> Result<(), Error> {
     match backend {
         AudioBackend::PulseAudio => pactl::toggle_source_mute(name),
         AudioBackend::Alsa => alsa::toggle_source_mute(name),
     }
 }
 
-pub fn set_default_sink(backend: AudioBackend, name: &str) -> Result<(), String> {
+pub fn set_default_sink(backend: AudioBackend, name: &str) -> Result<(), Error> {
     match backend {
         AudioBackend::PulseAudio => pactl::set_default_sink(name),
-        AudioBackend::Alsa => Err("ALSA does not support setting default sink".to_string()),
+        AudioBackend::Alsa => Err(Error::backend("ALSA does not support setting default sink")),
     }
 }
 
-pub fn set_default_source(backend: AudioBackend, name: &str) -> Result<(), String> {
+pub fn set_default_source(backend: AudioBackend, name: &str) -> Result<(), Error> {
     match backend {
         AudioBackend::PulseAudio => pactl::set_default_source(name),
-        AudioBackend::Alsa => Err("ALSA does not support setting default source".to_string()),
+        AudioBackend::Alsa => Err(Error::backend(
+            "ALSA does not support setting default source",
+        )),
     }
 }
 
-pub fn get_app_streams(backend: AudioBackend) -> AppResult<Vec<AppStream>> {
+pub fn get_app_streams(backend: AudioBackend) -> Result<Vec<AppStream>, Error> {
     match backend {
         AudioBackend::PulseAudio => pactl::get_app_streams(),
         AudioBackend::Alsa => Ok(vec![]),
     }
 }
 
-pub fn adjust_app_volume(backend: AudioBackend, index: u32, delta: i8) -> AppResult<()> {
+pub fn adjust_app_volume(backend: AudioBackend, index: u32, delta: i8) -> Result<(), Error> {
     match backend {
         AudioBackend::PulseAudio => pactl::adjust_app_volume(index, delta),
         AudioBackend::Alsa => Ok(()),
     }
 }
 
-pub fn toggle_app_mute(backend: AudioBackend, index: u32) -> AppResult<()> {
+pub fn set_app_volume(backend: AudioBackend, index: u32, percent: u8) -> Result<(), Error> {
+    match backend {
+        AudioBackend::PulseAudio => pactl::set_app_volume(index, percent),
+        AudioBackend::Alsa => Err(Error::backend("per-app volume needs PulseAudio")),
+    }
+}
+
+pub fn toggle_app_mute(backend: AudioBackend, index: u32) -> Result<(), Error> {
     match backend {
         AudioBackend::PulseAudio => pactl::toggle_app_mute(index),
         AudioBackend::Alsa => Ok(()),
@@ -0,0 +1,163 @@
+//! `voltui sink list`, `voltui source volume +5` and the other commands
+//! that run without the UI.
+
+use serde_json::{Value, json};
+use tuigreat::cli::{Call, Cli};
+use tuigreat::widgets::CommandSpec;
+use tuigreat::{AppResult, Error};
+
+use crate::audio::{AppStream, AudioBackend, Sink, Source};
+use crate::backends;
+
+const COMMANDS: &[CommandSpec] = &[
+    CommandSpec::new("sink list", "", "List outputs"),
+    CommandSpec::new("sink get", "[name]", "Show the default or named output"),
+    CommandSpec::new("sink default", "<name>", "Make an output the default"),
+    CommandSpec::new("sink volume", "<0-100|+N|-N> [name]", "Set the volume"),
+    CommandSpec::new("sink mute", "[name]", "Mute or unmute an output"),
+    CommandSpec::new("source list", "", "List inputs"),
+    CommandSpec::new("source get", "[name]", "Show the default or named input"),
+    CommandSpec::new("source default", "<name>", "Make an input the default"),
+    CommandSpec::new("source volume", "<0-100|+N|-N> [name]", "Set the volume"),
+    CommandSpec::new("source mute", "[name]", "Mute or unmute an input"),
+    CommandSpec::new("app list", "", "List application streams"),
+];
+
+pub const CLI: Cli = Cli::new("voltui", COMMANDS);
+
+/// A volume argument: a percentage, or percentage points up or down.
+enum Volume {
+    Set(u8),
+    Adjust(i16),
+}
+
+impl Volume {
+    fn parse(call: &Call) -> Result<Self, String> {
+        let arg = call.arg(0)?;
+        let volume = if arg.starts_with(['+', '-']) {
+            arg.parse().ok().map(Volume::Adjust)
+        } else {
+            arg.parse().ok().filter(|v| *v <= 100).map(Volume::Set)
+        };
+        volume.ok_or_else(|| call.usage_error())
+    }
+
+    /// The new volume, starting from `current`.
+    fn apply(&self, current: u8) -> u8 {
+        match *self {
+            Volume::Set(percent) => percent,
+            Volume::Adjust(delta) => {
+                u8::try_from((i16::from(current) + delta).clamp(0, 100)).unwrap_or(current)
+            }
+        }
+    }
+}
+
+/// Run a command against `backend`.
+///
+/// # Errors
+/// Returns an error if the arguments are wrong or the backend fails.
+pub fn run(backend: AudioBackend, call: &Call) -> AppResult<Value> {
+    let value = match call.name {
+        "sink list" => backends::get_sinks(backend)?
+            .iter()
+            .map(sink_json)
+            .collect(),
+        "sink get" => sink_json(&find_sink(backend, call.args.first())?),
+        "sink default" => {
+            backends::set_default_sink(backend, call.arg(0)?)?;
+            Value::Null
+        }
+        "sink volume" => {
+            let volume = Volume::parse(call)?;
+            let mut sink = find_sink(backend, call.args.get(1))?;
+            sink.volume = volume.apply(sink.volume);
+            backends::set_sink_volume(backend, &sink.name, sink.volume)?;
+            sink_json(&sink)
+        }
+        "sink mute" => {
+            let name = find_sink(backend, call.args.first())?.name.clone();
+            backends::toggle_sink_mute(backend, &name)?;
+            sink_json(&find_sink(backend, Some(&name))?)
+        }
+        "source list" => backends::get_sources(backend)?
+            .iter()
+            .map(source_json)
+            .collect(),
+        "source get" => source_json(&find_source(backend, call.args.first())?),
+        "source default" => {
+            backends::set_default_source(backend, call.arg(0)?)?;
+            Value::Null
+        }
+        "source volume" => {
+            let volume = Volume::parse(call)?;
+            let mut source = find_source(backend, call.args.get(1))?;
+            source.volume = volume.apply(source.volume);
+            backends::set_source_volume(backend, &source.name, source.volume)?;
+            source_json(&source)
+        }
+        "source mute" => {
+            let name = find_source(backend, call.args.first())?.name.clone();
+            backends::toggle_source_mute(backend, &name)?;
+            source_json(&find_source(backend, Some(&name))?)
+        }
+        "app list" => backends::get_app_streams(backend)?
+            .iter()
+            .map(app_json)
+            .collect(),
+        _ => return Err(call.usage_error().into()),
+    };
+    Ok(value)
+}
+
+/// The sink called `name`, or the default sink.
+fn find_sink(backend: AudioBackend, name: Option<&String>) -> Result<Sink, Error> {
+    backends::get_sinks(backend)?
+        .into_iter()
+        .find(|s| name.map_or(s.is_default, |name| s.name == *name))
+        .ok_or_else(|| not_found("output", name))
+}
+
+/// The source called `name`, or the default source.
+fn find_source(backend: AudioBackend, name: Option<&String>) -> Result<Source, Error> {
+    backends::get_sources(backend)?
+        .into_iter()
+        .find(|s| name.map_or(s.is_default, |name| s.name == *name))
+        .ok_or_else(|| not_found("input", name))
+}
+
+fn not_found(what: &str, name: Option<&String>) -> Error {
+    match name {
+        Some(name) => Error::backend(format!("No {what} called {name}")),
+        None => Error::backend(format!("No default {what}")),
+    }
+}
+
+fn sink_json(sink: &Sink) -> Value {
+    json!({
+        "name": sink.name,
+        "description": sink.description,
+        "volume": sink.volume,
+        "muted": sink.muted,
+        "default": sink.is_default,
+    })
+}
+
+fn source_json(source: &Source) -> Value {
+    json!({
+        "name": source.name,
+        "description": source.description,
+        "volume": source.volume,
+        "muted": source.muted,
+        "default": source.is_default,
+    })
+}
+
+fn app_json(stream: &AppStream) -> Value {
+    json!({
+        "index": stream.index,
+        "name": stream.app_name,
+        "volume": stream.volume,
+        "muted": stream.muted,
+    })
+}
@@ -1,4 +1,11 @@
-//! Audio TUI parsing utilities.
+//! Audio TUI parsing utilities, backends and the UI.
+
+pub mod audio;
+pub mod backends;
+pub mod cli;
+pub mod ui;
+
+pub use ui::PwTui;
 
 /// Extract `sink_name` from module arguments string.
 ///
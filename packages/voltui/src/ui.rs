@@ -0,0 +1,1538 @@
+//! The interactive UI, also hosted by the `anytui` hub.
+
+use std::process::Command;
+use std::sync::mpsc::TryRecvError;
+use std::time::{Duration, Instant};
+
+use crossterm::event::{MouseEvent, MouseEventKind};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+};
+use serde_json::{Value, json};
+use tuigreat::{
+    Action, App, AppResult, Confirmations, Events, Interval, Keymap, MessageLog, Notifier,
+    Progress, StatusMessage, Tasks, Theme,
+    events::Lines,
+    repeat_action,
+    widgets::{
+        Column, CommandEvent, CommandLine, CommandSpec, ConfirmEvent, ConfirmPopup, DetailPopup,
+        HelpPopup, Invocation, MessagePopup, SearchDirection, SearchPopup, SearchState,
+        SelectableList, SelectableTable, Tabs,
+    },
+    yank,
+};
+
+use crate::audio::{AppStream, AudioBackend, Sink, Source};
+use crate::backends;
+
+fn volume_bar(volume: u8) -> String {
+    let filled = (usize::from(volume) / 10).min(10);
+    let empty = 10 - filled;
+    format!("[{}{}]", "=".repeat(filled), " ".repeat(empty))
+}
+
+/// How often to poll the backend without change notifications.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long to wait after a change notification for the rest of its burst.
+const CHANGE_DELAY: Duration = Duration::from_millis(50);
+
+/// Table columns of a row's volume bar and percentage, where the scroll
+/// wheel changes volume instead of moving the selection.
+const VOLUME_COLUMNS: std::ops::Range<usize> = 1..3;
+
+/// What the sink, source and application tables show about a stream.
+trait Stream {
+    fn label(&self) -> &str;
+    fn volume(&self) -> u8;
+    fn muted(&self) -> bool;
+    fn is_default(&self) -> bool {
+        false
+    }
+}
+
+impl Stream for Sink {
+    fn label(&self) -> &str {
+        &self.description
+    }
+    fn volume(&self) -> u8 {
+        self.volume
+    }
+    fn muted(&self) -> bool {
+        self.muted
+    }
+    fn is_default(&self) -> bool {
+        self.is_default
+    }
+}
+
+impl Stream for Source {
+    fn label(&self) -> &str {
+        &self.description
+    }
+    fn volume(&self) -> u8 {
+        self.volume
+    }
+    fn muted(&self) -> bool {
+        self.muted
+    }
+    fn is_default(&self) -> bool {
+        self.is_default
+    }
+}
+
+impl Stream for AppStream {
+    fn label(&self) -> &str {
+        &self.app_name
+    }
+    fn volume(&self) -> u8 {
+        self.volume
+    }
+    fn muted(&self) -> bool {
+        self.muted
+    }
+}
+
+/// Default and mute flags, volume bar, percentage and name. Search and
+/// `f{char}` match the name.
+fn stream_table<T: Stream>(items: Vec<T>, key: fn(&T) -> String) -> SelectableTable<T> {
+    let list = SelectableList::new(items, |s: &T| s.label().to_string()).with_key(key);
+    let columns = vec![
+        Column::new("", Constraint::Length(2), |s: &T| {
+            let default = if s.is_default() { '*' } else { ' ' };
+            let mute = if s.muted() { 'M' } else { ' ' };
+            format!("{default}{mute}")
+        }),
+        Column::new("Volume", Constraint::Length(12), |s: &T| {
+            volume_bar(s.volume())
+        })
+        .with_style(|s: &T, theme: &Theme| {
+            if s.muted() {
+                theme.muted()
+            } else {
+                theme.normal()
+            }
+        })
+        .with_sort(|a: &T, b: &T| a.volume().cmp(&b.volume())),
+        Column::new("%", Constraint::Length(4), |s: &T| {
+            format!("{}%", s.volume())
+        })
+        .with_alignment(Alignment::Right)
+        .with_sort(|a: &T, b: &T| a.volume().cmp(&b.volume())),
+        Column::new("Name", Constraint::Fill(1), |s: &T| s.label().to_string()),
+    ];
+    SelectableTable::from_list(list, columns)
+}
+
+/// `combined_2` is shown as "Combined 2".
+fn combined_display_name(name: &str) -> String {
+    name.strip_prefix("combined_")
+        .map_or_else(|| name.to_string(), |n| format!("Combined {n}"))
+}
+
+/// Results of background tasks.
+enum TaskResult {
+    PipewireRestarted,
+}
+
+/// Stop and start the PipeWire services, waiting for them to settle.
+fn restart_pipewire_services(progress: &Progress) -> TaskResult {
+    // Stop all services
+    let _ = tuigreat::runner::Command::new("systemctl")
+        .args([
+            "--user",
+            "stop",
+            "pipewire",
+            "pipewire-pulse",
+            "wireplumber",
+        ])
+        .output();
+
+    // Small delay
+    std::thread::sleep(Duration::from_millis(500));
+
+    // Start services in order
+    progress.set(" Starting PipeWire...");
+    let _ = tuigreat::runner::Command::new("systemctl")
+        .args([
+            "--user",
+            "start",
+            "pipewire",
+            "wireplumber",
+            "pipewire-pulse",
+        ])
+        .output();
+
+    // Wait for services to stabilize
+    std::thread::sleep(Duration::from_millis(1000));
+    TaskResult::PipewireRestarted
+}
+
+/// Outputs, inputs and application streams of an audio backend.
+pub struct PwTui {
+    theme: Theme,
+    keymap: Keymap,
+    tabs: Tabs,
+    sinks: SelectableTable<Sink>,
+    sources: SelectableTable<Source>,
+    apps: SelectableTable<AppStream>,
+    show_help: bool,
+    messages: MessageLog,
+    // Full output of the last error, opened with `!`
+    details: Option<DetailPopup>,
+    history: Option<MessagePopup>,
+    notifier: Notifier,
+    confirmations: Confirmations,
+    confirm: Option<ConfirmPopup>,
+    // Polling fallback when `pactl subscribe` is not running
+    refresh_timer: Interval,
+    // Change notifications from `pactl subscribe`, stopped when dropped
+    changes: Option<Lines>,
+    // When to refresh after a burst of change notifications
+    change_refresh: Option<Instant>,
+    events: Events,
+    tasks: Tasks<TaskResult>,
+    combined_modules: Vec<(u32, String)>,
+    combined_selected: usize,
+    // Which panel is focused in Combine tab: false = left (sinks), true = right (combined)
+    combine_right_focus: bool,
+    // `:` command line
+    command: CommandLine,
+    // Search mode
+    search_mode: bool,
+    // Jump mode (vim-style f/F)
+    jump_mode: Option<bool>, // Some(true) = forward, Some(false) = backward
+    // Audio backend
+    backend: AudioBackend,
+    // With `--inline`, Enter prints the sink or source name and quits
+    picker: bool,
+    picked: Option<String>,
+}
+
+impl PwTui {
+    /// Load the devices and streams of `backend`.
+    ///
+    /// # Errors
+    /// Returns an error if the backend cannot list them.
+    pub fn new(backend: AudioBackend) -> AppResult<Self> {
+        let sinks = backends::get_sinks(backend)?;
+        let sources = backends::get_sources(backend)?;
+        let app_streams = backends::get_app_streams(backend)?;
+        let combined_modules = if backend == AudioBackend::PulseAudio {
+            backends::pactl::get_combined_modules()?
+        } else {
+            vec![]
+        };
+
+        let mut tab_names = vec!["Output".to_string(), "Input".to_string()];
+        if backend == AudioBackend::PulseAudio {
+            tab_names.push("Apps".to_string());
+            tab_names.push("Combine".to_string());
+        }
+        let app_title = format!("Audio Manager v0.1 ({backend})");
+        let keymap = Keymap::load("voltui");
+        let mut messages = MessageLog::new();
+        let confirmations = Confirmations::load("voltui", &["remove", "restart"]);
+        for w in keymap.warnings().iter().chain(confirmations.warnings()) {
+            messages.push(StatusMessage::warning(format!("Warning: {w}")));
+        }
+
+        Ok(Self {
+            theme: Theme::default(),
+            keymap,
+            tabs: Tabs::new(tab_names).with_app_title(&app_title),
+            sinks: stream_table(sinks, |s| s.name.clone()),
+            sources: stream_table(sources, |s| s.name.clone()),
+            apps: stream_table(app_streams, |a| a.index.to_string()),
+            show_help: false,
+            messages,
+            details: None,
+            history: None,
+            notifier: Notifier::new("voltui"),
+            confirmations,
+            confirm: None,
+            refresh_timer: Interval::new(REFRESH_INTERVAL),
+            changes: None,
+            change_refresh: None,
+            events: Events::default(),
+            tasks: Tasks::new(),
+            combined_modules,
+            combined_selected: 0,
+            combine_right_focus: false,
+            command: CommandLine::new(commands(backend)),
+            search_mode: false,
+            jump_mode: None,
+            backend,
+            picker: false,
+            picked: None,
+        })
+    }
+
+    /// With `picker`, Enter quits and [`App::output`] gives the name of
+    /// the selected sink or source.
+    #[must_use]
+    pub fn with_picker(mut self, picker: bool) -> Self {
+        self.picker = picker;
+        self
+    }
+
+    fn refresh(&mut self) -> AppResult<()> {
+        if self.tasks.is_busy() {
+            return Ok(());
+        }
+        let sinks = backends::get_sinks(self.backend)?;
+        self.notify_default_change(&sinks);
+        self.sinks.set_items(sinks);
+        self.sources.set_items(backends::get_sources(self.backend)?);
+        self.apps
+            .set_items(backends::get_app_streams(self.backend)?);
+        if self.backend == AudioBackend::PulseAudio {
+            self.combined_modules = backends::pactl::get_combined_modules()?;
+        }
+        // Ensure combined_selected stays in bounds after refresh
+        if self.combined_modules.is_empty() {
+            self.combined_selected = 0;
+        } else {
+            self.combined_selected = self.combined_selected.min(self.combined_modules.len() - 1);
+        }
+        Ok(())
+    }
+
+    /// Tell the desktop when another program switches the default output.
+    fn notify_default_change(&self, sinks: &[Sink]) {
+        let previous = self.sinks.items().iter().find(|s| s.is_default);
+        if let Some(sink) = sinks.iter().find(|s| s.is_default)
+            && previous.is_some_and(|p| p.name != sink.name)
+        {
+            self.notifier
+                .notify(format!("Default output: {}", sink.description), "Audio");
+        }
+    }
+
+    /// Listen for PulseAudio changes instead of polling.
+    fn subscribe(&mut self) {
+        if self.backend == AudioBackend::PulseAudio {
+            self.changes = self
+                .events
+                .lines(Command::new("pactl").arg("subscribe"))
+                .ok();
+        }
+    }
+
+    /// Drain pending change notifications, returning whether there were any.
+    fn take_changes(&mut self) -> bool {
+        let Some(changes) = &self.changes else {
+            return false;
+        };
+        let mut changed = false;
+        loop {
+            match changes.receiver().try_recv() {
+                Ok(_) => changed = true,
+                Err(TryRecvError::Empty) => return changed,
+                Err(TryRecvError::Disconnected) => {
+                    // pactl exited (e.g. PipeWire restart): fall back to polling
+                    self.changes = None;
+                    return true;
+                }
+            }
+        }
+    }
+
+    fn refresh_paused(&self) -> bool {
+        // The backend is down while PipeWire restarts
+        self.show_help
+            || self.details.is_some()
+            || self.history.is_some()
+            || self.confirm.is_some()
+            || self.input_mode()
+            || self.tasks.is_busy()
+    }
+
+    fn current_tab(&self) -> usize {
+        self.tabs.selected()
+    }
+
+    fn set_volume(&mut self, delta: i8) -> AppResult<()> {
+        if self.tasks.is_busy() {
+            return Ok(());
+        }
+        // Volumes of the changed items, to report the new level
+        let volumes: Vec<u8> = match self.current_tab() {
+            0 => {
+                let sinks = self.sinks.marked_or_selected();
+                for sink in &sinks {
+                    backends::adjust_sink_volume(self.backend, &sink.name, delta)?;
+                }
+                sinks.iter().map(|s| s.volume).collect()
+            }
+            1 => {
+                let sources = self.sources.marked_or_selected();
+                for source in &sources {
+                    backends::adjust_source_volume(self.backend, &source.name, delta)?;
+                }
+                sources.iter().map(|s| s.volume).collect()
+            }
+            2 => {
+                let apps = self.apps.marked_or_selected();
+                for app in &apps {
+                    backends::adjust_app_volume(self.backend, app.index, delta)?;
+                }
+                apps.iter().map(|a| a.volume).collect()
+            }
+            _ => Vec::new(),
+        };
+        match volumes.as_slice() {
+            [] => {}
+            [volume] => {
+                let new_vol = (i16::from(*volume) + i16::from(delta) * 5).clamp(0, 100);
+                self.messages
+                    .hint(StatusMessage::info(format!("Volume: {new_vol}%")));
+            }
+            volumes => {
+                let change = i16::from(delta) * 5;
+                self.messages.hint(StatusMessage::info(format!(
+                    "Volume {change:+}% on {} items",
+                    volumes.len()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn set_volume_to(&mut self, percent: u8) -> AppResult<()> {
+        if self.tasks.is_busy() {
+            return Ok(());
+        }
+        match self.current_tab() {
+            0 => {
+                for sink in self.sinks.marked_or_selected() {
+                    backends::set_sink_volume(self.backend, &sink.name, percent)?;
+                }
+            }
+            1 => {
+                for source in self.sources.marked_or_selected() {
+                    backends::set_source_volume(self.backend, &source.name, percent)?;
+                }
+            }
+            2 => {
+                for app in self.apps.marked_or_selected() {
+                    backends::set_app_volume(self.backend, app.index, percent)?;
+                }
+            }
+            _ => return Ok(()),
+        }
+        self.messages
+            .hint(StatusMessage::info(format!("Volume: {percent}%")));
+        Ok(())
+    }
+
+    fn toggle_mute(&mut self) -> AppResult<()> {
+        if self.tasks.is_busy() {
+            return Ok(());
+        }
+        // Mute state of the toggled items before the change
+        let muted: Vec<bool> = match self.current_tab() {
+            0 => {
+                let sinks = self.sinks.marked_or_selected();
+                for sink in &sinks {
+                    backends::toggle_sink_mute(self.backend, &sink.name)?;
+                }
+                sinks.iter().map(|s| s.muted).collect()
+            }
+            1 => {
+                let sources = self.sources.marked_or_selected();
+                for source in &sources {
+                    backends::toggle_source_mute(self.backend, &source.name)?;
+                }
+                sources.iter().map(|s| s.muted).collect()
+            }
+            2 => {
+                let apps = self.apps.marked_or_selected();
+                for app in &apps {
+                    backends::toggle_app_mute(self.backend, app.index)?;
+                }
+                apps.iter().map(|a| a.muted).collect()
+            }
+            _ => Vec::new(),
+        };
+        match muted.as_slice() {
+            [] => {}
+            [true] => self.messages.push(StatusMessage::info("Unmuted")),
+            [false] => self.messages.push(StatusMessage::info("Muted")),
+            muted => self.messages.push(StatusMessage::info(format!(
+                "Toggled mute on {} items",
+                muted.len()
+            ))),
+        }
+        Ok(())
+    }
+
+    fn restart_pipewire(&mut self) {
+        if self.tasks.is_busy() {
+            return;
+        }
+        self.confirm = self.confirmations.ask(
+            "restart",
+            "Restart PipeWire? Audio stops for a moment and streams may move.",
+        );
+        if self.confirm.is_none() {
+            self.start_restart();
+        }
+    }
+
+    fn start_restart(&mut self) {
+        self.tasks
+            .spawn(" Restarting PipeWire...", restart_pipewire_services);
+    }
+
+    fn finish_task(&mut self, result: TaskResult) {
+        match result {
+            TaskResult::PipewireRestarted => {
+                // Refresh the device lists
+                if self.refresh().is_ok() {
+                    self.subscribe();
+                    self.messages
+                        .push(StatusMessage::success("PipeWire restarted"));
+                } else {
+                    self.messages
+                        .push(StatusMessage::error("PipeWire restarted (refresh failed)"));
+                }
+            }
+        }
+    }
+
+    fn run_command(&mut self, invocation: &Invocation) -> AppResult<()> {
+        match invocation.name {
+            "volume" => match invocation.parse::<u8>() {
+                Ok(percent @ 0..=100) => {
+                    self.set_volume_to(percent)?;
+                    self.refresh()?;
+                }
+                _ => self
+                    .messages
+                    .push(StatusMessage::error(invocation.usage_error())),
+            },
+            "mute" => {
+                self.toggle_mute()?;
+                self.refresh()?;
+            }
+            "set-default" if self.current_tab() <= 1 => {
+                self.set_default();
+                self.refresh()?;
+            }
+            "set-default" => self
+                .messages
+                .push(StatusMessage::error("Select an output or input")),
+            "restart-pipewire" => self.restart_pipewire(),
+            "refresh" => self.refresh()?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn combine_tab(&self) -> usize {
+        if self.backend == AudioBackend::PulseAudio {
+            3
+        } else {
+            usize::MAX // unreachable — Combine tab doesn't exist for ALSA
+        }
+    }
+
+    /// Name of the selected sink or source, as `pactl` knows it.
+    fn selected_name(&self) -> Option<String> {
+        match self.current_tab() {
+            0 => self.sinks.selected().map(|s| s.name.clone()),
+            1 => self.sources.selected().map(|s| s.name.clone()),
+            _ => None,
+        }
+    }
+
+    fn set_default(&mut self) {
+        match self.current_tab() {
+            0 => {
+                if let Some(sink) = self.sinks.selected() {
+                    match backends::set_default_sink(self.backend, &sink.name) {
+                        Ok(()) => self.messages.push(StatusMessage::success(format!(
+                            "Default: {}",
+                            sink.description
+                        ))),
+                        Err(e) => self.messages.push(e),
+                    }
+                }
+            }
+            1 => {
+                if let Some(source) = self.sources.selected() {
+                    match backends::set_default_source(self.backend, &source.name) {
+                        Ok(()) => self.messages.push(StatusMessage::success(format!(
+                            "Default: {}",
+                            source.description
+                        ))),
+                        Err(e) => self.messages.push(e),
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn toggle_combine_selection(&mut self) {
+        self.sinks.toggle_mark();
+        if let Some(index) = self.sinks.selected_index()
+            && let Some(sink) = self.sinks.selected()
+        {
+            self.messages
+                .push(StatusMessage::info(if self.sinks.is_marked(index) {
+                    format!(
+                        "Selected: {} ({} total)",
+                        sink.description,
+                        self.sinks.marked().len()
+                    )
+                } else {
+                    format!("Deselected: {}", sink.description)
+                }));
+        }
+    }
+
+    fn toggle_mark(&mut self) {
+        match self.current_tab() {
+            0 => self.sinks.toggle_mark(),
+            1 => self.sources.toggle_mark(),
+            2 => self.apps.toggle_mark(),
+            3 if !self.combine_right_focus => self.toggle_combine_selection(),
+            _ => {}
+        }
+    }
+
+    fn toggle_visual(&mut self) {
+        match self.current_tab() {
+            1 => self.sources.toggle_visual(),
+            2 => self.apps.toggle_visual(),
+            3 if self.combine_right_focus => {}
+            _ => self.sinks.toggle_visual(),
+        }
+    }
+
+    /// Sort by the `column`th column (counting from 1), or step to the next
+    /// sort without one.
+    fn sort(&mut self, column: Option<usize>) {
+        match (self.current_tab(), column) {
+            (0, Some(n)) => self.sinks.sort_by(n.saturating_sub(1)),
+            (1, Some(n)) => self.sources.sort_by(n.saturating_sub(1)),
+            (2, Some(n)) => self.apps.sort_by(n.saturating_sub(1)),
+            (0, None) => self.sinks.cycle_sort(),
+            (1, None) => self.sources.cycle_sort(),
+            (2, None) => self.apps.cycle_sort(),
+            _ => {}
+        }
+    }
+
+    fn unmark(&mut self) {
+        match self.current_tab() {
+            1 => self.sources.unmark(),
+            2 => self.apps.unmark(),
+            _ => self.sinks.unmark(),
+        }
+    }
+
+    fn create_combined(&mut self) {
+        // Marks made on the Output tab count too, but not combined sinks
+        let sink_names: Vec<&str> = self
+            .sinks
+            .marked()
+            .into_iter()
+            .filter(|sink| !sink.name.starts_with("combined_"))
+            .map(|sink| sink.name.as_str())
+            .collect();
+        if sink_names.len() < 2 {
+            self.messages
+                .push(StatusMessage::info("Select at least 2 sinks"));
+            return;
+        }
+
+        // Find the next available number for combined sink
+        let mut next_num = 1u32;
+        for (_, name) in &self.combined_modules {
+            if let Some(num_str) = name.strip_prefix("combined_")
+                && let Ok(num) = num_str.parse::<u32>()
+            {
+                next_num = next_num.max(num.saturating_add(1));
+            }
+        }
+        // Also check existing sinks in case module detection failed
+        for sink in self.sinks.items() {
+            if let Some(num_str) = sink.name.strip_prefix("combined_")
+                && let Ok(num) = num_str.parse::<u32>()
+            {
+                next_num = next_num.max(num.saturating_add(1));
+            }
+        }
+        let combined_name = format!("combined_{next_num}");
+
+        match backends::pactl::create_combined_sink(&combined_name, &sink_names) {
+            Ok(()) => {
+                self.messages.push(StatusMessage::success(format!(
+                    "Created: Combined {next_num}"
+                )));
+                self.sinks.clear_marks();
+                let _ = self.refresh();
+            }
+            Err(e) => self.messages.push(e),
+        }
+    }
+
+    fn remove_selected_combined(&mut self) {
+        if let Some((_, name)) = self.combined_modules.get(self.combined_selected) {
+            let question = format!("Remove {}?", combined_display_name(name));
+            self.confirm = self.confirmations.ask("remove", question);
+            if self.confirm.is_none() {
+                self.remove_combined();
+            }
+        }
+    }
+
+    fn remove_combined(&mut self) {
+        if let Some((module_id, name)) = self.combined_modules.get(self.combined_selected) {
+            let display_name = combined_display_name(name);
+            match backends::pactl::remove_combined_sink(*module_id) {
+                Ok(()) => {
+                    self.messages
+                        .push(StatusMessage::success(format!("Removed: {display_name}")));
+                    let _ = self.refresh();
+                }
+                Err(e) => self.messages.push(e),
+            }
+        }
+    }
+
+    fn combined_next(&mut self) {
+        if !self.combined_modules.is_empty() {
+            self.combined_selected = (self.combined_selected + 1) % self.combined_modules.len();
+        }
+    }
+
+    fn combined_previous(&mut self) {
+        if !self.combined_modules.is_empty() {
+            self.combined_selected = self
+                .combined_selected
+                .checked_sub(1)
+                .unwrap_or(self.combined_modules.len().saturating_sub(1));
+        }
+    }
+
+    fn start_search(&mut self, direction: SearchDirection) {
+        match self.current_tab() {
+            0 | 3 => self.sinks.start_search(direction),
+            1 => self.sources.start_search(direction),
+            2 => self.apps.start_search(direction),
+            _ => {}
+        }
+        self.search_mode = true;
+    }
+
+    fn search_edit(&mut self, action: Action) {
+        match self.current_tab() {
+            0 | 3 => self.sinks.edit_search(action),
+            1 => self.sources.edit_search(action),
+            2 => self.apps.edit_search(action),
+            _ => false,
+        };
+    }
+
+    fn focused_search(&self) -> SearchState<'_> {
+        match self.current_tab() {
+            1 => self.sources.search_state(),
+            2 => self.apps.search_state(),
+            _ => self.sinks.search_state(),
+        }
+    }
+
+    fn focused_match_info(&self) -> Option<(usize, usize)> {
+        match self.current_tab() {
+            0 | 3 => self.sinks.match_info(),
+            1 => self.sources.match_info(),
+            2 => self.apps.match_info(),
+            _ => None,
+        }
+    }
+
+    fn clear_search(&mut self) {
+        match self.current_tab() {
+            0 | 3 => self.sinks.clear_search(),
+            1 => self.sources.clear_search(),
+            2 => self.apps.clear_search(),
+            _ => {}
+        }
+        self.search_mode = false;
+    }
+
+    fn next_match(&mut self) {
+        match self.current_tab() {
+            0 | 3 => {
+                self.sinks.next_match();
+            }
+            1 => {
+                self.sources.next_match();
+            }
+            2 => {
+                self.apps.next_match();
+            }
+            _ => {}
+        }
+    }
+
+    fn prev_match(&mut self) {
+        match self.current_tab() {
+            0 | 3 => {
+                self.sinks.prev_match();
+            }
+            1 => {
+                self.sources.prev_match();
+            }
+            2 => {
+                self.apps.prev_match();
+            }
+            _ => {}
+        }
+    }
+
+    fn half_page_down(&mut self) {
+        match self.current_tab() {
+            0 | 3 => self.sinks.half_page_down(),
+            1 => self.sources.half_page_down(),
+            2 => self.apps.half_page_down(),
+            _ => {}
+        }
+    }
+
+    fn half_page_up(&mut self) {
+        match self.current_tab() {
+            0 | 3 => self.sinks.half_page_up(),
+            1 => self.sources.half_page_up(),
+            2 => self.apps.half_page_up(),
+            _ => {}
+        }
+    }
+
+    fn full_page_down(&mut self) {
+        match self.current_tab() {
+            0 | 3 => self.sinks.page_down(),
+            1 => self.sources.page_down(),
+            2 => self.apps.page_down(),
+            _ => {}
+        }
+    }
+
+    fn full_page_up(&mut self) {
+        match self.current_tab() {
+            0 | 3 => self.sinks.page_up(),
+            1 => self.sources.page_up(),
+            2 => self.apps.page_up(),
+            _ => {}
+        }
+    }
+
+    fn render_combine_tab(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let content_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Percentage(60), // Sinks
+                Constraint::Percentage(40), // Combined modules
+            ])
+            .split(area);
+
+        // Sinks with selection indicators (exclude combined sinks)
+        let filtered_sinks: Vec<(usize, &Sink)> = self
+            .sinks
+            .items()
+            .iter()
+            .enumerate()
+            .filter(|(_, sink)| !sink.name.starts_with("combined_"))
+            .collect();
+
+        let items: Vec<ListItem> = filtered_sinks
+            .iter()
+            .map(|(original_idx, sink)| {
+                let selected = self.sinks.is_marked(*original_idx);
+                let checkbox = if selected { "[x]" } else { "[ ]" };
+                let text = format!("{} {}", checkbox, sink.description);
+                let style = if Some(*original_idx) == self.sinks.selected_index()
+                    && !self.combine_right_focus
+                {
+                    Style::default().add_modifier(Modifier::REVERSED)
+                } else if selected {
+                    self.theme.highlight()
+                } else {
+                    Style::default()
+                };
+                ListItem::new(text).style(style)
+            })
+            .collect();
+
+        let left_border = if self.combine_right_focus {
+            self.theme.border()
+        } else {
+            self.theme.border_focused()
+        };
+        let block = Block::default()
+            .title(" Select Sinks ")
+            .title_style(self.theme.title())
+            .borders(Borders::ALL)
+            .border_type(Theme::BORDER_TYPE)
+            .border_style(left_border);
+        let list = List::new(items).block(block);
+        frame.render_widget(list, content_chunks[0]);
+
+        // Combined modules list with selection
+        let combined_items: Vec<ListItem> = self
+            .combined_modules
+            .iter()
+            .enumerate()
+            .map(|(i, (_, name))| {
+                let style = if i == self.combined_selected && self.combine_right_focus {
+                    Style::default().add_modifier(Modifier::REVERSED)
+                } else {
+                    Style::default()
+                };
+                let display_name = combined_display_name(name);
+                ListItem::new(format!("  {display_name}")).style(style)
+            })
+            .collect();
+
+        let right_border = if self.combine_right_focus {
+            self.theme.border_focused()
+        } else {
+            self.theme.border()
+        };
+        let combined_block = Block::default()
+            .title(" Combined Sinks ")
+            .title_style(self.theme.title())
+            .borders(Borders::ALL)
+            .border_type(Theme::BORDER_TYPE)
+            .border_style(right_border);
+
+        if combined_items.is_empty() {
+            let msg = Paragraph::new(" No combined sinks").block(combined_block);
+            frame.render_widget(msg, content_chunks[1]);
+        } else {
+            let list = List::new(combined_items).block(combined_block);
+            frame.render_widget(list, content_chunks[1]);
+        }
+    }
+
+    fn search_popup_title(&self) -> &'static str {
+        match self.current_tab() {
+            0 => " Search Output ",
+            1 => " Search Input ",
+            2 => " Search Apps ",
+            _ => " Search Sinks ",
+        }
+    }
+
+    fn yank_selected(&mut self) {
+        let text = match self.current_tab() {
+            0 | 3 => self.sinks.selected().map(|s| s.description.clone()),
+            1 => self.sources.selected().map(|s| s.description.clone()),
+            2 => self.apps.selected().map(|s| s.app_name.clone()),
+            _ => None,
+        };
+
+        if let Some(text) = text {
+            match yank(&text) {
+                Ok(backend) => self.messages.push(StatusMessage::success(format!(
+                    "Yanked with {backend}: {text}"
+                ))),
+                Err(e) => self.messages.push(e),
+            }
+        } else {
+            self.messages.push(StatusMessage::info("Nothing to yank"));
+        }
+    }
+
+    fn handle_search_action(&mut self, action: Action) {
+        match action {
+            Action::Back => {
+                if self.focused_search().query.is_empty() {
+                    self.clear_search();
+                } else {
+                    self.search_edit(action);
+                }
+            }
+            Action::Quit => self.clear_search(),
+            Action::Select => {
+                self.search_mode = false;
+                if let Some((cur, total)) = self.focused_match_info() {
+                    self.messages
+                        .hint(StatusMessage::info(format!("Match {cur}/{total}")));
+                }
+            }
+            action => self.search_edit(action),
+        }
+    }
+
+    /// Move to next non-combined sink (for Combine tab navigation)
+    fn next_non_combined(&mut self) {
+        let items = self.sinks.items();
+        if items.is_empty() {
+            return;
+        }
+        let start = self.sinks.selected_index().map_or(0, |i| i + 1);
+        for i in 0..items.len() {
+            let idx = (start + i) % items.len();
+            if !items[idx].name.starts_with("combined_") {
+                self.sinks.select(idx);
+                return;
+            }
+        }
+    }
+
+    /// Move to previous non-combined sink (for Combine tab navigation)
+    fn prev_non_combined(&mut self) {
+        let items = self.sinks.items();
+        if items.is_empty() {
+            return;
+        }
+        let start = self
+            .sinks
+            .selected_index()
+            .unwrap_or(0)
+            .checked_sub(1)
+            .unwrap_or(items.len() - 1);
+        for i in 0..items.len() {
+            let idx = (start + items.len() - i) % items.len();
+            if !items[idx].name.starts_with("combined_") {
+                self.sinks.select(idx);
+                return;
+            }
+        }
+    }
+
+    fn handle_navigation(&mut self, action: Action) {
+        let ct = self.combine_tab();
+        match action {
+            Action::Down => match self.current_tab() {
+                1 => self.sources.next(),
+                2 => self.apps.next(),
+                t if t == ct && self.combine_right_focus => self.combined_next(),
+                t if t == ct => self.next_non_combined(),
+                0 => self.sinks.next(),
+                _ => {}
+            },
+            Action::Up => match self.current_tab() {
+                1 => self.sources.previous(),
+                2 => self.apps.previous(),
+                t if t == ct && self.combine_right_focus => self.combined_previous(),
+                t if t == ct => self.prev_non_combined(),
+                0 => self.sinks.previous(),
+                _ => {}
+            },
+            Action::Left => {
+                if self.current_tab() == ct && self.combine_right_focus {
+                    self.combine_right_focus = false;
+                    self.messages
+                        .hint(StatusMessage::info("Focus: Select Sinks"));
+                } else {
+                    self.tabs.previous();
+                }
+            }
+            Action::Right => {
+                if self.current_tab() == ct && !self.combine_right_focus {
+                    self.combine_right_focus = true;
+                    self.messages
+                        .hint(StatusMessage::info("Focus: Combined Sinks"));
+                } else {
+                    self.tabs.next();
+                }
+            }
+            Action::Top => match self.current_tab() {
+                0 | 3 => self.sinks.first(),
+                1 => self.sources.first(),
+                2 => self.apps.first(),
+                _ => {}
+            },
+            Action::Bottom => match self.current_tab() {
+                0 | 3 => self.sinks.last(),
+                1 => self.sources.last(),
+                2 => self.apps.last(),
+                _ => {}
+            },
+            Action::PageUp => self.half_page_up(),
+            Action::PageDown => self.half_page_down(),
+            Action::FullPageUp => self.full_page_up(),
+            Action::FullPageDown => self.full_page_down(),
+            _ => {}
+        }
+    }
+
+    fn handle_combine_action(&mut self, action: Action) {
+        match action {
+            Action::Char('\t') => {
+                self.combine_right_focus = !self.combine_right_focus;
+                self.messages
+                    .hint(StatusMessage::info(if self.combine_right_focus {
+                        "Focus: Combined Sinks"
+                    } else {
+                        "Focus: Select Sinks"
+                    }));
+            }
+            Action::Char('c') => self.create_combined(),
+            Action::Delete => {
+                if self.combine_right_focus && !self.combined_modules.is_empty() {
+                    self.remove_selected_combined();
+                } else if !self.combine_right_focus {
+                    self.messages.push(StatusMessage::info(
+                        "Press l to focus Combined Sinks, then d to delete",
+                    ));
+                }
+            }
+            // Pass navigation actions through
+            action => self.handle_navigation(action),
+        }
+    }
+}
+
+impl App for PwTui {
+    fn title(&self) -> &'static str {
+        "vol-tui"
+    }
+
+    fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    fn input_mode(&self) -> bool {
+        self.search_mode || self.jump_mode.is_some() || self.command.is_active()
+    }
+
+    fn handle_action_count(&mut self, action: Action, count: usize) -> AppResult<bool> {
+        if self.show_help
+            || self.details.is_some()
+            || self.history.is_some()
+            || self.confirm.is_some()
+        {
+            return self.handle_action(action);
+        }
+        match action {
+            // `5G` / `5gg` go to line 5
+            Action::Top | Action::Bottom => match self.current_tab() {
+                0 | 3 => self.sinks.goto_line(count),
+                1 => self.sources.goto_line(count),
+                2 => self.apps.goto_line(count),
+                _ => {}
+            },
+            // One backend call for `3+` instead of three
+            Action::VolumeUp | Action::VolumeDown => {
+                let steps = i8::try_from(count.min(20)).unwrap_or(20);
+                let delta = if action == Action::VolumeUp {
+                    steps
+                } else {
+                    -steps
+                };
+                self.set_volume(delta)?;
+                self.refresh()?;
+            }
+            // `3s` sorts by the third column
+            Action::Sort => self.sort(Some(count)),
+            _ => return repeat_action(self, action, count),
+        }
+        Ok(true)
+    }
+
+    fn mouse_capture(&self) -> bool {
+        true
+    }
+
+    fn handle_mouse(&mut self, event: MouseEvent) -> AppResult<bool> {
+        if self.show_help
+            || self.details.is_some()
+            || self.history.is_some()
+            || self.confirm.is_some()
+            || self.input_mode()
+            || self.tabs.handle_mouse(&event)
+        {
+            return Ok(true);
+        }
+        let cell = match self.current_tab() {
+            0 => self.sinks.cell_at(event.column, event.row),
+            1 => self.sources.cell_at(event.column, event.row),
+            2 => self.apps.cell_at(event.column, event.row),
+            _ => return Ok(true),
+        };
+        let delta = match event.kind {
+            MouseEventKind::ScrollUp => 1,
+            MouseEventKind::ScrollDown => -1,
+            _ => 0,
+        };
+        // Scrolling over a volume bar adjusts that row
+        if delta != 0
+            && let Some((index, column)) = cell
+            && VOLUME_COLUMNS.contains(&column)
+        {
+            match self.current_tab() {
+                0 => self.sinks.select(index),
+                1 => self.sources.select(index),
+                _ => self.apps.select(index),
+            }
+            self.set_volume(delta)?;
+            self.refresh()?;
+            return Ok(true);
+        }
+        match self.current_tab() {
+            0 => self.sinks.handle_mouse(&event),
+            1 => self.sources.handle_mouse(&event),
+            _ => self.apps.handle_mouse(&event),
+        };
+        Ok(true)
+    }
+
+    fn start(&mut self, events: &Events) {
+        self.events = events.clone();
+        self.tasks.connect(events);
+        self.subscribe();
+    }
+
+    fn report_error(&mut self, error: Box<dyn std::error::Error>) -> AppResult<()> {
+        self.messages.report(error)
+    }
+
+    fn output(&self) -> Option<String> {
+        self.picked.clone()
+    }
+
+    fn execute(&mut self, line: &str) -> AppResult<()> {
+        match self.command.parse(line) {
+            CommandEvent::Run(invocation) => self.run_command(&invocation),
+            CommandEvent::Error(e) => Err(e.into()),
+            CommandEvent::Editing | CommandEvent::Cancelled => Ok(()),
+        }
+    }
+
+    fn save_session(&self) -> Option<Value> {
+        Some(json!({
+            "tab": self.current_tab(),
+            "sinks": self.sinks.save_session(),
+            "sources": self.sources.save_session(),
+            "apps": self.apps.save_session(),
+        }))
+    }
+
+    fn restore_session(&mut self, saved: &Value) {
+        if let Some(tab) = saved["tab"].as_u64().and_then(|t| usize::try_from(t).ok()) {
+            self.tabs.select(tab);
+        }
+        self.sinks.restore_session(&saved["sinks"]);
+        self.sources.restore_session(&saved["sources"]);
+        self.apps.restore_session(&saved["apps"]);
+    }
+
+    fn summary(&self) -> Option<String> {
+        let sink = self.sinks.items().iter().find(|s| s.is_default)?;
+        Some(if sink.muted {
+            "muted".to_string()
+        } else {
+            format!("{}%", sink.volume)
+        })
+    }
+
+    fn tick(&mut self) -> AppResult<()> {
+        for result in self.tasks.finished() {
+            self.finish_task(result);
+        }
+        // Skip blocking refresh during popups/input to keep UI responsive
+        if self.refresh_paused() {
+            return Ok(());
+        }
+        let now = Instant::now();
+        let polled = self.changes.is_none() && self.refresh_timer.due(now);
+        // Moving a stream sends a burst of events, refreshed once at its end
+        if self.take_changes() {
+            self.change_refresh.get_or_insert(now + CHANGE_DELAY);
+        }
+        let changed = self.change_refresh.is_some_and(|at| at <= now);
+        if changed || polled {
+            self.change_refresh = None;
+            self.refresh()?;
+        }
+        Ok(())
+    }
+
+    fn next_tick(&self) -> Option<Instant> {
+        let refresh = (self.changes.is_none() && !self.refresh_paused())
+            .then(|| self.refresh_timer.deadline());
+        let changed = self.change_refresh.filter(|_| !self.refresh_paused());
+        [
+            refresh,
+            changed,
+            self.tasks.next_tick(),
+            self.messages.next_expiry(),
+        ]
+        .into_iter()
+        .flatten()
+        .min()
+    }
+
+    fn handle_action(&mut self, action: Action) -> AppResult<bool> {
+        // Jump mode - waiting for character
+        if let Some(forward) = self.jump_mode {
+            match action {
+                Action::Char(c) => {
+                    let found = match self.current_tab() {
+                        0 | 3 => self.sinks.jump_to_char(c, forward),
+                        1 => self.sources.jump_to_char(c, forward),
+                        2 => self.apps.jump_to_char(c, forward),
+                        _ => false,
+                    };
+                    self.messages.hint(StatusMessage::info(if found {
+                        format!("Jumped to '{c}'")
+                    } else {
+                        format!("No match for '{c}'")
+                    }));
+                }
+                _ => {
+                    self.messages.hint(StatusMessage::info("Jump cancelled"));
+                }
+            }
+            self.jump_mode = None;
+            return Ok(true);
+        }
+
+        if self.search_mode {
+            self.handle_search_action(action);
+            return Ok(true);
+        }
+
+        if self.command.is_active() {
+            match self.command.handle_action(action) {
+                CommandEvent::Run(invocation) => self.run_command(&invocation)?,
+                CommandEvent::Error(e) => self.messages.push(StatusMessage::error(e)),
+                CommandEvent::Editing | CommandEvent::Cancelled => {}
+            }
+            return Ok(true);
+        }
+
+        if self.show_help {
+            if matches!(action, Action::Help | Action::Back | Action::Quit) {
+                self.show_help = false;
+            }
+            return Ok(true);
+        }
+
+        if let Some(details) = &mut self.details {
+            if !details.handle_action(action) {
+                self.details = None;
+            }
+            return Ok(true);
+        }
+
+        if let Some(history) = &mut self.history {
+            if !history.handle_action(action) {
+                self.history = None;
+            }
+            return Ok(true);
+        }
+
+        if let Some(confirm) = &mut self.confirm {
+            match confirm.handle_action(action) {
+                ConfirmEvent::Pending => {}
+                ConfirmEvent::Cancelled => self.confirm = None,
+                ConfirmEvent::Confirmed => {
+                    self.confirmations.confirmed(confirm);
+                    let operation = self.confirm.take().map(|c| c.operation().to_string());
+                    match operation.as_deref() {
+                        Some("remove") => self.remove_combined(),
+                        Some("restart") => self.start_restart(),
+                        _ => {}
+                    }
+                }
+            }
+            return Ok(true);
+        }
+
+        match action {
+            // Esc first shows the whole list again
+            Action::Back if self.focused_search().is_filtered() => self.clear_search(),
+            Action::Back => self.unmark(),
+            Action::Quit => return Ok(false),
+            Action::Help => self.show_help = true,
+            Action::Details => {
+                self.details = self
+                    .messages
+                    .last_detail()
+                    .and_then(DetailPopup::for_status)
+            }
+            Action::Messages => self.history = Some(MessagePopup::new(&self.messages)),
+            Action::Refresh => self.refresh()?,
+            Action::Select => match self.current_tab() {
+                0 | 1 if self.picker => {
+                    self.picked = self.selected_name();
+                    return Ok(false);
+                }
+                0 | 1 => {
+                    self.set_default();
+                    self.refresh()?;
+                }
+                3 => self.toggle_combine_selection(),
+                _ => {}
+            },
+            Action::VolumeUp => {
+                self.set_volume(1)?;
+                self.refresh()?;
+            }
+            Action::VolumeDown => {
+                self.set_volume(-1)?;
+                self.refresh()?;
+            }
+            Action::Mute => {
+                self.toggle_mute()?;
+                self.refresh()?;
+            }
+            Action::Search => self.start_search(SearchDirection::Forward),
+            Action::Command => self.command.open(),
+            Action::SearchNext => self.next_match(),
+            Action::SearchPrev => self.prev_match(),
+            Action::Yank => self.yank_selected(),
+            Action::Mark => self.toggle_mark(),
+            Action::Visual => self.toggle_visual(),
+            Action::Sort => self.sort(None),
+            Action::JumpTo => {
+                self.jump_mode = Some(true);
+                self.messages.hint(StatusMessage::info("Jump to: "));
+            }
+            Action::JumpBack => {
+                self.jump_mode = Some(false);
+                self.messages.hint(StatusMessage::info("Jump back to: "));
+            }
+            Action::Char('R') if self.backend == AudioBackend::PulseAudio => {
+                self.restart_pipewire();
+            }
+            action if self.current_tab() == 3 => self.handle_combine_action(action),
+            action => self.handle_navigation(action),
+        }
+        Ok(true)
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1), // Tabs
+                Constraint::Min(5),    // Content
+                Constraint::Length(3), // Status box
+            ])
+            .split(frame.area());
+
+        // Tabs
+        self.tabs.render(frame, chunks[0], &self.theme);
+
+        match self.current_tab() {
+            0 => self.sinks.render(frame, chunks[1], "", &self.theme, true),
+            1 => self.sources.render(frame, chunks[1], "", &self.theme, true),
+            2 => self.apps.render(frame, chunks[1], "", &self.theme, true),
+            3 => self.render_combine_tab(frame, chunks[1]),
+            _ => {}
+        }
+
+        // Status box
+        let status_block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(Theme::BORDER_TYPE)
+            .border_style(self.theme.border());
+        let status = self.tasks.indicator().map_or_else(
+            || self.messages.to_line(&self.theme),
+            |progress| StatusMessage::info(progress.trim_start()).to_line(&self.theme),
+        );
+        let command_area = status_block.inner(chunks[2]);
+        let status = Paragraph::new(status).block(status_block);
+        frame.render_widget(status, chunks[2]);
+        if self.command.is_active() {
+            self.command.render(frame, command_area, &self.theme);
+        }
+
+        if self.show_help {
+            let bindings = if self.current_tab() == 3 {
+                self.keymap.help(&[
+                    (&[Action::Down, Action::Up], "Navigate"),
+                    (&[Action::Left, Action::Right], "Switch panel/tab"),
+                    (&[Action::Top, Action::Bottom], "Top/Bottom"),
+                    (&[Action::PageUp, Action::PageDown], "Half page"),
+                    (&[Action::Search], "Search"),
+                    (&[Action::SearchNext, Action::SearchPrev], "Next/Prev match"),
+                    (&[Action::Yank], "Yank (copy)"),
+                    (&[Action::Select, Action::Mark], "Toggle selection"),
+                    (&[Action::Visual], "Select range"),
+                    (&[Action::Char('c')], "Create combined"),
+                    (&[Action::Delete], "Delete combined"),
+                    (&[Action::Char('R')], "Restart PipeWire"),
+                    (&[Action::Command], "Command line"),
+                    (&[Action::Details], "Error details"),
+                    (&[Action::Messages], "Message history"),
+                    (&[Action::Log], "Debug log"),
+                    (&[Action::Quit], "Quit"),
+                ])
+            } else {
+                self.keymap.help(&[
+                    (&[Action::Down, Action::Up], "Navigate"),
+                    (&[Action::Left, Action::Right], "Switch tab"),
+                    (&[Action::Top, Action::Bottom], "Top/Bottom"),
+                    (&[Action::PageUp, Action::PageDown], "Half page"),
+                    (&[Action::Search], "Search"),
+                    (&[Action::SearchNext, Action::SearchPrev], "Next/Prev match"),
+                    (&[Action::Yank], "Yank (copy)"),
+                    (&[Action::JumpTo, Action::JumpBack], "Jump to char"),
+                    (&[Action::VolumeUp, Action::VolumeDown], "Volume"),
+                    (&[Action::Mute], "Mute"),
+                    (&[Action::Mark, Action::Visual], "Mark item/range"),
+                    (&[Action::Sort], "Sort by column"),
+                    (&[Action::Char('R')], "Restart PipeWire"),
+                    (&[Action::Command], "Command line"),
+                    (&[Action::Details], "Error details"),
+                    (&[Action::Messages], "Message history"),
+                    (&[Action::Log], "Debug log"),
+                    (&[Action::Quit], "Quit"),
+                ])
+            };
+            HelpPopup::render(frame, &bindings, &self.theme);
+        }
+
+        if let Some(details) = &self.details {
+            details.render(frame, &self.theme);
+        }
+
+        if let Some(history) = &self.history {
+            history.render(frame, &self.theme);
+        }
+
+        if let Some(confirm) = &self.confirm {
+            confirm.render(frame, &self.theme);
+        }
+
+        if self.search_mode {
+            SearchPopup::render(
+                frame,
+                self.search_popup_title(),
+                self.focused_search(),
+                &self.theme,
+            );
+        }
+    }
+}
+
+/// Commands for the `:` command line.
+fn commands(backend: AudioBackend) -> Vec<CommandSpec> {
+    let mut commands = vec![
+        CommandSpec::new("volume", "<0-100>", "Set volume of the selection"),
+        CommandSpec::new("mute", "", "Toggle mute"),
+        CommandSpec::new("set-default", "", "Make the selection the default"),
+        CommandSpec::new("refresh", "", "Reload devices"),
+    ];
+    if backend == AudioBackend::PulseAudio {
+        commands.push(CommandSpec::new("restart-pipewire", "", "Restart PipeWire"));
+    }
+    commands
+}
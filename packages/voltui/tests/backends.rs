@@ -0,0 +1,125 @@
+use std::sync::Arc;
+
+use tuigreat::runner::{self, FakeRunner, Reply};
+use tuigreat::{Error, StatusLevel};
+use voltui::audio::{AudioBackend, detect_audio_backend};
+use voltui::backends::{self, pactl};
+
+const PACTL: AudioBackend = AudioBackend::PulseAudio;
+
+/// Replay the recorded `pactl` session on this test's thread.
+fn fake_pactl() -> Arc<FakeRunner> {
+    let fake = Arc::new(
+        FakeRunner::load(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/pactl.txt"
+        ))
+        .unwrap(),
+    );
+    runner::set(fake.clone());
+    fake
+}
+
+#[test]
+fn test_detect_backend() {
+    fake_pactl();
+    assert_eq!(detect_audio_backend(), Some(PACTL));
+}
+
+#[test]
+fn test_sinks_from_json() {
+    fake_pactl();
+    let sinks = backends::get_sinks(PACTL).unwrap();
+    assert_eq!(sinks.len(), 2);
+    assert_eq!(sinks[0].description, "G435 Wireless Gaming Headset");
+    assert_eq!(sinks[0].volume, 45);
+    assert!(sinks[0].is_default);
+    assert!(!sinks[0].muted);
+    // Over-amplified volume is capped
+    assert_eq!(sinks[1].volume, 100);
+    assert!(sinks[1].muted);
+    assert!(!sinks[1].is_default);
+}
+
+#[test]
+fn test_sources_skip_monitors() {
+    fake_pactl();
+    let sources = backends::get_sources(PACTL).unwrap();
+    assert_eq!(sources.len(), 1);
+    assert_eq!(sources[0].volume, 60);
+    assert!(sources[0].is_default);
+}
+
+#[test]
+fn test_app_streams_fall_back_to_media_name() {
+    fake_pactl();
+    let streams = backends::get_app_streams(PACTL).unwrap();
+    let names: Vec<&str> = streams.iter().map(|s| s.app_name.as_str()).collect();
+    assert_eq!(names, ["Firefox", "Playback"]);
+    assert_eq!(streams[1].index, 118);
+}
+
+#[test]
+fn test_combined_modules() {
+    fake_pactl();
+    let modules = pactl::get_combined_modules().unwrap();
+    assert_eq!(modules, [(536_870_920, "combined_living".to_string())]);
+}
+
+#[test]
+fn test_volume_commands() {
+    let fake = fake_pactl();
+    backends::adjust_sink_volume(PACTL, "headset", -2).unwrap();
+    backends::set_sink_volume(PACTL, "headset", 30).unwrap();
+    assert!(fake.ran(&["pactl", "set-sink-volume", "headset", "-10%"]));
+    assert!(fake.ran(&["pactl", "set-sink-volume", "headset", "30%"]));
+}
+
+#[test]
+fn test_error_from_stderr() {
+    fake_pactl();
+    let error = backends::set_default_sink(PACTL, "No Such Sink").unwrap_err();
+    assert_eq!(error.to_string(), "Failure: No such entity");
+    assert_eq!(error.level(), StatusLevel::Error);
+    assert_eq!(
+        error.detail().as_deref(),
+        Some("$ pactl set-default-sink 'No Such Sink'\nFailure: No such entity")
+    );
+}
+
+#[test]
+fn test_replies_change_mid_test() {
+    let fake = fake_pactl();
+    fake.add(
+        Reply::new(&["pactl", "get-default-sink"])
+            .stdout("alsa_output.pci-0000_00_1f.3.analog-stereo\n"),
+    );
+    let sinks = backends::get_sinks(PACTL).unwrap();
+    assert!(!sinks[0].is_default);
+    assert!(sinks[1].is_default);
+}
+
+#[test]
+fn test_missing_reply_is_an_error() {
+    fake_pactl();
+    assert_eq!(
+        backends::toggle_sink_mute(PACTL, "headset"),
+        Err(Error::MissingBinary("pactl".to_string()))
+    );
+}
+
+#[test]
+fn test_bad_json_is_a_parse_error() {
+    let fake = fake_pactl();
+    fake.add(Reply::new(&["pactl", "--format=json", "list", "sinks"]).stdout("[{"));
+    assert!(matches!(
+        backends::get_sinks(PACTL),
+        Err(Error::Parse { what, .. }) if what == "pactl sinks"
+    ));
+}
+
+#[test]
+fn test_alsa_has_no_app_volume() {
+    let error = backends::set_app_volume(AudioBackend::Alsa, 3, 40).unwrap_err();
+    assert_eq!(error.to_string(), "per-app volume needs PulseAudio");
+}
@@ -0,0 +1,70 @@
+use std::sync::Arc;
+
+use serde_json::json;
+use tuigreat::cli::{Call, format_value};
+use tuigreat::runner::{self, FakeRunner};
+use voltui::audio::AudioBackend;
+use voltui::cli::{self, CLI};
+
+fn call(args: &[&str]) -> Call {
+    let args: Vec<String> = args.iter().map(ToString::to_string).collect();
+    CLI.parse(&args).unwrap().unwrap()
+}
+
+fn fake_pactl() -> Arc<FakeRunner> {
+    let fake = Arc::new(
+        FakeRunner::load(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/pactl.txt"
+        ))
+        .unwrap(),
+    );
+    runner::set(fake.clone());
+    fake
+}
+
+#[test]
+fn test_sink_list() {
+    fake_pactl();
+    let list = call(&["voltui", "sink", "list", "--json"]);
+    assert!(list.json);
+    let sinks = cli::run(AudioBackend::PulseAudio, &list).unwrap();
+    assert_eq!(sinks.as_array().unwrap().len(), 2);
+    assert_eq!(
+        sinks[0],
+        json!({
+            "name": "alsa_output.usb-Logitech_G435-00.analog-stereo",
+            "description": "G435 Wireless Gaming Headset",
+            "volume": 45,
+            "muted": false,
+            "default": true,
+        })
+    );
+    assert_eq!(
+        format_value(&sinks, false).lines().nth(1),
+        Some(
+            "alsa_output.pci-0000_00_1f.3.analog-stereo\tBuilt-in Audio Analog Stereo\t100\ttrue\tfalse"
+        )
+    );
+}
+
+#[test]
+fn test_sink_volume() {
+    let fake = fake_pactl();
+    let volume = call(&["voltui", "sink", "volume", "+5"]);
+    let sink = cli::run(AudioBackend::PulseAudio, &volume).unwrap();
+    assert_eq!(sink["volume"], 50);
+    assert!(fake.ran(&[
+        "pactl",
+        "set-sink-volume",
+        "alsa_output.usb-Logitech_G435-00.analog-stereo",
+        "50%"
+    ]));
+
+    let too_loud = call(&["voltui", "sink", "volume", "150"]);
+    let error = cli::run(AudioBackend::PulseAudio, &too_loud).unwrap_err();
+    assert_eq!(error.to_string(), "usage: sink volume <0-100|+N|-N> [name]");
+    let missing = call(&["voltui", "sink", "get", "nope"]);
+    let error = cli::run(AudioBackend::PulseAudio, &missing).unwrap_err();
+    assert_eq!(error.to_string(), "No output called nope");
+}
@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use tuigreat::runner::{self, FakeRunner, Reply};
+use tuigreat::testing::TestHarness;
+use tuigreat::{Action, App};
+use voltui::PwTui;
+use voltui::audio::AudioBackend;
+
+/// The app on the recorded `pactl` session.
+fn app() -> (TestHarness<PwTui>, Arc<FakeRunner>) {
+    let fake = Arc::new(
+        FakeRunner::load(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/pactl.txt"
+        ))
+        .unwrap(),
+    );
+    runner::set(fake.clone());
+    let app = PwTui::new(AudioBackend::PulseAudio).unwrap();
+    (TestHarness::new(app, 80, 12), fake)
+}
+
+#[test]
+fn test_devices_from_pactl() {
+    let (mut harness, _) = app();
+    let screen = harness.screen();
+    assert!(screen.contains(">  *  [====      ]  45% G435 Wireless Gaming Headset"));
+    assert!(screen.contains("    M [==========] 100% Built-in Audio Analog Stereo"));
+    assert_eq!(harness.app().summary(), Some("45%".to_string()));
+
+    // Monitors are not inputs
+    harness.press("l");
+    assert!(!harness.screen().contains("Monitor of"));
+    harness.press("l");
+    assert!(harness.screen().contains(">     [========  ]  80% Firefox"));
+    harness.press("l");
+    assert!(harness.screen().contains("Combined living"));
+}
+
+#[test]
+fn test_volume_and_mute() {
+    let (mut harness, fake) = app();
+    harness.action(Action::VolumeUp);
+    assert!(fake.ran(&[
+        "pactl",
+        "set-sink-volume",
+        "alsa_output.usb-Logitech_G435-00.analog-stereo",
+        "+5%",
+    ]));
+    assert!(harness.screen().contains("Volume: 50%"));
+
+    fake.add(Reply::new(&["pactl", "set-sink-mute", "*", "toggle"]));
+    harness.press("j").action(Action::Mute);
+    assert!(fake.ran(&[
+        "pactl",
+        "set-sink-mute",
+        "alsa_output.pci-0000_00_1f.3.analog-stereo",
+        "toggle",
+    ]));
+    assert!(!harness.screen().contains("not found"));
+}
+
+#[test]
+fn test_snapshots() {
+    let (mut harness, _) = app();
+    harness.assert_snapshot("output");
+    harness.press("l l");
+    harness.assert_snapshot("apps");
+    harness.press("l");
+    harness.assert_snapshot("combine");
+}